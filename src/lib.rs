@@ -9,6 +9,12 @@
 /// Part1: Metamodel
 pub mod part_1;
 
+/// Part2: Application Programming Interfaces
+pub mod part_2;
+
+/// Part4: Security
+pub mod part_4;
+
 /// Utility functions like validating text to specific formats and deserializers to specific needs,
 /// like text with defined constraints.
 pub mod utilities;
@@ -0,0 +1,702 @@
+//! Interpretation of the IDTA Asset Interfaces Description (AID) submodel.
+//!
+//! An AID submodel describes the interfaces of an asset following the W3C Web of Things Thing
+//! Description: every interface collection has `EndpointMetadata` with the `base` address and an
+//! `InterfaceMetadata` with the property, action and event affordances and their `forms`.
+//! [`AssetInterfacesDescription`] reads these collections by their idShorts as fixed by the
+//! template into typed descriptors.
+//!
+//! ```
+//! use aas::part_1::v3_1::aid::{AffordanceType, AssetInterfacesDescription, Protocol};
+//! use aas::part_1::v3_1::core::Submodel;
+//!
+//! let submodel: Submodel = serde_json::from_str(r#"{
+//!     "id": "https://example.com/aid/1",
+//!     "submodelElements": [{
+//!         "modelType": "SubmodelElementCollection", "idShort": "InterfaceHTTP",
+//!         "value": [
+//!             {"modelType": "SubmodelElementCollection", "idShort": "EndpointMetadata", "value": [
+//!                 {"modelType": "Property", "idShort": "base", "valueType": "xs:anyURI",
+//!                  "value": "http://192.168.1.20"}
+//!             ]},
+//!             {"modelType": "SubmodelElementCollection", "idShort": "InterfaceMetadata", "value": [
+//!                 {"modelType": "SubmodelElementCollection", "idShort": "properties", "value": [
+//!                     {"modelType": "SubmodelElementCollection", "idShort": "temperature", "value": [
+//!                         {"modelType": "Property", "idShort": "type", "valueType": "xs:string",
+//!                          "value": "number"},
+//!                         {"modelType": "SubmodelElementCollection", "idShort": "forms", "value": [
+//!                             {"modelType": "Property", "idShort": "href", "valueType": "xs:string",
+//!                              "value": "/temperature"}
+//!                         ]}
+//!                     ]}
+//!                 ]}
+//!             ]}
+//!         ]
+//!     }]
+//! }"#).unwrap();
+//!
+//! let aid = AssetInterfacesDescription::try_from(&submodel).unwrap();
+//! let interface = &aid.interfaces[0];
+//! assert_eq!(interface.protocol, Protocol::Http);
+//!
+//! let temperature = interface.property("temperature").unwrap();
+//! assert_eq!(temperature.data_type, Some(AffordanceType::Number));
+//! assert_eq!(
+//!     temperature.forms[0].target(&interface.endpoint),
+//!     "http://192.168.1.20/temperature"
+//! );
+//! ```
+
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::primitives::DataXsdError;
+use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+use crate::part_1::v3_1::submodel_elements::{Range, SubmodelElement};
+use std::collections::BTreeMap;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+pub const SUBMODEL_SEMANTIC_ID: &str =
+    "https://admin-shell.io/idta/AssetInterfacesDescription/1/0/Submodel";
+pub const INTERFACE_SEMANTIC_ID: &str =
+    "https://admin-shell.io/idta/AssetInterfacesDescription/1/0/Interface";
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum AidError {
+    #[error("The submodel {0} is not an Asset Interfaces Description")]
+    NotAid(String),
+
+    #[error("Missing {0}")]
+    MissingElement(String),
+
+    #[error("{path} has to be a {expected}")]
+    InvalidElement { path: String, expected: String },
+}
+
+/// Protocol of an interface, given by its supplemental semantic ids or the scheme of `base`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Protocol {
+    Http,
+    Modbus,
+    Mqtt,
+    Other(String),
+}
+
+/// Data schema type of an affordance, i.e. the `type` of a property.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+#[strum(serialize_all = "camelCase")]
+pub enum AffordanceType {
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Object,
+    Array,
+    Null,
+}
+
+impl AffordanceType {
+    /// The value type of a `Property` holding values of this type.
+    /// Objects, arrays and null have no atomic value type.
+    pub fn value_type(self) -> Option<DataXsd> {
+        match self {
+            AffordanceType::Boolean => DataXsd::from_value_type("xs:boolean"),
+            AffordanceType::Integer => DataXsd::from_value_type("xs:integer"),
+            AffordanceType::Number => DataXsd::from_value_type("xs:double"),
+            AffordanceType::String => DataXsd::from_value_type("xs:string"),
+            AffordanceType::Object | AffordanceType::Array | AffordanceType::Null => None,
+        }
+    }
+}
+
+/// Typed view on an AID submodel.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AssetInterfacesDescription {
+    pub interfaces: Vec<Interface>,
+}
+
+impl AssetInterfacesDescription {
+    pub fn interface(&self, id_short: &str) -> Option<&Interface> {
+        self.interfaces
+            .iter()
+            .find(|interface| interface.id_short == id_short)
+    }
+
+    pub fn interfaces_for<'a>(
+        &'a self,
+        protocol: &'a Protocol,
+    ) -> impl Iterator<Item = &'a Interface> + 'a {
+        self.interfaces
+            .iter()
+            .filter(move |interface| &interface.protocol == protocol)
+    }
+}
+
+impl TryFrom<&Submodel> for AssetInterfacesDescription {
+    type Error = AidError;
+
+    /// Every collection with the interface semantic id or an `EndpointMetadata` is an interface.
+    fn try_from(submodel: &Submodel) -> Result<Self, Self::Error> {
+        if let Some(semantic_id) = &submodel.semantics.semantic_id
+            && !semantic_id.matches_identifier(SUBMODEL_SEMANTIC_ID)
+        {
+            return Err(AidError::NotAid(submodel.id().to_string()));
+        }
+
+        let interfaces = submodel
+            .submodel_elements
+            .iter()
+            .flatten()
+            .filter(|element| {
+                matches!(element, SubmodelElement::SubmodelElementCollection(_))
+                    && (has_semantic_id(element, INTERFACE_SEMANTIC_ID)
+                        || child(children(element), "EndpointMetadata").is_some())
+            })
+            .map(Interface::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { interfaces })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Interface {
+    pub id_short: String,
+    pub title: Option<String>,
+    pub protocol: Protocol,
+    pub endpoint: EndpointMetadata,
+    pub properties: Vec<PropertyAffordance>,
+    pub actions: Vec<InteractionAffordance>,
+    pub events: Vec<InteractionAffordance>,
+}
+
+impl Interface {
+    /// The property affordance named `key`, nested ones included.
+    pub fn property(&self, key: &str) -> Option<&PropertyAffordance> {
+        fn find<'a>(
+            properties: &'a [PropertyAffordance],
+            key: &str,
+        ) -> Option<&'a PropertyAffordance> {
+            properties
+                .iter()
+                .find_map(|property| match property.key == key {
+                    true => Some(property),
+                    false => find(&property.properties, key),
+                })
+        }
+
+        find(&self.properties, key)
+    }
+
+    fn parse(element: &SubmodelElement) -> Result<Self, AidError> {
+        let id_short = element.id_short().unwrap_or_default().to_string();
+        let elements = children(element);
+
+        let endpoint = EndpointMetadata::parse(
+            child(elements, "EndpointMetadata")
+                .ok_or_else(|| AidError::MissingElement(format!("{id_short}.EndpointMetadata")))?,
+            &format!("{id_short}.EndpointMetadata"),
+        )?;
+
+        let metadata = child(elements, "InterfaceMetadata").map(children);
+        let affordances = |name: &str| {
+            metadata
+                .and_then(|metadata| child(metadata, name))
+                .map(children)
+                .unwrap_or_default()
+                .iter()
+                .filter(|element| matches!(element, SubmodelElement::SubmodelElementCollection(_)))
+        };
+        let path = format!("{id_short}.InterfaceMetadata");
+
+        Ok(Self {
+            title: text(elements, "title"),
+            protocol: protocol(element, &endpoint.base),
+            properties: affordances("properties")
+                .map(|element| PropertyAffordance::parse(element, &format!("{path}.properties")))
+                .collect::<Result<_, _>>()?,
+            actions: affordances("actions")
+                .map(|element| InteractionAffordance::parse(element, &format!("{path}.actions")))
+                .collect::<Result<_, _>>()?,
+            events: affordances("events")
+                .map(|element| InteractionAffordance::parse(element, &format!("{path}.events")))
+                .collect::<Result<_, _>>()?,
+            endpoint,
+            id_short,
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct EndpointMetadata {
+    /// Base address the `href`s of forms are relative to, i.e. `modbus+tcp://10.0.0.5:502`.
+    pub base: String,
+    pub content_type: Option<String>,
+
+    /// Names of the security definitions in use.
+    pub security: Vec<String>,
+    pub security_definitions: Vec<SecurityScheme>,
+
+    /// Protocol specific terms, i.e. `modv_mostSignificantByte`.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl EndpointMetadata {
+    fn parse(element: &SubmodelElement, path: &str) -> Result<Self, AidError> {
+        let elements = children(element);
+
+        let security = child(elements, "security")
+            .map(children)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|element| match element {
+                SubmodelElement::ReferenceElement(reference) => {
+                    reference.value.as_ref()?.keys().last()
+                }
+                _ => None,
+            })
+            .map(|key| key.value().to_string())
+            .collect();
+
+        let security_definitions = child(elements, "securityDefinitions")
+            .map(children)
+            .unwrap_or_default()
+            .iter()
+            .map(|element| SecurityScheme {
+                name: element.id_short().unwrap_or_default().to_string(),
+                scheme: text(children(element), "scheme"),
+                fields: texts(children(element), &["scheme"]),
+            })
+            .collect();
+
+        Ok(Self {
+            base: text(elements, "base")
+                .ok_or_else(|| AidError::MissingElement(format!("{path}.base")))?,
+            content_type: text(elements, "contentType"),
+            security,
+            security_definitions,
+            fields: texts(elements, &["base", "contentType"]),
+        })
+    }
+}
+
+/// An entry of `securityDefinitions`, named by its idShort.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SecurityScheme {
+    pub name: String,
+
+    /// i.e. `nosec`, `basic` or `bearer`.
+    pub scheme: Option<String>,
+    pub fields: BTreeMap<String, String>,
+}
+
+/// How to reach an affordance.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Form {
+    pub href: String,
+    pub content_type: Option<String>,
+
+    /// Protocol specific terms, i.e. `htv_methodName`, `modv_function` or `mqv_qos`.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Form {
+    /// The `href` resolved against the `base` of the endpoint, absolute ones are kept.
+    pub fn target(&self, endpoint: &EndpointMetadata) -> String {
+        match self.href.contains("://") {
+            true => self.href.clone(),
+            false => format!(
+                "{}/{}",
+                endpoint.base.trim_end_matches('/'),
+                self.href.trim_start_matches('/')
+            ),
+        }
+    }
+
+    fn parse(element: &SubmodelElement, path: &str) -> Result<Self, AidError> {
+        let elements = children(element);
+
+        Ok(Self {
+            href: text(elements, "href")
+                .ok_or_else(|| AidError::MissingElement(format!("{path}.href")))?,
+            content_type: text(elements, "contentType"),
+            fields: texts(elements, &["href", "contentType"]),
+        })
+    }
+}
+
+/// A property of the asset, i.e. a sensor value or a register.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PropertyAffordance {
+    pub id_short: String,
+
+    /// Name of the property, the idShort if no `key` is given.
+    pub key: String,
+    pub title: Option<String>,
+    pub data_type: Option<AffordanceType>,
+    pub observable: bool,
+    pub unit: Option<String>,
+
+    /// Lexical bounds of `min_max`.
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub forms: Vec<Form>,
+
+    /// Members of an object.
+    pub properties: Vec<PropertyAffordance>,
+}
+
+impl PropertyAffordance {
+    /// The value type to bind the property with: a `modv_type` of the forms like `xsd:int`,
+    /// else derived from the `type`.
+    pub fn value_type(&self) -> Option<DataXsd> {
+        self.forms
+            .iter()
+            .filter_map(|form| form.fields.get("modv_type"))
+            .find_map(|value_type| {
+                let value_type = value_type.strip_prefix("xsd:").unwrap_or(value_type);
+                let value_type = value_type.strip_prefix("xs:").unwrap_or(value_type);
+                DataXsd::from_value_type(&format!("xs:{value_type}"))
+            })
+            .or_else(|| self.data_type?.value_type())
+    }
+
+    fn parse(element: &SubmodelElement, parent: &str) -> Result<Self, AidError> {
+        let id_short = element.id_short().unwrap_or_default().to_string();
+        let path = format!("{parent}.{id_short}");
+        let elements = children(element);
+
+        let data_type = text(elements, "type")
+            .map(|data_type| {
+                data_type.parse().map_err(|_| AidError::InvalidElement {
+                    path: format!("{path}.type"),
+                    expected: "Thing Description data type".into(),
+                })
+            })
+            .transpose()?;
+
+        let (min, max) = match child(elements, "min_max") {
+            Some(SubmodelElement::Range(range)) => {
+                range_bounds(range).map_err(|_| AidError::InvalidElement {
+                    path: format!("{path}.min_max"),
+                    expected: "Range with bounds of its valueType".into(),
+                })?
+            }
+            Some(_) => {
+                return Err(AidError::InvalidElement {
+                    path: format!("{path}.min_max"),
+                    expected: "Range".into(),
+                });
+            }
+            None => (None, None),
+        };
+
+        let properties = child(elements, "properties")
+            .map(children)
+            .unwrap_or_default()
+            .iter()
+            .map(|element| PropertyAffordance::parse(element, &format!("{path}.properties")))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            key: text(elements, "key").unwrap_or_else(|| id_short.clone()),
+            title: text(elements, "title"),
+            data_type,
+            observable: text(elements, "observable").is_some_and(|value| value == "true"),
+            unit: text(elements, "unit"),
+            min,
+            max,
+            forms: forms(elements, &path)?,
+            properties,
+            id_short,
+        })
+    }
+}
+
+/// An action or an event of the asset.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct InteractionAffordance {
+    pub id_short: String,
+    pub key: String,
+    pub title: Option<String>,
+    pub forms: Vec<Form>,
+}
+
+impl InteractionAffordance {
+    fn parse(element: &SubmodelElement, parent: &str) -> Result<Self, AidError> {
+        let id_short = element.id_short().unwrap_or_default().to_string();
+        let path = format!("{parent}.{id_short}");
+        let elements = children(element);
+
+        Ok(Self {
+            key: text(elements, "key").unwrap_or_else(|| id_short.clone()),
+            title: text(elements, "title"),
+            forms: forms(elements, &path)?,
+            id_short,
+        })
+    }
+}
+
+/// `forms` is a single collection in AID, lists of forms are accepted as well.
+fn forms(elements: &[SubmodelElement], path: &str) -> Result<Vec<Form>, AidError> {
+    let path = format!("{path}.forms");
+
+    match child(elements, "forms") {
+        Some(form @ SubmodelElement::SubmodelElementCollection(_)) => {
+            Ok(vec![Form::parse(form, &path)?])
+        }
+        Some(SubmodelElement::SubmodelElementList(list)) => list
+            .value
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, form)| Form::parse(form, &format!("{path}[{index}]")))
+            .collect(),
+        Some(_) => Err(AidError::InvalidElement {
+            path,
+            expected: "SubmodelElementCollection".into(),
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn protocol(interface: &SubmodelElement, base: &str) -> Protocol {
+    let semantic_ids = interface
+        .semantics()
+        .supplemental_semantic_ids
+        .iter()
+        .flatten()
+        .filter_map(|semantic_id| semantic_id.global_identifier());
+    let scheme = base.split_once("://").map(|(scheme, _)| scheme);
+
+    for hint in semantic_ids.chain(scheme) {
+        let hint = hint.to_ascii_lowercase();
+        if hint.contains("modbus") {
+            return Protocol::Modbus;
+        } else if hint.contains("mqtt") {
+            return Protocol::Mqtt;
+        } else if hint.ends_with("/2011/http") || hint == "http" || hint == "https" {
+            return Protocol::Http;
+        }
+    }
+
+    Protocol::Other(scheme.unwrap_or_default().to_string())
+}
+
+fn has_semantic_id(element: &SubmodelElement, semantic_id: &str) -> bool {
+    element
+        .semantics()
+        .semantic_id
+        .as_ref()
+        .is_some_and(|own| own.matches_identifier(semantic_id))
+}
+
+fn children(element: &SubmodelElement) -> &[SubmodelElement] {
+    element.children().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn child<'a>(elements: &'a [SubmodelElement], id_short: &str) -> Option<&'a SubmodelElement> {
+    elements
+        .iter()
+        .find(|element| element.id_short() == Some(id_short))
+}
+
+/// The lexical value of the `Property` or the first text of the `MultiLanguageProperty`.
+fn value(element: &SubmodelElement) -> Option<String> {
+    match element {
+        SubmodelElement::Property(property) => property.value.lexical(),
+        SubmodelElement::MultiLanguageProperty(property) => {
+            Some(property.value.as_ref()?.first()?.text.clone())
+        }
+        _ => None,
+    }
+}
+
+fn text(elements: &[SubmodelElement], id_short: &str) -> Option<String> {
+    child(elements, id_short).and_then(value)
+}
+
+/// Values of all properties but the `known` ones, by idShort.
+fn texts(elements: &[SubmodelElement], known: &[&str]) -> BTreeMap<String, String> {
+    elements
+        .iter()
+        .filter_map(|element| Some((element.id_short()?, value(element)?)))
+        .filter(|(id_short, _)| !known.contains(id_short))
+        .map(|(id_short, value)| (id_short.to_string(), value))
+        .collect()
+}
+
+fn range_bounds(range: &Range) -> Result<(Option<String>, Option<String>), DataXsdError> {
+    let (min, max) = range.bounds()?;
+
+    Ok((min.lexical(), max.lexical()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODBUS: &str = r#"{
+        "id": "https://example.com/aid/1",
+        "semanticId": {"type": "ExternalReference", "keys": [{"type": "GlobalReference",
+            "value": "https://admin-shell.io/idta/AssetInterfacesDescription/1/0/Submodel"}]},
+        "submodelElements": [{
+            "modelType": "SubmodelElementCollection", "idShort": "InterfaceMODBUS",
+            "semanticId": {"type": "ExternalReference", "keys": [{"type": "GlobalReference",
+                "value": "https://admin-shell.io/idta/AssetInterfacesDescription/1/0/Interface"}]},
+            "supplementalSemanticIds": [{"type": "ExternalReference", "keys": [
+                {"type": "GlobalReference", "value": "https://www.w3.org/2019/wot/modbus"}]}],
+            "value": [
+                {"modelType": "Property", "idShort": "title", "valueType": "xs:string",
+                 "value": "Pump"},
+                {"modelType": "SubmodelElementCollection", "idShort": "EndpointMetadata", "value": [
+                    {"modelType": "Property", "idShort": "base", "valueType": "xs:anyURI",
+                     "value": "modbus+tcp://10.0.0.5:502"},
+                    {"modelType": "Property", "idShort": "contentType", "valueType": "xs:string",
+                     "value": "application/octet-stream"},
+                    {"modelType": "Property", "idShort": "modv_mostSignificantByte",
+                     "valueType": "xs:boolean", "value": true},
+                    {"modelType": "SubmodelElementCollection", "idShort": "securityDefinitions",
+                     "value": [
+                        {"modelType": "SubmodelElementCollection", "idShort": "nosec_sc", "value": [
+                            {"modelType": "Property", "idShort": "scheme",
+                             "valueType": "xs:string", "value": "nosec"}
+                        ]}
+                    ]},
+                    {"modelType": "SubmodelElementList", "idShort": "security",
+                     "typeValueListElement": "ReferenceElement", "value": [
+                        {"modelType": "ReferenceElement", "value": {"type": "ModelReference",
+                            "keys": [
+                                {"type": "Submodel", "value": "https://example.com/aid/1"},
+                                {"type": "SubmodelElementCollection", "value": "InterfaceMODBUS"},
+                                {"type": "SubmodelElementCollection", "value": "EndpointMetadata"},
+                                {"type": "SubmodelElementCollection", "value": "securityDefinitions"},
+                                {"type": "SubmodelElementCollection", "value": "nosec_sc"}
+                            ]}}
+                    ]}
+                ]},
+                {"modelType": "SubmodelElementCollection", "idShort": "InterfaceMetadata", "value": [
+                    {"modelType": "SubmodelElementCollection", "idShort": "properties", "value": [
+                        {"modelType": "SubmodelElementCollection", "idShort": "speed", "value": [
+                            {"modelType": "Property", "idShort": "key", "valueType": "xs:string",
+                             "value": "motorSpeed"},
+                            {"modelType": "Property", "idShort": "type", "valueType": "xs:string",
+                             "value": "integer"},
+                            {"modelType": "Property", "idShort": "observable",
+                             "valueType": "xs:boolean", "value": true},
+                            {"modelType": "Range", "idShort": "min_max", "valueType": "xs:integer",
+                             "min": 0, "max": 3000},
+                            {"modelType": "SubmodelElementCollection", "idShort": "forms", "value": [
+                                {"modelType": "Property", "idShort": "href",
+                                 "valueType": "xs:string", "value": "/1/40001?quantity=1"},
+                                {"modelType": "Property", "idShort": "modv_function",
+                                 "valueType": "xs:string", "value": "readHoldingRegisters"},
+                                {"modelType": "Property", "idShort": "modv_type",
+                                 "valueType": "xs:string", "value": "xsd:unsignedShort"}
+                            ]}
+                        ]}
+                    ]},
+                    {"modelType": "SubmodelElementCollection", "idShort": "actions", "value": [
+                        {"modelType": "SubmodelElementCollection", "idShort": "reset", "value": [
+                            {"modelType": "SubmodelElementCollection", "idShort": "forms", "value": [
+                                {"modelType": "Property", "idShort": "href",
+                                 "valueType": "xs:string", "value": "/1/1"}
+                            ]}
+                        ]}
+                    ]}
+                ]}
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn modbus_interface() {
+        let submodel: Submodel = serde_json::from_str(MODBUS).unwrap();
+        let aid = AssetInterfacesDescription::try_from(&submodel).unwrap();
+
+        let interface = aid.interface("InterfaceMODBUS").unwrap();
+        assert_eq!(interface.title.as_deref(), Some("Pump"));
+        assert_eq!(interface.protocol, Protocol::Modbus);
+        assert_eq!(aid.interfaces_for(&Protocol::Modbus).count(), 1);
+
+        let endpoint = &interface.endpoint;
+        assert_eq!(endpoint.base, "modbus+tcp://10.0.0.5:502");
+        assert_eq!(
+            endpoint.content_type.as_deref(),
+            Some("application/octet-stream")
+        );
+        assert_eq!(endpoint.fields["modv_mostSignificantByte"], "true");
+        assert_eq!(endpoint.security, vec!["nosec_sc"]);
+        assert_eq!(
+            endpoint.security_definitions[0].scheme.as_deref(),
+            Some("nosec")
+        );
+
+        let speed = interface.property("motorSpeed").unwrap();
+        assert_eq!(speed.id_short, "speed");
+        assert_eq!(speed.data_type, Some(AffordanceType::Integer));
+        assert!(speed.observable);
+        assert_eq!(
+            (speed.min.as_deref(), speed.max.as_deref()),
+            (Some("0"), Some("3000"))
+        );
+        assert_eq!(
+            speed.forms[0].fields["modv_function"],
+            "readHoldingRegisters"
+        );
+        assert_eq!(
+            speed.forms[0].target(endpoint),
+            "modbus+tcp://10.0.0.5:502/1/40001?quantity=1"
+        );
+        assert_eq!(speed.value_type(), Some(DataXsd::UnsignedShort(None)));
+
+        assert_eq!(interface.actions[0].key, "reset");
+        assert_eq!(interface.actions[0].forms[0].href, "/1/1");
+        assert!(interface.events.is_empty());
+    }
+
+    #[test]
+    fn invalid_descriptions() {
+        let mut submodel: Submodel = serde_json::from_str(MODBUS).unwrap();
+        submodel.semantics.semantic_id = Some(
+            "[ExternalRef](GlobalReference)https://example.com/other"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            AssetInterfacesDescription::try_from(&submodel),
+            Err(AidError::NotAid("https://example.com/aid/1".into()))
+        );
+
+        let submodel: Submodel = serde_json::from_str(
+            &MODBUS.replace(r#""idShort": "base""#, r#""idShort": "address""#),
+        )
+        .unwrap();
+        assert_eq!(
+            AssetInterfacesDescription::try_from(&submodel),
+            Err(AidError::MissingElement(
+                "InterfaceMODBUS.EndpointMetadata.base".into()
+            ))
+        );
+
+        let submodel: Submodel =
+            serde_json::from_str(&MODBUS.replace(r#""value": "integer""#, r#""value": "decimal""#))
+                .unwrap();
+        assert_eq!(
+            AssetInterfacesDescription::try_from(&submodel),
+            Err(AidError::InvalidElement {
+                path: "InterfaceMODBUS.InterfaceMetadata.properties.speed.type".into(),
+                expected: "Thing Description data type".into()
+            })
+        );
+    }
+
+    #[test]
+    fn affordance_value_types() {
+        assert_eq!(
+            AffordanceType::Number.value_type(),
+            Some(DataXsd::Double(None))
+        );
+        assert_eq!(AffordanceType::Object.value_type(), None);
+        assert_eq!("boolean".parse(), Ok(AffordanceType::Boolean));
+    }
+}
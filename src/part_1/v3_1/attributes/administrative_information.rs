@@ -6,7 +6,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
 /// Administrative metainformation for an element like version information
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
 pub struct AdministrativeInformation {
     #[serde(flatten)]
     pub version: Version,
@@ -30,7 +30,7 @@ pub struct AdministrativeInformation {
 /// This means that a revision requires a version.
 /// If there is no version, there is no revision.
 /// Revision is optional.
-#[derive(Clone, PartialEq, Debug, Serialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Default)]
 pub struct Version {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
@@ -1,7 +1,6 @@
 use crate::part_1::v3_1::level_type::LevelType;
 use crate::part_1::v3_1::primitives::lang_string::LangString;
-use crate::part_1::v3_1::reference::Reference;
-use crate::part_1::v3_1::reference::deserialize_external_reference;
+use crate::part_1::v3_1::reference::{ExternalReference, Reference};
 use crate::part_1::v3_1::value_list::ValueList;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
@@ -11,14 +10,13 @@ use strum::{Display, EnumString};
 pub struct HasDataSpecification {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "embeddedDataSpecifications")]
-    embedded_data_specifications: Option<Vec<EmbeddedDataSpecification>>,
+    pub(crate) embedded_data_specifications: Option<Vec<EmbeddedDataSpecification>>,
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct EmbeddedDataSpecification {
     #[serde(rename = "dataSpecification")]
-    #[serde(deserialize_with = "deserialize_external_reference")]
-    pub data_specification: Reference,
+    pub data_specification: ExternalReference,
 
     #[serde(rename = "dataSpecificationContent")]
     pub data_specification_content: DataSpecificationIec61360,
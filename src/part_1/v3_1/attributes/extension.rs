@@ -1,5 +1,5 @@
 use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
-use crate::part_1::v3_1::reference::Reference;
+use crate::part_1::v3_1::reference::{ModelReference, Reference};
 use serde::{Deserialize, Serialize};
 
 /// HasExtensions
@@ -41,7 +41,7 @@ pub struct Extension {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "refersTo")]
-    pub refers_to: Option<Vec<Reference>>,
+    pub refers_to: Option<Vec<ModelReference>>,
 }
 
 impl Extension {
@@ -89,7 +89,7 @@ mod tests {
 
         let json = r#"{"name":"","valueType":"xs:string"}"#;
 
-        let actual = serde_json::from_str(&json).expect("Should deserialize");
+        let actual = serde_json::from_str(json).expect("Should deserialize");
         assert_eq!(expected, actual);
     }
 }
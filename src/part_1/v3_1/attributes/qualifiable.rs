@@ -28,6 +28,7 @@ pub struct QualifierInner {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "kind")]
+#[allow(clippy::enum_variant_names)]
 pub enum Qualifier {
     ConceptQualifier(QualifierInner),
     TemplateQualifier(QualifierInner),
@@ -38,6 +39,27 @@ pub enum Qualifier {
     Unknown(QualifierInner),
 }
 
+impl Qualifiable {
+    /// A qualifier of the type `qualifier_type` is present, i.e. `Confidential`.
+    pub fn has_qualifier(&self, qualifier_type: &str) -> bool {
+        self.qualifiers
+            .iter()
+            .flatten()
+            .any(|qualifier| qualifier.inner().ty == qualifier_type)
+    }
+}
+
+impl Qualifier {
+    pub fn inner(&self) -> &QualifierInner {
+        match self {
+            Qualifier::ConceptQualifier(inner)
+            | Qualifier::TemplateQualifier(inner)
+            | Qualifier::ValueQualifier(inner)
+            | Qualifier::Unknown(inner) => inner,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,3 +12,22 @@ pub struct HasSemantics {
     #[serde(rename = "supplementalSemanticIds")]
     pub supplemental_semantic_ids: Option<Vec<Reference>>,
 }
+
+impl HasSemantics {
+    /// The semantic id matches `semantic_id`, see [`Reference::matches_semantic_id`].
+    pub fn has_semantic_id(&self, semantic_id: &Reference) -> bool {
+        self.semantic_id
+            .as_ref()
+            .is_some_and(|own| own.matches_semantic_id(semantic_id))
+    }
+
+    /// The semantic id or one of the supplemental semantic ids matches `semantic_id`.
+    pub fn has_any_semantic_id(&self, semantic_id: &Reference) -> bool {
+        self.has_semantic_id(semantic_id)
+            || self
+                .supplemental_semantic_ids
+                .iter()
+                .flatten()
+                .any(|own| own.matches_semantic_id(semantic_id))
+    }
+}
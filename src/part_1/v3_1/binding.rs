@@ -0,0 +1,705 @@
+//! Live data binding of submodel element values.
+//!
+//! A [`DataSource`] delivers [`Sample`]s, i.e. readings of a sensor or a PLC tag. A [`Binding`]
+//! connects a source to the `Property`, `Range` or `MultiLanguageProperty` at an idShort path and
+//! [`SubmodelBindings`] writes the samples into a [`Submodel`]. Values are coerced into the value
+//! type of the element, so a source only has to deliver the lexical representation.
+//!
+//! ```
+//! use aas::part_1::v3_1::binding::{Binding, PollingSource, SourceValue, SubmodelBindings};
+//! use aas::part_1::v3_1::core::Submodel;
+//! use aas::part_1::v3_1::submodel_elements::SubmodelElement;
+//!
+//! let mut submodel: Submodel = serde_json::from_str(r#"{
+//!     "id": "https://example.com/submodels/1",
+//!     "submodelElements": [
+//!         {"modelType": "Property", "idShort": "Temperature", "valueType": "xs:double"}
+//!     ]
+//! }"#).unwrap();
+//!
+//! let mut bindings = SubmodelBindings::new();
+//! bindings.add(Binding::new(
+//!     "Temperature",
+//!     PollingSource::new(|| Ok(SourceValue::Text("21.5".into()))),
+//! ));
+//!
+//! let report = bindings.sync(&mut submodel);
+//! assert_eq!(report.updated, vec!["Temperature".to_string()]);
+//!
+//! let Some(SubmodelElement::Property(property)) = submodel.element("Temperature") else {
+//!     panic!()
+//! };
+//! assert_eq!(property.get::<f64>(), Ok(Some(21.5)));
+//! ```
+
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+use crate::part_1::v3_1::primitives::{DataXsdError, DateTimeUTC};
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::{Environment, LangString};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum BindingError {
+    #[error("No submodel element found at {0}")]
+    UnknownElement(String),
+
+    #[error("No submodel found with id {0}")]
+    UnknownSubmodel(String),
+
+    #[error("Only a Property, Range or MultiLanguageProperty can be bound, not a {0}")]
+    UnsupportedElement(String),
+
+    #[error(transparent)]
+    Coercion(#[from] DataXsdError),
+
+    #[error("A {value} value can not be written into a {element}")]
+    IncompatibleValue { element: String, value: String },
+
+    #[error("{0} is not a valid language tag")]
+    InvalidLanguage(String),
+
+    #[error("The data source failed: {0}")]
+    Source(String),
+}
+
+/// A value as delivered by a data source.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SourceValue {
+    /// Lexical representation, parsed by the value type of the element.
+    /// A `Range` accepts `min..max`, either bound may be empty.
+    /// A `MultiLanguageProperty` takes it as text in the language of the binding.
+    Text(String),
+
+    /// Typed value, converted into the value type of the element by its lexical representation.
+    Value(DataXsd),
+
+    /// Lexical bounds of a `Range`.
+    Range {
+        min: Option<String>,
+        max: Option<String>,
+    },
+
+    /// Replaces all texts of a `MultiLanguageProperty`.
+    LangStrings(Vec<LangString>),
+}
+
+impl SourceValue {
+    fn kind(&self) -> &'static str {
+        match self {
+            SourceValue::Text(_) => "text",
+            SourceValue::Value(_) => "typed",
+            SourceValue::Range { .. } => "range",
+            SourceValue::LangStrings(_) => "multi language",
+        }
+    }
+}
+
+impl From<&str> for SourceValue {
+    fn from(value: &str) -> Self {
+        SourceValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for SourceValue {
+    fn from(value: String) -> Self {
+        SourceValue::Text(value)
+    }
+}
+
+impl From<DataXsd> for SourceValue {
+    fn from(value: DataXsd) -> Self {
+        SourceValue::Value(value)
+    }
+}
+
+/// A value together with the time it was acquired at.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sample {
+    pub value: SourceValue,
+    pub timestamp: DateTimeUTC,
+}
+
+impl Sample {
+    /// A sample acquired now.
+    pub fn new(value: impl Into<SourceValue>) -> Self {
+        Self::at(value, Utc::now())
+    }
+
+    pub fn at(value: impl Into<SourceValue>, timestamp: DateTimeUTC) -> Self {
+        Self {
+            value: value.into(),
+            timestamp,
+        }
+    }
+}
+
+/// Provider of live values, i.e. a fieldbus, a sensor driver or a message queue.
+pub trait DataSource: Send {
+    /// The next sample, `Ok(None)` if nothing new is available. Must not block.
+    fn read(&mut self) -> Result<Option<Sample>, BindingError>;
+}
+
+/// Calls a closure on every read and stamps its value with the current time.
+pub struct PollingSource<F> {
+    poll: F,
+}
+
+impl<F> PollingSource<F>
+where
+    F: FnMut() -> Result<SourceValue, BindingError> + Send,
+{
+    pub fn new(poll: F) -> Self {
+        Self { poll }
+    }
+}
+
+impl<F> DataSource for PollingSource<F>
+where
+    F: FnMut() -> Result<SourceValue, BindingError> + Send,
+{
+    fn read(&mut self) -> Result<Option<Sample>, BindingError> {
+        (self.poll)().map(|value| Some(Sample::new(value)))
+    }
+}
+
+/// Replays lines of a reader, i.e. a file or stdin, as text samples. Mainly meant for tests and
+/// simulations.
+///
+/// Every line is a value, optionally preceded by a RFC 3339 timestamp:
+/// `2025-01-01T12:00:00Z 21.5`. Lines without timestamp are stamped when read. Empty lines and
+/// lines starting with `#` are skipped. Each [`DataSource::read`] returns the next line.
+pub struct LineSource {
+    lines: Receiver<io::Result<String>>,
+}
+
+impl LineSource {
+    /// Reads `reader` on a background thread, so reads never block.
+    pub fn new<R: BufRead + Send + 'static>(reader: R) -> Self {
+        let (sender, lines) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in reader.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { lines }
+    }
+
+    pub fn stdin() -> Self {
+        Self::new(BufReader::new(io::stdin()))
+    }
+
+    pub fn file(path: impl AsRef<Path>) -> Result<Self, BindingError> {
+        let file = fs::File::open(path).map_err(|err| BindingError::Source(err.to_string()))?;
+        Ok(Self::new(BufReader::new(file)))
+    }
+}
+
+impl DataSource for LineSource {
+    fn read(&mut self) -> Result<Option<Sample>, BindingError> {
+        loop {
+            let line = match self.lines.try_recv() {
+                Ok(line) => line.map_err(|err| BindingError::Source(err.to_string()))?,
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => return Ok(None),
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let sample = match line.split_once(char::is_whitespace) {
+                Some((timestamp, value)) => match DateTime::parse_from_rfc3339(timestamp) {
+                    Ok(timestamp) => Sample::at(value.trim_start(), timestamp.to_utc()),
+                    Err(_) => Sample::new(line),
+                },
+                None => Sample::new(line),
+            };
+
+            return Ok(Some(sample));
+        }
+    }
+}
+
+/// Connects a data source to the element at an idShort path.
+pub struct Binding {
+    pub path: String,
+
+    /// Samples older than this are stale. Without, values never get stale.
+    pub max_age: Option<Duration>,
+
+    /// Removes the value of the element once it is stale, so outdated values aren't served.
+    pub clear_when_stale: bool,
+
+    /// Language of text values written into a `MultiLanguageProperty`.
+    pub language: String,
+
+    source: Box<dyn DataSource>,
+    last_update: Option<DateTimeUTC>,
+}
+
+impl Binding {
+    pub fn new(path: impl Into<String>, source: impl DataSource + 'static) -> Self {
+        Self {
+            path: path.into(),
+            max_age: None,
+            clear_when_stale: false,
+            language: "en".to_string(),
+            source: Box::new(source),
+            last_update: None,
+        }
+    }
+
+    /// Timestamp of the last sample written into the element.
+    pub fn last_update(&self) -> Option<DateTimeUTC> {
+        self.last_update
+    }
+
+    /// Whether the last sample is older than `max_age` at `now`, or no sample arrived yet.
+    pub fn is_stale_at(&self, now: DateTimeUTC) -> bool {
+        let Some(max_age) = self.max_age else {
+            return false;
+        };
+
+        match (self.last_update, TimeDelta::from_std(max_age)) {
+            (Some(last_update), Ok(max_age)) => now - last_update > max_age,
+            (Some(_), Err(_)) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Outcome of a [`SubmodelBindings::sync`], each entry is the path of a binding.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SyncReport {
+    pub updated: Vec<String>,
+    pub stale: Vec<String>,
+    pub errors: Vec<(String, BindingError)>,
+}
+
+/// The bindings of a submodel.
+#[derive(Default)]
+pub struct SubmodelBindings {
+    bindings: Vec<Binding>,
+}
+
+impl SubmodelBindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, binding: Binding) -> &mut Self {
+        self.bindings.push(binding);
+        self
+    }
+
+    pub fn binding(&self, path: &str) -> Option<&Binding> {
+        self.bindings.iter().find(|binding| binding.path == path)
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Reads every source once and writes new samples into `submodel`.
+    pub fn sync(&mut self, submodel: &mut Submodel) -> SyncReport {
+        self.sync_at(submodel, Utc::now())
+    }
+
+    /// Like [`SubmodelBindings::sync`], with staleness judged at `now`.
+    ///
+    /// Samples older than the last written one are dropped, so out of order delivery never
+    /// rolls a value back.
+    pub fn sync_at(&mut self, submodel: &mut Submodel, now: DateTimeUTC) -> SyncReport {
+        let mut report = SyncReport::default();
+
+        for binding in &mut self.bindings {
+            let path = binding.path.clone();
+
+            let sample = match binding.source.read() {
+                Ok(sample) => sample,
+                Err(err) => {
+                    report.errors.push((path.clone(), err));
+                    None
+                }
+            };
+
+            if let Some(sample) = sample
+                && binding
+                    .last_update
+                    .is_none_or(|last_update| sample.timestamp >= last_update)
+            {
+                match write(submodel, binding, sample.value) {
+                    Ok(()) => {
+                        binding.last_update = Some(sample.timestamp);
+                        report.updated.push(path.clone());
+                    }
+                    Err(err) => report.errors.push((path.clone(), err)),
+                }
+            }
+
+            if binding.is_stale_at(now) {
+                if binding.clear_when_stale
+                    && let Err(err) = clear(submodel, &binding.path)
+                {
+                    report.errors.push((path.clone(), err));
+                }
+                report.stale.push(path);
+            }
+        }
+
+        report
+    }
+
+    /// Syncs the submodel `submodel_id` of `environment` every `interval` on a background
+    /// thread, passing each outcome to `on_report`.
+    pub fn spawn<F>(
+        mut self,
+        environment: Arc<Mutex<Environment>>,
+        submodel_id: impl Into<String>,
+        interval: Duration,
+        mut on_report: F,
+    ) -> BindingHandle
+    where
+        F: FnMut(Result<SyncReport, BindingError>) + Send + 'static,
+    {
+        let submodel_id = submodel_id.into();
+        let (stop, stopped) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            loop {
+                let report = {
+                    let mut environment = environment.lock().unwrap_or_else(|err| err.into_inner());
+                    match environment.submodel_mut(&submodel_id) {
+                        Some(submodel) => Ok(self.sync(submodel)),
+                        None => Err(BindingError::UnknownSubmodel(submodel_id.clone())),
+                    }
+                };
+                on_report(report);
+
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            }
+            self
+        });
+
+        BindingHandle { stop, thread }
+    }
+}
+
+/// Background synchronisation started by [`SubmodelBindings::spawn`].
+pub struct BindingHandle {
+    stop: Sender<()>,
+    thread: JoinHandle<SubmodelBindings>,
+}
+
+impl BindingHandle {
+    /// Stops the synchronisation and hands the bindings back.
+    pub fn stop(self) -> SubmodelBindings {
+        let _ = self.stop.send(());
+        match self.thread.join() {
+            Ok(bindings) => bindings,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
+
+fn write(
+    submodel: &mut Submodel,
+    binding: &Binding,
+    value: SourceValue,
+) -> Result<(), BindingError> {
+    let element = submodel
+        .element_mut(&binding.path)
+        .ok_or_else(|| BindingError::UnknownElement(binding.path.clone()))?;
+
+    let incompatible =
+        |element: &SubmodelElement, value: &SourceValue| BindingError::IncompatibleValue {
+            element: element.to_string(),
+            value: value.kind().to_string(),
+        };
+
+    match (&mut *element, value) {
+        (SubmodelElement::Property(property), SourceValue::Text(text)) => {
+            property.value.set_lexical(&text)?
+        }
+        (SubmodelElement::Property(property), SourceValue::Value(value)) => match value.lexical() {
+            Some(lexical) => property.value.set_lexical(&lexical)?,
+            None => property.value.clear(),
+        },
+        (SubmodelElement::Range(range), SourceValue::Range { min, max }) => {
+            range.set_lexical(min.as_deref(), max.as_deref())?
+        }
+        (SubmodelElement::Range(range), SourceValue::Text(text)) => {
+            let Some((min, max)) = text.split_once("..") else {
+                return Err(incompatible(element, &SourceValue::Text(text)));
+            };
+            range.set_lexical(bound(min), bound(max))?
+        }
+        (SubmodelElement::MultiLanguageProperty(property), SourceValue::LangStrings(texts)) => {
+            property.value = Some(texts)
+        }
+        (SubmodelElement::MultiLanguageProperty(property), SourceValue::Text(text)) => {
+            let text = LangString::try_new(&binding.language, text)
+                .map_err(|_| BindingError::InvalidLanguage(binding.language.clone()))?;
+            let texts = property.value.get_or_insert_default();
+
+            match texts.iter_mut().find(|t| t.language == text.language) {
+                Some(existing) => *existing = text,
+                None => texts.push(text),
+            }
+        }
+        (
+            SubmodelElement::Property(_)
+            | SubmodelElement::Range(_)
+            | SubmodelElement::MultiLanguageProperty(_),
+            value,
+        ) => return Err(incompatible(element, &value)),
+        (element, _) => return Err(BindingError::UnsupportedElement(element.to_string())),
+    }
+
+    Ok(())
+}
+
+fn clear(submodel: &mut Submodel, path: &str) -> Result<(), BindingError> {
+    match submodel.element_mut(path) {
+        Some(SubmodelElement::Property(property)) => property.value.clear(),
+        Some(SubmodelElement::Range(range)) => range.set_lexical(None, None)?,
+        Some(SubmodelElement::MultiLanguageProperty(property)) => property.value = None,
+        Some(element) => return Err(BindingError::UnsupportedElement(element.to_string())),
+        None => return Err(BindingError::UnknownElement(path.to_string())),
+    }
+
+    Ok(())
+}
+
+/// An empty bound of `min..max` is unbounded.
+fn bound(bound: &str) -> Option<&str> {
+    Some(bound.trim()).filter(|bound| !bound.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::submodel_elements::Range;
+    use std::io::Cursor;
+
+    fn submodel() -> Submodel {
+        serde_json::from_str(
+            r#"{
+                "id": "https://example.com/submodels/1",
+                "submodelElements": [
+                    {"modelType": "Property", "idShort": "Speed", "valueType": "xs:int"},
+                    {"modelType": "Range", "idShort": "Limits", "valueType": "xs:double"},
+                    {"modelType": "MultiLanguageProperty", "idShort": "Status",
+                     "value": [{"language": "de", "text": "Bereit"}]},
+                    {"modelType": "Blob", "idShort": "Image", "contentType": "image/png"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn property(submodel: &Submodel, path: &str) -> DataXsd {
+        match submodel.element(path) {
+            Some(SubmodelElement::Property(property)) => property.value.clone(),
+            other => panic!("expected a Property, found {other:?}"),
+        }
+    }
+
+    fn time(seconds: i64) -> DateTimeUTC {
+        DateTime::from_timestamp(seconds, 0).unwrap()
+    }
+
+    /// Hands out prepared samples, one per read.
+    struct Samples(Vec<Sample>);
+
+    impl DataSource for Samples {
+        fn read(&mut self) -> Result<Option<Sample>, BindingError> {
+            Ok((!self.0.is_empty()).then(|| self.0.remove(0)))
+        }
+    }
+
+    #[test]
+    fn coercion() {
+        let mut submodel = submodel();
+        let mut bindings = SubmodelBindings::new();
+        bindings
+            .add(Binding::new(
+                "Speed",
+                Samples(vec![Sample::new(DataXsd::Double(Some(42.0)))]),
+            ))
+            .add(Binding::new("Limits", Samples(vec![Sample::new("-1.5..")])))
+            .add(Binding::new("Status", Samples(vec![Sample::new("Ready")])))
+            .add(Binding::new("Image", Samples(vec![Sample::new("x")])));
+
+        let report = bindings.sync(&mut submodel);
+        assert_eq!(report.updated, vec!["Speed", "Limits", "Status"]);
+        assert_eq!(
+            report.errors,
+            vec![(
+                "Image".to_string(),
+                BindingError::UnsupportedElement("Blob".into())
+            )]
+        );
+
+        assert_eq!(property(&submodel, "Speed"), DataXsd::Int(Some(42)));
+
+        let Some(SubmodelElement::Range(Range::Double(limits))) = submodel.element("Limits") else {
+            panic!()
+        };
+        assert_eq!((limits.min, limits.max), (Some(-1.5), None));
+
+        let Some(SubmodelElement::MultiLanguageProperty(status)) = submodel.element("Status")
+        else {
+            panic!()
+        };
+        let texts: Vec<_> = status
+            .value
+            .iter()
+            .flatten()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(texts, vec![r#""Bereit"@de"#, r#""Ready"@en"#]);
+    }
+
+    #[test]
+    fn coercion_errors() {
+        let mut submodel = submodel();
+        let mut bindings = SubmodelBindings::new();
+        bindings
+            .add(Binding::new("Speed", Samples(vec![Sample::new("fast")])))
+            .add(Binding::new("Limits", Samples(vec![Sample::new("1.5")])))
+            .add(Binding::new("Missing", Samples(vec![Sample::new("1")])));
+
+        let report = bindings.sync(&mut submodel);
+        assert!(report.updated.is_empty());
+        assert!(matches!(
+            report.errors[0].1,
+            BindingError::Coercion(DataXsdError::InvalidLexical { .. })
+        ));
+        assert_eq!(
+            report.errors[1].1,
+            BindingError::IncompatibleValue {
+                element: "Range".into(),
+                value: "text".into()
+            }
+        );
+        assert_eq!(
+            report.errors[2].1,
+            BindingError::UnknownElement("Missing".into())
+        );
+    }
+
+    #[test]
+    fn timestamps_and_staleness() {
+        let mut submodel = submodel();
+        let mut binding = Binding::new(
+            "Speed",
+            Samples(vec![
+                Sample::at("1", time(100)),
+                Sample::at("2", time(90)),
+                Sample::at("3", time(110)),
+            ]),
+        );
+        binding.max_age = Some(Duration::from_secs(30));
+        binding.clear_when_stale = true;
+
+        let mut bindings = SubmodelBindings::new();
+        bindings.add(binding);
+
+        let report = bindings.sync_at(&mut submodel, time(100));
+        assert_eq!(report.updated, vec!["Speed"]);
+        assert!(report.stale.is_empty());
+
+        // Out of order samples are dropped.
+        let report = bindings.sync_at(&mut submodel, time(105));
+        assert!(report.updated.is_empty());
+        assert_eq!(property(&submodel, "Speed"), DataXsd::Int(Some(1)));
+
+        bindings.sync_at(&mut submodel, time(110));
+        assert_eq!(
+            bindings.binding("Speed").unwrap().last_update(),
+            Some(time(110))
+        );
+        assert_eq!(property(&submodel, "Speed"), DataXsd::Int(Some(3)));
+
+        let report = bindings.sync_at(&mut submodel, time(141));
+        assert_eq!(report.stale, vec!["Speed"]);
+        assert_eq!(property(&submodel, "Speed"), DataXsd::Int(None));
+    }
+
+    #[test]
+    fn line_source() {
+        let input = "# speed\n\n2025-01-01T12:00:00+01:00 7\n8\n";
+        let mut source = LineSource::new(Cursor::new(input));
+
+        let mut samples = Vec::new();
+        while samples.len() < 2 {
+            if let Some(sample) = source.read().unwrap() {
+                samples.push(sample);
+            }
+        }
+
+        assert_eq!(samples[0].value, SourceValue::Text("7".into()));
+        assert_eq!(
+            samples[0].timestamp,
+            DateTime::parse_from_rfc3339("2025-01-01T11:00:00Z").unwrap()
+        );
+        assert_eq!(samples[1].value, SourceValue::Text("8".into()));
+    }
+
+    #[test]
+    fn spawn() {
+        let environment = Arc::new(Mutex::new(Environment {
+            asset_administration_shells: None,
+            submodels: Some(vec![submodel()]),
+            concept_descriptions: None,
+        }));
+
+        let mut counter = 0;
+        let mut bindings = SubmodelBindings::new();
+        bindings.add(Binding::new(
+            "Speed",
+            PollingSource::new(move || {
+                counter += 1;
+                Ok(counter.to_string().into())
+            }),
+        ));
+
+        let (reports, received) = mpsc::channel();
+        let handle = bindings.spawn(
+            environment.clone(),
+            "https://example.com/submodels/1",
+            Duration::from_millis(1),
+            move |report| {
+                let _ = reports.send(report);
+            },
+        );
+        for _ in 0..3 {
+            assert!(received.recv().unwrap().is_ok());
+        }
+        let bindings = handle.stop();
+
+        assert!(bindings.binding("Speed").unwrap().last_update().is_some());
+        let environment = environment.lock().unwrap();
+        let submodel = environment
+            .submodel("https://example.com/submodels/1")
+            .unwrap();
+        assert!(property(submodel, "Speed").get::<i32>().unwrap().unwrap() >= 3);
+    }
+}
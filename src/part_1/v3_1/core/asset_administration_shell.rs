@@ -1,10 +1,9 @@
+use crate::part_1::ToJsonMetamodel;
 use crate::part_1::v3_1::attributes::data_specification::HasDataSpecification;
 use crate::part_1::v3_1::attributes::identifiable::Identifiable;
 use crate::part_1::v3_1::attributes::semantics::HasSemantics;
 use crate::part_1::v3_1::primitives::{ContentType, Identifier, Label, Uri};
-use crate::part_1::v3_1::reference::Reference;
-use crate::part_1::v3_1::reference::deserialize_optional_external_reference;
-use crate::part_1::{ToJsonMetamodel, ToJsonValue};
+use crate::part_1::v3_1::reference::{ExternalReference, ModelReference};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
@@ -23,10 +22,10 @@ pub struct AssetAdministrationShell {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "derivedFrom")]
-    pub derived_from: Option<Reference>,
+    pub derived_from: Option<ModelReference>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub submodels: Option<Vec<Reference>>,
+    pub submodels: Option<Vec<ModelReference>>,
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -41,7 +40,7 @@ pub struct AssetAdministrationShellMetamodel {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "derivedFrom")]
-    pub derived_from: Option<Reference>,
+    pub derived_from: Option<ModelReference>,
 }
 
 impl From<AssetAdministrationShell> for AssetAdministrationShellMetamodel {
@@ -74,6 +73,34 @@ pub enum AssetInformation {
     Type(AssetInformationInner),
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, EnumString, Display)]
+pub enum AssetKind {
+    Instance,
+    NotApplicable,
+    Role,
+    Type,
+}
+
+impl AssetInformation {
+    pub fn asset_kind(&self) -> AssetKind {
+        match self {
+            AssetInformation::Instance(_) => AssetKind::Instance,
+            AssetInformation::NotApplicable(_) => AssetKind::NotApplicable,
+            AssetInformation::Role(_) => AssetKind::Role,
+            AssetInformation::Type(_) => AssetKind::Type,
+        }
+    }
+
+    pub fn inner(&self) -> &AssetInformationInner {
+        match self {
+            AssetInformation::Instance(inner)
+            | AssetInformation::NotApplicable(inner)
+            | AssetInformation::Role(inner)
+            | AssetInformation::Type(inner) => inner,
+        }
+    }
+}
+
 // TODO: Skip option serialization
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
 pub struct AssetInformationInner {
@@ -105,12 +132,9 @@ pub struct SpecificAssetId {
 
     /// The unique ID of the (external) subject the specific asset ID value belongs to or
     /// has meaning to
-    /// Needs to be an external reference!
-    /// TODO: Typesafe with Newtype pattern
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "externalSubjectId")]
-    #[serde(deserialize_with = "deserialize_optional_external_reference")]
-    pub external_subject_id: Option<Reference>,
+    pub external_subject_id: Option<ExternalReference>,
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -142,7 +166,7 @@ mod tests {
             res,
             Resource {
                 path: UriBuf::from_str("file:://anywhere.json").unwrap(),
-                content_type: Some("application/json".into()),
+                content_type: Some(ContentType::try_from("application/json").unwrap()),
             }
         )
     }
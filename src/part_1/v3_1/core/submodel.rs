@@ -4,8 +4,12 @@ use crate::part_1::v3_1::attributes::identifiable::Identifiable;
 use crate::part_1::v3_1::attributes::kind::ModellingKind;
 use crate::part_1::v3_1::attributes::qualifiable::Qualifiable;
 use crate::part_1::v3_1::attributes::semantics::HasSemantics;
+use crate::part_1::v3_1::key::{Key, KeyTypes};
+use crate::part_1::v3_1::primitives::Identifier;
+use crate::part_1::v3_1::reference::{ModelReference, ReferenceInner};
 use crate::part_1::v3_1::submodel_elements::SubmodelElement;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 // make it an enum of ModellingKind?
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -74,3 +78,361 @@ impl ToJsonMetamodel for Submodel {
         serde_json::to_string(&meta)
     }
 }
+
+/// Segment of an idShort path, i.e. `Maintenance`, `Counters`, `[0]` for `Maintenance.Counters[0]`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum PathSegment<'a> {
+    IdShort(&'a str),
+    Index(usize),
+    /// `[]`, any element of a list. Only matched by [`Submodel::elements_at`].
+    AnyIndex,
+}
+
+pub(crate) fn parse_id_short_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+
+    for part in path.split('.') {
+        let (id_short, mut indices) = part.split_once('[').map_or((part, ""), |(id_short, _)| {
+            (id_short, &part[id_short.len()..])
+        });
+
+        if id_short.is_empty() {
+            return None;
+        }
+        segments.push(PathSegment::IdShort(id_short));
+
+        while !indices.is_empty() {
+            let (index, rest) = indices.strip_prefix('[')?.split_once(']')?;
+            segments.push(match index {
+                "" => PathSegment::AnyIndex,
+                index => PathSegment::Index(index.parse().ok()?),
+            });
+            indices = rest;
+        }
+    }
+
+    Some(segments)
+}
+
+/// Elements of a `SubmodelElementList` are addressed by index, all others by idShort.
+fn find_element<'a>(
+    elements: &'a [SubmodelElement],
+    segments: &[PathSegment],
+    in_list: bool,
+) -> Option<&'a SubmodelElement> {
+    let (first, rest) = segments.split_first()?;
+
+    let element = match first {
+        PathSegment::IdShort(id_short) if !in_list => elements
+            .iter()
+            .find(|element| element.id_short() == Some(id_short))?,
+        PathSegment::Index(index) if in_list => elements.get(*index)?,
+        _ => return None,
+    };
+
+    match rest.is_empty() {
+        true => Some(element),
+        false => find_element(
+            element.children()?,
+            rest,
+            matches!(element, SubmodelElement::SubmodelElementList(_)),
+        ),
+    }
+}
+
+/// Like [`find_element`], but with all elements of a list for `[]`.
+fn find_elements<'a>(
+    elements: &'a [SubmodelElement],
+    segments: &[PathSegment],
+    in_list: bool,
+    found: &mut Vec<&'a SubmodelElement>,
+) {
+    let Some((first, rest)) = segments.split_first() else {
+        return;
+    };
+
+    let matching = match first {
+        PathSegment::IdShort(id_short) if !in_list => elements
+            .iter()
+            .filter(|element| element.id_short() == Some(id_short))
+            .collect(),
+        PathSegment::Index(index) if in_list => elements.get(*index).into_iter().collect(),
+        PathSegment::AnyIndex if in_list => elements.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    for element in matching {
+        match (rest.is_empty(), element.children()) {
+            (true, _) => found.push(element),
+            (false, Some(children)) => {
+                let is_list = matches!(element, SubmodelElement::SubmodelElementList(_));
+                find_elements(children, rest, is_list, found);
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+fn find_element_mut<'a>(
+    elements: &'a mut [SubmodelElement],
+    segments: &[PathSegment],
+    in_list: bool,
+) -> Option<&'a mut SubmodelElement> {
+    let (first, rest) = segments.split_first()?;
+
+    let element = match first {
+        PathSegment::IdShort(id_short) if !in_list => elements
+            .iter_mut()
+            .find(|element| element.id_short() == Some(id_short))?,
+        PathSegment::Index(index) if in_list => elements.get_mut(*index)?,
+        _ => return None,
+    };
+
+    match rest.is_empty() {
+        true => Some(element),
+        false => {
+            let in_list = matches!(element, SubmodelElement::SubmodelElementList(_));
+            find_element_mut(element.children_mut()?, rest, in_list)
+        }
+    }
+}
+
+/// The segments of the keys following the submodel key.
+fn key_segments(keys: &[Key]) -> Vec<PathSegment<'_>> {
+    keys.iter()
+        .map(|key| match key.value().parse() {
+            Ok(index) if key.value().bytes().all(|b| b.is_ascii_digit()) => {
+                PathSegment::Index(index)
+            }
+            _ => PathSegment::IdShort(key.value()),
+        })
+        .collect()
+}
+
+impl Submodel {
+    pub fn id(&self) -> &Identifier {
+        &self.identifiable.id
+    }
+
+    /// A copy of this submodel template as an instance with the given id, see
+    /// [`crate::part_1::v3_1::id_generator`] for minting the id.
+    pub fn instantiate(&self, id: Identifier) -> Submodel {
+        let mut instance = self.clone();
+        instance.identifiable.id = id;
+        instance.kind = Some(ModellingKind::Instance);
+        instance
+    }
+
+    /// The element at an idShort path like `Maintenance.Counters[0]`.
+    pub fn element(&self, id_short_path: &str) -> Option<&SubmodelElement> {
+        find_element(
+            self.submodel_elements.as_deref()?,
+            &parse_id_short_path(id_short_path)?,
+            false,
+        )
+    }
+
+    /// All elements at an idShort path, where `[]` stands for any element of a list, i.e.
+    /// `Markings[].MarkingName`.
+    pub fn elements_at(&self, id_short_path: &str) -> Vec<&SubmodelElement> {
+        let mut found = Vec::new();
+
+        if let Some(segments) = parse_id_short_path(id_short_path) {
+            find_elements(
+                self.submodel_elements.as_deref().unwrap_or_default(),
+                &segments,
+                false,
+                &mut found,
+            );
+        }
+
+        found
+    }
+
+    pub fn element_mut(&mut self, id_short_path: &str) -> Option<&mut SubmodelElement> {
+        let segments = parse_id_short_path(id_short_path)?;
+
+        find_element_mut(self.submodel_elements.as_deref_mut()?, &segments, false)
+    }
+
+    /// The element referred to by the keys following the `Submodel` key of a model reference.
+    pub fn element_by_keys(&self, keys: &[Key]) -> Option<&SubmodelElement> {
+        find_element(
+            self.submodel_elements.as_deref()?,
+            &key_segments(keys),
+            false,
+        )
+    }
+
+    pub fn element_by_keys_mut(&mut self, keys: &[Key]) -> Option<&mut SubmodelElement> {
+        let segments = key_segments(keys);
+
+        find_element_mut(self.submodel_elements.as_deref_mut()?, &segments, false)
+    }
+
+    /// Model reference to the submodel itself.
+    pub fn reference(&self) -> ModelReference {
+        ModelReference::new(ReferenceInner::new(Key::Submodel(self.id().to_string())))
+    }
+
+    /// Model reference to the element at an idShort path, the key types follow the elements.
+    pub fn element_reference(&self, id_short_path: &str) -> Option<ModelReference> {
+        let segments = parse_id_short_path(id_short_path)?;
+        let mut keys = vec![Key::Submodel(self.id().to_string())];
+
+        for length in 1..=segments.len() {
+            let element = find_element(
+                self.submodel_elements.as_deref()?,
+                &segments[..length],
+                false,
+            )?;
+            let key_type = KeyTypes::from_str(&element.to_string()).ok()?;
+            let value = match segments[length - 1] {
+                PathSegment::IdShort(id_short) => id_short.to_string(),
+                PathSegment::Index(index) => index.to_string(),
+                PathSegment::AnyIndex => return None,
+            };
+
+            keys.push(Key::new(key_type, value));
+        }
+
+        Some(ModelReference::new(ReferenceInner::from_vec(keys)))
+    }
+
+    /// idShort paths of all elements, depth first.
+    pub fn id_short_paths(&self) -> Vec<String> {
+        self.elements_by_path()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// All elements with their idShort paths, depth first.
+    pub fn elements_by_path(&self) -> Vec<(String, &SubmodelElement)> {
+        fn collect<'a>(
+            elements: &'a [SubmodelElement],
+            prefix: Option<&str>,
+            in_list: bool,
+            paths: &mut Vec<(String, &'a SubmodelElement)>,
+        ) {
+            for (index, element) in elements.iter().enumerate() {
+                let path = match (prefix, in_list, element.id_short()) {
+                    (Some(prefix), true, _) => format!("{prefix}[{index}]"),
+                    (Some(prefix), false, Some(id_short)) => format!("{prefix}.{id_short}"),
+                    (None, _, Some(id_short)) => id_short.to_string(),
+                    _ => continue,
+                };
+
+                paths.push((path.clone(), element));
+
+                if let Some(children) = element.children() {
+                    let is_list = matches!(element, SubmodelElement::SubmodelElementList(_));
+                    collect(children, Some(&path), is_list, paths);
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        collect(
+            self.submodel_elements.as_deref().unwrap_or_default(),
+            None,
+            false,
+            &mut paths,
+        );
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submodel() -> Submodel {
+        let json = r#"
+        {
+          "modelType": "Submodel",
+          "id": "urn:x:sm:maintenance",
+          "submodelElements": [
+            {
+              "modelType": "SubmodelElementCollection",
+              "idShort": "Maintenance",
+              "value": [
+                {
+                  "modelType": "SubmodelElementList",
+                  "idShort": "Counters",
+                  "typeValueListElement": "Property",
+                  "value": [
+                    { "modelType": "Property", "valueType": "xs:string", "value": "a" },
+                    { "modelType": "Property", "valueType": "xs:string", "value": "b" }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+        "#;
+
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn id_short_path() {
+        let mut submodel = submodel();
+
+        assert_eq!(
+            submodel.id_short_paths(),
+            vec![
+                "Maintenance",
+                "Maintenance.Counters",
+                "Maintenance.Counters[0]",
+                "Maintenance.Counters[1]"
+            ]
+        );
+        assert!(matches!(
+            submodel.element("Maintenance.Counters[1]"),
+            Some(SubmodelElement::Property(_))
+        ));
+        assert!(submodel.element("Maintenance.Counters.0").is_none());
+        assert!(submodel.element("Maintenance[0]").is_none());
+        assert!(submodel.element_mut("Maintenance.Counters[0]").is_some());
+    }
+
+    #[test]
+    fn any_index() {
+        let submodel = submodel();
+
+        assert_eq!(
+            submodel.elements_at("Maintenance.Counters[]"),
+            vec![
+                submodel.element("Maintenance.Counters[0]").unwrap(),
+                submodel.element("Maintenance.Counters[1]").unwrap()
+            ]
+        );
+        assert_eq!(submodel.elements_at("Maintenance.Counters[1]").len(), 1);
+        assert!(submodel.elements_at("Maintenance[]").is_empty());
+        assert!(submodel.element("Maintenance.Counters[]").is_none());
+        assert!(
+            submodel
+                .element_reference("Maintenance.Counters[]")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn element_reference() {
+        let submodel = submodel();
+        let reference = submodel
+            .element_reference("Maintenance.Counters[1]")
+            .unwrap();
+
+        assert_eq!(
+            reference.to_string(),
+            "[ModelRef](Submodel)urn:x:sm:maintenance, (SubmodelElementCollection)Maintenance, \
+             (SubmodelElementList)Counters, (Property)1"
+        );
+        assert_eq!(
+            submodel.element_by_keys(&reference.keys[1..]),
+            submodel.element("Maintenance.Counters[1]")
+        );
+    }
+}
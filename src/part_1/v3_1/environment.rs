@@ -1,6 +1,14 @@
+use crate::part_1::v3_1::attributes::identifiable::Identifiable;
 use crate::part_1::v3_1::concept_description::ConceptDescription;
 use crate::part_1::v3_1::core::{AssetAdministrationShell, Submodel};
+use crate::part_1::v3_1::key::Key;
+use crate::part_1::v3_1::primitives::Identifier;
+use crate::part_1::v3_1::reference::Reference;
+use crate::part_1::v3_1::references::{VisitReferencesMut, reference_keys};
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Environment {
@@ -16,6 +24,406 @@ pub struct Environment {
     pub concept_descriptions: Option<Vec<ConceptDescription>>,
 }
 
+/// What [`Environment::extract_shell`] and [`Environment::extract_submodel`] take along besides
+/// the submodels and concept descriptions referred to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ExtractOptions {
+    /// The shells of the assets of `Entity` elements and of `derivedFrom`, with all they refer to.
+    pub related_shells: bool,
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RenameError {
+    #[error("The identifier {0} is used more than once after renaming")]
+    Conflict(String),
+
+    #[error("{id} can't be renamed: {reason}")]
+    Invalid { id: String, reason: String },
+}
+
+/// A value changed by [`Environment::rename_identifier`] and its variants.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IdentifierChange {
+    /// The id of the shell, submodel or concept description changed, after renaming.
+    pub identifiable: String,
+    /// JSON pointer to the changed value within the identifiable, i.e. `/semanticId/keys/0/value`.
+    pub pointer: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// An element of the environment a model reference refers to.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(untagged)]
+pub enum Referred<'a> {
+    AssetAdministrationShell(&'a AssetAdministrationShell),
+    Submodel(&'a Submodel),
+    ConceptDescription(&'a ConceptDescription),
+    SubmodelElement(&'a SubmodelElement),
+}
+
+impl Referred<'_> {
+    pub fn semantic_id(&self) -> Option<&Reference> {
+        match self {
+            Referred::Submodel(submodel) => submodel.semantics.semantic_id.as_ref(),
+            Referred::SubmodelElement(element) => element.semantics().semantic_id.as_ref(),
+            Referred::AssetAdministrationShell(_) | Referred::ConceptDescription(_) => None,
+        }
+    }
+}
+
+impl Environment {
+    pub fn asset_administration_shell(&self, id: &str) -> Option<&AssetAdministrationShell> {
+        self.asset_administration_shells
+            .iter()
+            .flatten()
+            .find(|shell| shell.identifiable.id.as_ref() == id)
+    }
+
+    pub fn submodel(&self, id: &str) -> Option<&Submodel> {
+        self.submodels
+            .iter()
+            .flatten()
+            .find(|submodel| submodel.identifiable.id.as_ref() == id)
+    }
+
+    pub fn submodel_mut(&mut self, id: &str) -> Option<&mut Submodel> {
+        self.submodels
+            .iter_mut()
+            .flatten()
+            .find(|submodel| submodel.identifiable.id.as_ref() == id)
+    }
+
+    pub fn concept_description(&self, id: &str) -> Option<&ConceptDescription> {
+        self.concept_descriptions
+            .iter()
+            .flatten()
+            .find(|description| description.identifiable.id.as_ref() == id)
+    }
+
+    /// Whether a shell, submodel or concept description has the id.
+    pub fn contains_identifier(&self, id: &str) -> bool {
+        self.asset_administration_shell(id).is_some()
+            || self.submodel(id).is_some()
+            || self.concept_description(id).is_some()
+    }
+
+    /// Resolves the keys of a model reference.
+    /// Keys following a `Submodel` key are resolved as elements, list elements by index.
+    pub fn resolve(&self, keys: &[Key]) -> Option<Referred<'_>> {
+        let (first, rest) = keys.split_first()?;
+
+        match first {
+            Key::AssetAdministrationShell(id) if rest.is_empty() => self
+                .asset_administration_shell(id)
+                .map(Referred::AssetAdministrationShell),
+            Key::ConceptDescription(id) if rest.is_empty() => self
+                .concept_description(id)
+                .map(Referred::ConceptDescription),
+            Key::Submodel(id) if rest.is_empty() => self.submodel(id).map(Referred::Submodel),
+            Key::Submodel(id) => self
+                .submodel(id)?
+                .element_by_keys(rest)
+                .map(Referred::SubmodelElement),
+            _ => None,
+        }
+    }
+
+    /// The element a model reference of the form `(Submodel)id, (…)idShort, …` refers to.
+    pub fn element(&self, keys: &[Key]) -> Option<&SubmodelElement> {
+        match self.resolve(keys)? {
+            Referred::SubmodelElement(element) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// The element a model reference of the form `(Submodel)id, (…)idShort, …` refers to.
+    pub fn element_mut(&mut self, keys: &[Key]) -> Option<&mut SubmodelElement> {
+        match keys.split_first()? {
+            (Key::Submodel(id), rest) if !rest.is_empty() => {
+                self.submodel_mut(id)?.element_by_keys_mut(rest)
+            }
+            _ => None,
+        }
+    }
+
+    /// A new environment with the shell and everything it refers to, `None` for an unknown id.
+    /// Submodels and concept descriptions are taken along transitively, i.e. the concept
+    /// descriptions of semantic ids and the ones these are a case of.
+    pub fn extract_shell(&self, id: &str, options: ExtractOptions) -> Option<Environment> {
+        self.asset_administration_shell(id)?;
+        Some(self.extract(Key::AssetAdministrationShell(id.to_string()), options))
+    }
+
+    /// A new environment with the submodel and everything it refers to, see
+    /// [`Environment::extract_shell`].
+    pub fn extract_submodel(&self, id: &str, options: ExtractOptions) -> Option<Environment> {
+        self.submodel(id)?;
+        Some(self.extract(Key::Submodel(id.to_string()), options))
+    }
+
+    fn extract(&self, root: Key, options: ExtractOptions) -> Environment {
+        let mut included = HashSet::new();
+        let mut pending = vec![root];
+
+        while let Some(key) = pending.pop() {
+            if included.contains(&key) {
+                continue;
+            }
+
+            let references = match &key {
+                Key::AssetAdministrationShell(id) => {
+                    self.asset_administration_shell(id).map(reference_keys)
+                }
+                Key::Submodel(id) => {
+                    let submodel = self.submodel(id);
+                    if options.related_shells {
+                        let elements =
+                            submodel.and_then(|submodel| submodel.submodel_elements.as_deref());
+                        pending.extend(self.entity_shells(elements.unwrap_or_default()));
+                    }
+                    submodel.map(reference_keys)
+                }
+                Key::ConceptDescription(id) => self.concept_description(id).map(reference_keys),
+                _ => continue,
+            };
+
+            pending.extend(
+                references
+                    .iter()
+                    .flatten()
+                    .flat_map(|keys| self.referred_identifiables(keys, options)),
+            );
+
+            included.insert(key);
+        }
+
+        let pick = |key: &Key| included.contains(key);
+        Environment {
+            asset_administration_shells: picked(&self.asset_administration_shells, |shell| {
+                pick(&Key::AssetAdministrationShell(
+                    shell.identifiable.id.to_string(),
+                ))
+            }),
+            submodels: picked(&self.submodels, |submodel| {
+                pick(&Key::Submodel(submodel.identifiable.id.to_string()))
+            }),
+            concept_descriptions: picked(&self.concept_descriptions, |description| {
+                pick(&Key::ConceptDescription(
+                    description.identifiable.id.to_string(),
+                ))
+            }),
+        }
+    }
+
+    /// Changes the id of an identifiable from `old` to `new` and every key referring to it, i.e.
+    /// the submodels of shells, semantic ids, reference elements, relationships and the
+    /// `externalSubjectId` of specific asset ids. Keys of external identifiers are renamed as
+    /// well, so `old` does not need to be part of the environment.
+    ///
+    /// Nothing is changed if an error is returned.
+    pub fn rename_identifier(
+        &mut self,
+        old: &str,
+        new: &str,
+    ) -> Result<Vec<IdentifierChange>, RenameError> {
+        self.rename_with(|id| (id == old).then(|| new.to_string()))
+    }
+
+    /// Renames all identifiers in `renames` at once, see [`Environment::rename_identifier`].
+    /// Ids may be swapped, as every value is renamed at most once.
+    pub fn rename_identifiers(
+        &mut self,
+        renames: &HashMap<String, String>,
+    ) -> Result<Vec<IdentifierChange>, RenameError> {
+        self.rename_with(|id| renames.get(id).cloned())
+    }
+
+    /// Replaces the prefix `old` of identifiers by `new`, i.e. `urn:example:` by
+    /// `https://example.com/`, see [`Environment::rename_identifier`].
+    pub fn rename_identifier_prefix(
+        &mut self,
+        old: &str,
+        new: &str,
+    ) -> Result<Vec<IdentifierChange>, RenameError> {
+        self.rename_with(|id| id.strip_prefix(old).map(|rest| format!("{new}{rest}")))
+    }
+
+    fn rename_with(
+        &mut self,
+        rename: impl Fn(&str) -> Option<String>,
+    ) -> Result<Vec<IdentifierChange>, RenameError> {
+        let shells = self.asset_administration_shells.iter().flatten();
+        let submodels = self.submodels.iter().flatten();
+        let descriptions = self.concept_descriptions.iter().flatten();
+        let ids = shells
+            .map(|shell| shell.identifiable.id.to_string())
+            .chain(submodels.map(|submodel| submodel.identifiable.id.to_string()))
+            .chain(descriptions.map(|description| description.identifiable.id.to_string()));
+
+        let mut seen = HashSet::new();
+        for id in ids {
+            let id = match rename(&id) {
+                Some(new) => {
+                    Identifier::try_from(new.as_str()).map_err(|error| RenameError::Invalid {
+                        id: new.clone(),
+                        reason: error.to_string(),
+                    })?;
+                    new
+                }
+                None => id,
+            };
+            if !seen.insert(id.clone()) {
+                return Err(RenameError::Conflict(id));
+            }
+        }
+
+        let mut changes = Vec::new();
+        for shell in self.asset_administration_shells.iter_mut().flatten() {
+            rename_in(
+                shell,
+                |shell| &mut shell.identifiable,
+                &rename,
+                &mut changes,
+            );
+        }
+        for submodel in self.submodels.iter_mut().flatten() {
+            rename_in(
+                submodel,
+                |submodel| &mut submodel.identifiable,
+                &rename,
+                &mut changes,
+            );
+        }
+        for description in self.concept_descriptions.iter_mut().flatten() {
+            rename_in(
+                description,
+                |description| &mut description.identifiable,
+                &rename,
+                &mut changes,
+            );
+        }
+
+        Ok(changes)
+    }
+
+    /// The identifiables of this environment a reference leads to: the submodel or shell it
+    /// starts with and concept descriptions named by any of its keys, i.e. a semantic id.
+    fn referred_identifiables(&self, keys: &[Key], options: ExtractOptions) -> Vec<Key> {
+        let mut referred = keys
+            .iter()
+            .filter(|key| self.concept_description(key.value()).is_some())
+            .map(|key| Key::ConceptDescription(key.value().clone()))
+            .collect::<Vec<_>>();
+
+        match keys.first() {
+            Some(Key::Submodel(id)) if self.submodel(id).is_some() => {
+                referred.push(Key::Submodel(id.clone()));
+            }
+            Some(Key::AssetAdministrationShell(id))
+                if options.related_shells && self.asset_administration_shell(id).is_some() =>
+            {
+                referred.push(Key::AssetAdministrationShell(id.clone()));
+            }
+            _ => {}
+        }
+
+        referred
+    }
+
+    /// The shells of the assets `Entity` elements stand for, nested entities included.
+    fn entity_shells(&self, elements: &[SubmodelElement]) -> Vec<Key> {
+        let mut shells = Vec::new();
+
+        for element in elements {
+            if let SubmodelElement::Entity(entity) = element {
+                let asset = entity.inner().global_asset_id.as_ref();
+                shells.extend(
+                    self.asset_administration_shells
+                        .iter()
+                        .flatten()
+                        .filter(|shell| {
+                            asset.is_some_and(|asset| {
+                                shell.asset_information.inner().global_asset_id.as_ref()
+                                    == Some(asset)
+                            })
+                        })
+                        .map(|shell| {
+                            Key::AssetAdministrationShell(shell.identifiable.id.to_string())
+                        }),
+                );
+            }
+            if let Some(children) = element.children() {
+                shells.extend(self.entity_shells(children));
+            }
+        }
+
+        shells
+    }
+}
+
+/// The clones of the items `pick` accepts, `None` if there are none.
+fn picked<T: Clone>(items: &Option<Vec<T>>, pick: impl Fn(&T) -> bool) -> Option<Vec<T>> {
+    let picked = items
+        .iter()
+        .flatten()
+        .filter(|item| pick(item))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    (!picked.is_empty()).then_some(picked)
+}
+
+/// Renames the id of an identifiable and the keys of its references standing for identifiers.
+/// New ids have been validated by the caller.
+fn rename_in<T: VisitReferencesMut>(
+    item: &mut T,
+    identifiable: fn(&mut T) -> &mut Identifiable,
+    rename: &impl Fn(&str) -> Option<String>,
+    changes: &mut Vec<IdentifierChange>,
+) {
+    let mut found = Vec::new();
+
+    let id = &mut identifiable(item).id;
+    if let Some(new) = rename(id) {
+        found.push(("/id".to_string(), id.to_string(), new.clone()));
+        *id = Identifier::try_from(new).expect("Renamed ids are validated before renaming");
+    }
+
+    item.visit_references_mut("", &mut |pointer, reference| {
+        for (index, key) in reference.keys.iter_mut().enumerate() {
+            let identifies = matches!(
+                key,
+                Key::AssetAdministrationShell(_)
+                    | Key::Submodel(_)
+                    | Key::ConceptDescription(_)
+                    | Key::Identifiable(_)
+                    | Key::GlobalReference(_)
+            );
+            if identifies && let Some(new) = rename(key.value()) {
+                found.push((
+                    format!("{pointer}/keys/{index}/value"),
+                    key.value().clone(),
+                    new.clone(),
+                ));
+                *key.value_mut() = new;
+            }
+        }
+    });
+
+    let id = identifiable(item).id.to_string();
+    changes.extend(
+        found
+            .into_iter()
+            .map(|(pointer, old, new)| IdentifierChange {
+                identifiable: id.clone(),
+                pointer,
+                old,
+                new,
+            }),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,4 +436,239 @@ mod tests {
 
         println!("{:#?}", env);
     }
+
+    fn plant() -> Environment {
+        let external = |id: &str| serde_json::json!({ "type": "ExternalReference", "keys": [{ "type": "GlobalReference", "value": id }] });
+        let submodel_reference = |id: &str| serde_json::json!({ "type": "ModelReference", "keys": [{ "type": "Submodel", "value": id }] });
+        let shell = |id: &str, submodel: &str| {
+            serde_json::json!({
+                "id": id,
+                "assetInformation": { "assetKind": "Instance", "globalAssetId": format!("{id}/asset") },
+                "submodels": [submodel_reference(submodel)]
+            })
+        };
+
+        serde_json::from_value(serde_json::json!({
+            "assetAdministrationShells": [
+                shell("urn:aas:machine", "urn:sm:bom"),
+                shell("urn:aas:motor", "urn:sm:motor-data"),
+                shell("urn:aas:other", "urn:sm:other")
+            ],
+            "submodels": [
+                {
+                    "modelType": "Submodel",
+                    "id": "urn:sm:bom",
+                    "semanticId": external("urn:cd:bom"),
+                    "submodelElements": [{
+                        "modelType": "Entity",
+                        "idShort": "Motor",
+                        "entityType": "SelfManagedEntity",
+                        "globalAssetId": "urn:aas:motor/asset"
+                    }, {
+                        "modelType": "ReferenceElement",
+                        "idShort": "Manual",
+                        "value": {
+                            "type": "ModelReference",
+                            "keys": [
+                                { "type": "Submodel", "value": "urn:sm:documentation" },
+                                { "type": "File", "value": "Manual" }
+                            ]
+                        }
+                    }]
+                },
+                {
+                    "modelType": "Submodel",
+                    "id": "urn:sm:documentation",
+                    "submodelElements": [{
+                        "modelType": "File",
+                        "idShort": "Manual",
+                        "contentType": "application/pdf",
+                        "supplementalSemanticIds": [external("urn:cd:manual")]
+                    }]
+                },
+                {
+                    "modelType": "Submodel",
+                    "id": "urn:sm:motor-data",
+                    "submodelElements": [{
+                        "modelType": "Property",
+                        "idShort": "MaxPower",
+                        "valueType": "xs:int",
+                        "semanticId": external("urn:cd:max-power")
+                    }]
+                },
+                { "modelType": "Submodel", "id": "urn:sm:other", "semanticId": external("urn:cd:other") }
+            ],
+            "conceptDescriptions": [
+                { "id": "urn:cd:bom", "isCaseOf": external("urn:cd:structure") },
+                { "id": "urn:cd:structure" },
+                { "id": "urn:cd:manual" },
+                {
+                    "id": "urn:cd:max-power",
+                    "embeddedDataSpecifications": [{
+                        "dataSpecification": external("https://admin-shell.io/DataSpecificationTemplates/DataSpecificationIec61360/3"),
+                        "dataSpecificationContent": {
+                            "modelType": "DataSpecificationIec61360",
+                            "preferredName": [{ "language": "en", "text": "max power" }],
+                            "unitId": external("urn:cd:watt")
+                        }
+                    }]
+                },
+                { "id": "urn:cd:watt" },
+                { "id": "urn:cd:other" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn ids(environment: &Environment) -> Vec<String> {
+        let shells = environment.asset_administration_shells.iter().flatten();
+        let submodels = environment.submodels.iter().flatten();
+        let descriptions = environment.concept_descriptions.iter().flatten();
+
+        shells
+            .map(|shell| shell.identifiable.id.to_string())
+            .chain(submodels.map(|submodel| submodel.identifiable.id.to_string()))
+            .chain(descriptions.map(|description| description.identifiable.id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn extract_shell() {
+        let plant = plant();
+
+        let machine = plant
+            .extract_shell("urn:aas:machine", ExtractOptions::default())
+            .unwrap();
+        assert_eq!(
+            ids(&machine),
+            [
+                "urn:aas:machine",
+                "urn:sm:bom",
+                "urn:sm:documentation",
+                "urn:cd:bom",
+                "urn:cd:structure",
+                "urn:cd:manual"
+            ]
+        );
+
+        let related = plant
+            .extract_shell(
+                "urn:aas:machine",
+                ExtractOptions {
+                    related_shells: true,
+                },
+            )
+            .unwrap();
+        assert_eq!(ids(&related).len(), 10);
+        assert!(related.submodel("urn:sm:motor-data").is_some());
+        assert!(related.concept_description("urn:cd:watt").is_some());
+        assert!(related.submodel("urn:sm:other").is_none());
+
+        assert!(
+            plant
+                .extract_shell("urn:aas:unknown", ExtractOptions::default())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn extract_submodel() {
+        let plant = plant();
+
+        let motor = plant
+            .extract_submodel("urn:sm:motor-data", ExtractOptions::default())
+            .unwrap();
+        assert_eq!(
+            ids(&motor),
+            ["urn:sm:motor-data", "urn:cd:max-power", "urn:cd:watt"]
+        );
+        assert!(motor.asset_administration_shells.is_none());
+    }
+
+    #[test]
+    fn rename_identifier() {
+        let mut plant = plant();
+
+        let changes = plant
+            .rename_identifier(
+                "urn:sm:documentation",
+                "https://example.com/sm/documentation",
+            )
+            .unwrap();
+        assert_eq!(
+            changes,
+            [
+                IdentifierChange {
+                    identifiable: "urn:sm:bom".to_string(),
+                    pointer: "/submodelElements/1/value/keys/0/value".to_string(),
+                    old: "urn:sm:documentation".to_string(),
+                    new: "https://example.com/sm/documentation".to_string(),
+                },
+                IdentifierChange {
+                    identifiable: "https://example.com/sm/documentation".to_string(),
+                    pointer: "/id".to_string(),
+                    old: "urn:sm:documentation".to_string(),
+                    new: "https://example.com/sm/documentation".to_string(),
+                }
+            ]
+        );
+        assert!(
+            plant
+                .resolve(&[
+                    Key::Submodel("https://example.com/sm/documentation".to_string()),
+                    Key::File("Manual".to_string())
+                ])
+                .is_some()
+        );
+
+        let swap = HashMap::from([
+            ("urn:aas:machine".to_string(), "urn:aas:motor".to_string()),
+            ("urn:aas:motor".to_string(), "urn:aas:machine".to_string()),
+        ]);
+        assert_eq!(plant.rename_identifiers(&swap).unwrap().len(), 2);
+        assert_eq!(
+            plant.asset_administration_shells.as_ref().unwrap()[0]
+                .identifiable
+                .id
+                .to_string(),
+            "urn:aas:motor"
+        );
+    }
+
+    #[test]
+    fn rename_identifier_prefix() {
+        let mut plant = plant();
+
+        let changes = plant
+            .rename_identifier_prefix("urn:cd:", "https://example.com/cd/")
+            .unwrap();
+        assert_eq!(changes.len(), 12);
+        assert!(changes.iter().any(|change| {
+            change.identifiable == "https://example.com/cd/max-power"
+                && change.pointer
+                    == "/embeddedDataSpecifications/0/dataSpecificationContent/unitId/keys/0/value"
+        }));
+
+        let bom = plant
+            .concept_description("https://example.com/cd/bom")
+            .unwrap();
+        let json = serde_json::to_value(bom).unwrap();
+        assert_eq!(
+            json["isCaseOf"]["keys"][0]["value"],
+            "https://example.com/cd/structure"
+        );
+        assert!(plant.concept_description("urn:cd:bom").is_none());
+    }
+
+    #[test]
+    fn rename_conflict() {
+        let mut plant = plant();
+        let before = plant.clone();
+
+        assert_eq!(
+            plant.rename_identifier("urn:sm:other", "urn:sm:bom"),
+            Err(RenameError::Conflict("urn:sm:bom".to_string()))
+        );
+        assert_eq!(plant, before);
+    }
 }
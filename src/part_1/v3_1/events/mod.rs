@@ -0,0 +1,551 @@
+//! Emission of `BasicEventElement` events.
+//!
+//! An [`EventEmitter`] builds [`EventPayload`] messages for event elements of an
+//! [`Environment`] and hands them to an [`EventSink`]. Only events with direction `output` and
+//! state `on` are emitted. `minInterval` throttles emissions, `maxInterval` is served by
+//! [`EventEmitter::emit_due`]. Every emission updates `lastUpdate` of the event element.
+
+use crate::part_1::v3_1::primitives::{BlobType, DateTimeUTC, Uri};
+use crate::part_1::v3_1::reference::{ExternalReference, ModelReference};
+use crate::part_1::v3_1::submodel_elements::{
+    BasicEventElement, Direction, EventPayload, StateOfEvent, SubmodelElement,
+};
+use crate::part_1::v3_1::{Environment, Referred};
+use crate::utilities::parse_xs_duration;
+use chrono::{TimeDelta, Utc};
+use std::sync::mpsc::Sender;
+use thiserror::Error;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum EventError {
+    #[error("No BasicEventElement found for {0}")]
+    UnknownEvent(String),
+
+    #[error("The event {0} does not have the direction output")]
+    NotOutput(String),
+
+    #[error("The event {0} does not have the direction input")]
+    NotInput(String),
+
+    #[error("Invalid interval {0}, expected a xs:duration")]
+    InvalidInterval(String),
+
+    #[error("Failed to publish the event: {0}")]
+    Sink(String),
+
+    #[error("The payload can not be applied: {0}")]
+    InvalidPayload(String),
+
+    #[error("Payloads can only be applied to a Property, Blob or File, not to {0}")]
+    UnsupportedObserved(String),
+}
+
+/// Receiver of emitted events, i.e. a message broker connection.
+/// Implemented for every `Fn(&EventPayload) -> Result<(), EventError>` and for channels.
+pub trait EventSink: Send + Sync {
+    fn publish(&self, event: &EventPayload) -> Result<(), EventError>;
+}
+
+impl<F> EventSink for F
+where
+    F: Fn(&EventPayload) -> Result<(), EventError> + Send + Sync,
+{
+    fn publish(&self, event: &EventPayload) -> Result<(), EventError> {
+        self(event)
+    }
+}
+
+impl EventSink for Sender<EventPayload> {
+    fn publish(&self, event: &EventPayload) -> Result<(), EventError> {
+        self.send(event.clone())
+            .map_err(|err| EventError::Sink(err.to_string()))
+    }
+}
+
+/// Outcome of [`EventEmitter::emit`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum Emission {
+    Sent(Box<EventPayload>),
+
+    /// The state of the event is `off`.
+    Disabled,
+
+    /// `minInterval` did not elapse since the last update.
+    Throttled {
+        next: DateTimeUTC,
+    },
+}
+
+/// Outcome of [`EventEmitter::emit_due`].
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DueEmissions {
+    pub sent: Vec<EventPayload>,
+
+    /// Event elements that could not be emitted, e.g. because of an invalid `maxInterval`.
+    /// They do not keep the other events from being emitted.
+    pub failed: Vec<(ModelReference, EventError)>,
+}
+
+pub struct EventEmitter<S> {
+    sink: S,
+}
+
+impl<S: EventSink> EventEmitter<S> {
+    pub fn new(sink: S) -> Self {
+        Self { sink }
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Emits the event element `source` now.
+    pub fn emit(
+        &self,
+        environment: &mut Environment,
+        source: &ModelReference,
+        payload: Option<BlobType>,
+    ) -> Result<Emission, EventError> {
+        self.emit_at(environment, source, None, payload, Utc::now())
+    }
+
+    /// Emits the event element `source` at `time_stamp`, caused by `subject_id`.
+    pub fn emit_at(
+        &self,
+        environment: &mut Environment,
+        source: &ModelReference,
+        subject_id: Option<ExternalReference>,
+        payload: Option<BlobType>,
+        time_stamp: DateTimeUTC,
+    ) -> Result<Emission, EventError> {
+        let event = event_element(environment, source)?;
+
+        if event.direction != Direction::Output {
+            return Err(EventError::NotOutput(source.to_string()));
+        }
+        if event.state == StateOfEvent::Off {
+            return Ok(Emission::Disabled);
+        }
+
+        if let (Some(min_interval), Some(last_update)) =
+            (interval(&event.min_interval)?, event.last_update)
+        {
+            let next = last_update + min_interval;
+            if time_stamp < next {
+                return Ok(Emission::Throttled { next });
+            }
+        }
+
+        let observable_semantic_id = environment
+            .resolve(&event.observed.keys)
+            .and_then(|observed| observed.semantic_id().cloned());
+
+        let event = EventPayload {
+            source: source.clone(),
+            source_semantic_id: event.submodel_element_fields.semantics.semantic_id.clone(),
+            observable_reference: event.observed.clone(),
+            observable_semantic_id,
+            topic: event.message_topic.clone(),
+            subject_id,
+            time_stamp,
+            payload,
+        };
+
+        self.sink.publish(&event)?;
+
+        if let Some(SubmodelElement::BasicEventElement(element)) =
+            environment.element_mut(&source.keys)
+        {
+            element.last_update = Some(time_stamp);
+        }
+
+        Ok(Emission::Sent(Box::new(event)))
+    }
+
+    /// Emits all output events whose `maxInterval` elapsed since their last update.
+    /// The payload is the JSON serialization of the observed element.
+    pub fn emit_due(&self, environment: &mut Environment, time_stamp: DateTimeUTC) -> DueEmissions {
+        let mut emissions = DueEmissions::default();
+        let mut due = Vec::new();
+
+        for source in event_elements(environment) {
+            let event = match event_element(environment, &source) {
+                Ok(event) => event,
+                Err(err) => {
+                    emissions.failed.push((source, err));
+                    continue;
+                }
+            };
+
+            let max_interval = match interval(&event.max_interval) {
+                Ok(Some(max_interval)) => max_interval,
+                Ok(None) => continue,
+                Err(err) => {
+                    emissions.failed.push((source, err));
+                    continue;
+                }
+            };
+            let elapsed = event
+                .last_update
+                .is_none_or(|last_update| last_update + max_interval <= time_stamp);
+
+            if event.direction == Direction::Output && event.state == StateOfEvent::On && elapsed {
+                let payload = environment
+                    .resolve(&event.observed.keys)
+                    .and_then(|observed| serde_json::to_vec(&observed).ok());
+
+                due.push((source, payload));
+            }
+        }
+
+        for (source, payload) in due {
+            match self.emit_at(environment, &source, None, payload, time_stamp) {
+                Ok(Emission::Sent(event)) => emissions.sent.push(*event),
+                Ok(_) => {}
+                Err(err) => emissions.failed.push((source, err)),
+            }
+        }
+
+        emissions
+    }
+}
+
+/// Accepts an event received for an input event element.
+///
+/// The payload is applied to the observed element: it is parsed by the value type of a
+/// `Property`, taken as is by a `Blob` and as path or URI by a `File`. Afterwards `lastUpdate`
+/// is updated. Returns false if the state of the event is `off`.
+pub fn receive(environment: &mut Environment, event: &EventPayload) -> Result<bool, EventError> {
+    let element = event_element(environment, &event.source)?;
+
+    if element.direction != Direction::Input {
+        return Err(EventError::NotInput(event.source.to_string()));
+    }
+    if element.state == StateOfEvent::Off {
+        return Ok(false);
+    }
+
+    if let Some(payload) = &event.payload {
+        let observed = element.observed.clone();
+        let text = || {
+            String::from_utf8(payload.clone())
+                .map_err(|_| EventError::InvalidPayload("payload is not UTF-8".into()))
+        };
+
+        match environment.element_mut(&observed.keys) {
+            Some(SubmodelElement::Property(property)) => property
+                .value
+                .set_lexical(&text()?)
+                .map_err(|err| EventError::InvalidPayload(err.to_string()))?,
+            Some(SubmodelElement::Blob(blob)) => blob.value = Some(payload.clone()),
+            Some(SubmodelElement::File(file)) => {
+                file.value =
+                    Some(Uri::new(text()?.into_bytes()).map_err(|_| {
+                        EventError::InvalidPayload("payload is not a valid URI".into())
+                    })?)
+            }
+            _ => return Err(EventError::UnsupportedObserved(observed.to_string())),
+        }
+    }
+
+    if let Some(SubmodelElement::BasicEventElement(element)) =
+        environment.element_mut(&event.source.keys)
+    {
+        element.last_update = Some(event.time_stamp);
+    }
+
+    Ok(true)
+}
+
+fn event_element<'a>(
+    environment: &'a Environment,
+    source: &ModelReference,
+) -> Result<&'a BasicEventElement, EventError> {
+    match environment.resolve(&source.keys) {
+        Some(Referred::SubmodelElement(SubmodelElement::BasicEventElement(event))) => Ok(event),
+        _ => Err(EventError::UnknownEvent(source.to_string())),
+    }
+}
+
+/// References to all event elements of the environment.
+fn event_elements(environment: &Environment) -> Vec<ModelReference> {
+    environment
+        .submodels
+        .iter()
+        .flatten()
+        .flat_map(|submodel| {
+            submodel
+                .id_short_paths()
+                .into_iter()
+                .filter(|path| {
+                    matches!(
+                        submodel.element(path),
+                        Some(SubmodelElement::BasicEventElement(_))
+                    )
+                })
+                .filter_map(|path| submodel.element_reference(&path))
+        })
+        .collect()
+}
+
+fn interval(interval: &Option<String>) -> Result<Option<TimeDelta>, EventError> {
+    interval
+        .as_deref()
+        .map(|value| {
+            parse_xs_duration(value)
+                .and_then(|duration| TimeDelta::from_std(duration).ok())
+                .ok_or_else(|| EventError::InvalidInterval(value.to_string()))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::Reference;
+    use crate::part_1::v3_1::core::Submodel;
+    use std::str::FromStr;
+    use std::sync::mpsc;
+
+    fn environment() -> Environment {
+        let json = r#"
+        {
+          "submodels": [
+            {
+              "modelType": "Submodel",
+              "id": "urn:x:sm:condition",
+              "idShort": "ConditionMonitoring",
+              "submodelElements": [
+                {
+                  "modelType": "Property",
+                  "idShort": "Temperature",
+                  "semanticId": {
+                    "type": "ExternalReference",
+                    "keys": [{ "type": "GlobalReference", "value": "urn:x:temperature" }]
+                  },
+                  "valueType": "xs:string",
+                  "value": "21.5"
+                },
+                {
+                  "modelType": "BasicEventElement",
+                  "idShort": "TemperatureChanged",
+                  "observed": {
+                    "type": "ModelReference",
+                    "keys": [
+                      { "type": "Submodel", "value": "urn:x:sm:condition" },
+                      { "type": "Property", "value": "Temperature" }
+                    ]
+                  },
+                  "direction": "output",
+                  "state": "on",
+                  "messageTopic": "condition/temperature",
+                  "minInterval": "PT10S",
+                  "maxInterval": "PT1M"
+                },
+                {
+                  "modelType": "Property",
+                  "idShort": "Target",
+                  "valueType": "xs:double"
+                },
+                {
+                  "modelType": "BasicEventElement",
+                  "idShort": "Setpoint",
+                  "observed": {
+                    "type": "ModelReference",
+                    "keys": [
+                      { "type": "Submodel", "value": "urn:x:sm:condition" },
+                      { "type": "Property", "value": "Target" }
+                    ]
+                  },
+                  "direction": "input",
+                  "state": "on"
+                }
+              ]
+            }
+          ]
+        }
+        "#;
+
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn source(environment: &Environment, path: &str) -> ModelReference {
+        environment
+            .submodel("urn:x:sm:condition")
+            .and_then(|submodel: &Submodel| submodel.element_reference(path))
+            .unwrap()
+    }
+
+    fn time(value: &str) -> DateTimeUTC {
+        DateTimeUTC::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn emit() {
+        let mut environment = environment();
+        let source = source(&environment, "TemperatureChanged");
+        let (sender, receiver) = mpsc::channel();
+        let emitter = EventEmitter::new(sender);
+
+        let emission = emitter
+            .emit_at(
+                &mut environment,
+                &source,
+                None,
+                Some(b"21.5".to_vec()),
+                time("2025-01-01T10:00:00Z"),
+            )
+            .unwrap();
+
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(emission, Emission::Sent(Box::new(event.clone())));
+        assert_eq!(
+            event.observable_semantic_id,
+            Some(Reference::from_str("[ExternalRef](GlobalReference)urn:x:temperature").unwrap())
+        );
+        assert_eq!(event.topic.unwrap().as_ref(), "condition/temperature");
+        assert_eq!(
+            source.to_string(),
+            "[ModelRef](Submodel)urn:x:sm:condition, (BasicEventElement)TemperatureChanged"
+        );
+
+        let Some(Referred::SubmodelElement(SubmodelElement::BasicEventElement(element))) =
+            environment.resolve(&source.keys)
+        else {
+            panic!("event element not found");
+        };
+        assert_eq!(element.last_update, Some(time("2025-01-01T10:00:00Z")));
+    }
+
+    #[test]
+    fn throttle() {
+        let mut environment = environment();
+        let source = source(&environment, "TemperatureChanged");
+        let emitter = EventEmitter::new(|_: &EventPayload| Ok(()));
+
+        let mut emit = |time_stamp| {
+            emitter
+                .emit_at(&mut environment, &source, None, None, time(time_stamp))
+                .unwrap()
+        };
+
+        assert!(matches!(emit("2025-01-01T10:00:00Z"), Emission::Sent(_)));
+        assert_eq!(
+            emit("2025-01-01T10:00:05Z"),
+            Emission::Throttled {
+                next: time("2025-01-01T10:00:10Z")
+            }
+        );
+        assert!(matches!(emit("2025-01-01T10:00:10Z"), Emission::Sent(_)));
+    }
+
+    #[test]
+    fn emit_due() {
+        let mut environment = environment();
+        let emitter = EventEmitter::new(|_: &EventPayload| Ok(()));
+
+        let sent = emitter
+            .emit_due(&mut environment, time("2025-01-01T10:00:00Z"))
+            .sent;
+        assert_eq!(sent.len(), 1);
+        assert!(
+            String::from_utf8(sent[0].payload.clone().unwrap())
+                .unwrap()
+                .contains("21.5")
+        );
+
+        let not_due = emitter.emit_due(&mut environment, time("2025-01-01T10:00:30Z"));
+        assert_eq!(not_due, DueEmissions::default());
+
+        let due = emitter.emit_due(&mut environment, time("2025-01-01T10:01:00Z"));
+        assert_eq!(due.sent.len(), 1);
+        assert!(due.failed.is_empty());
+    }
+
+    #[test]
+    fn emit_due_invalid_interval() {
+        let mut environment = environment();
+        let source = source(&environment, "Setpoint");
+        if let Some(SubmodelElement::BasicEventElement(event)) =
+            environment.element_mut(&source.keys)
+        {
+            event.max_interval = Some("every minute".into());
+        }
+        let emitter = EventEmitter::new(|_: &EventPayload| Ok(()));
+
+        let emissions = emitter.emit_due(&mut environment, time("2025-01-01T10:00:00Z"));
+        assert_eq!(emissions.sent.len(), 1);
+        assert_eq!(
+            emissions.failed,
+            vec![(
+                source,
+                EventError::InvalidInterval("every minute".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn direction_and_state() {
+        let mut environment = environment();
+        let output = source(&environment, "TemperatureChanged");
+        let input = source(&environment, "Setpoint");
+        let emitter = EventEmitter::new(|_: &EventPayload| Ok(()));
+
+        assert_eq!(
+            emitter.emit(&mut environment, &input, None),
+            Err(EventError::NotOutput(input.to_string()))
+        );
+
+        if let Some(SubmodelElement::BasicEventElement(event)) =
+            environment.element_mut(&output.keys)
+        {
+            event.state = StateOfEvent::Off;
+        }
+        assert_eq!(
+            emitter.emit(&mut environment, &output, None),
+            Ok(Emission::Disabled)
+        );
+    }
+
+    #[test]
+    fn receive_input() {
+        let mut environment = environment();
+        let input = source(&environment, "Setpoint");
+        let event = EventPayload {
+            source: input.clone(),
+            source_semantic_id: None,
+            observable_reference: input,
+            observable_semantic_id: None,
+            topic: None,
+            subject_id: None,
+            time_stamp: time("2025-01-01T10:00:00Z"),
+            payload: Some(b"42.5".to_vec()),
+        };
+
+        assert_eq!(receive(&mut environment, &event), Ok(true));
+
+        let target = environment
+            .submodel("urn:x:sm:condition")
+            .and_then(|submodel| submodel.element("Target"));
+        let Some(SubmodelElement::Property(target)) = target else {
+            panic!("target not found");
+        };
+        assert_eq!(target.get::<f64>(), Ok(Some(42.5)));
+
+        let invalid = EventPayload {
+            payload: Some(b"warm".to_vec()),
+            ..event
+        };
+        assert!(matches!(
+            receive(&mut environment, &invalid),
+            Err(EventError::InvalidPayload(_))
+        ));
+    }
+}
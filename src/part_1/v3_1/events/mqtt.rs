@@ -0,0 +1,634 @@
+//! MQTT transport for events, enabled by the `mqtt` feature.
+//!
+//! The broker of an event element is read from its `messageBroker`, a reference to a `Property`
+//! holding the broker address, i.e. `mqtt://localhost:1883`. Events are exchanged on its
+//! `messageTopic`.
+//!
+//! The [`MqttPublisher`] is an [`EventSink`] sending output events as JSON [`EventPayload`].
+//! The [`MqttSubscriber`] applies messages for input events to the observed element, see
+//! [`receive`]. Messages are either a JSON `EventPayload` or the bare payload.
+//!
+//! Event elements without a usable broker or topic are skipped when connecting, they are
+//! reported by `skipped()` of the publisher and subscriber. Received messages which can't be
+//! applied are reported by [`MqttSubscriber::take_failed`].
+
+use crate::part_1::v3_1::Environment;
+use crate::part_1::v3_1::events::{EventError, EventSink, event_element, receive};
+use crate::part_1::v3_1::reference::ModelReference;
+use crate::part_1::v3_1::submodel_elements::{Direction, EventPayload, SubmodelElement};
+use chrono::Utc;
+use rumqttc::{Client, ClientError, Connection, Event, MqttOptions, Outgoing, Packet, QoS};
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MqttError {
+    #[error("The event {0} has no messageBroker")]
+    MissingBroker(String),
+
+    #[error("The event {0} has no messageTopic")]
+    MissingTopic(String),
+
+    #[error("The messageBroker {0} does not refer to a Property holding the broker address")]
+    UnresolvedBroker(String),
+
+    #[error("Invalid broker address {0}")]
+    InvalidAddress(String),
+
+    #[error(transparent)]
+    Client(#[from] ClientError),
+
+    #[error(transparent)]
+    Event(#[from] EventError),
+}
+
+/// Host and port of a broker, written as `mqtt://host:port`, `tcp://host:port` or `host:port`.
+/// The port defaults to 1883.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BrokerAddress {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Display for BrokerAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "mqtt://{}:{}", self.host, self.port)
+    }
+}
+
+impl FromStr for BrokerAddress {
+    type Err = MqttError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MqttError::InvalidAddress(s.to_string());
+        let address = s.trim();
+        let address = ["mqtt://", "tcp://"]
+            .iter()
+            .find_map(|scheme| address.strip_prefix(scheme))
+            .unwrap_or(address)
+            .trim_end_matches('/');
+
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (address, 1883),
+        };
+
+        if host.is_empty() || host.contains(['/', ' ']) {
+            return Err(invalid());
+        }
+
+        Ok(BrokerAddress {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+/// Broker and topic of an event element.
+struct Route {
+    source: ModelReference,
+    broker: BrokerAddress,
+    topic: String,
+}
+
+/// Event elements which can't be routed, with the reason.
+pub type Skipped = Vec<(ModelReference, MqttError)>;
+
+/// Routes of all event elements with the given direction. Event elements without a usable
+/// broker or topic are skipped and returned separately.
+fn routes(environment: &Environment, direction: Direction) -> (Vec<Route>, Skipped) {
+    let mut routes = Vec::new();
+    let mut skipped = Vec::new();
+
+    for source in super::event_elements(environment) {
+        match route(environment, &source, &direction) {
+            Ok(Some(route)) => routes.push(route),
+            Ok(None) => {}
+            Err(err) => skipped.push((source, err)),
+        }
+    }
+
+    (routes, skipped)
+}
+
+/// The route of the event element `source`, if it has the given direction.
+fn route(
+    environment: &Environment,
+    source: &ModelReference,
+    direction: &Direction,
+) -> Result<Option<Route>, MqttError> {
+    let event = event_element(environment, source)?;
+    if event.direction != *direction {
+        return Ok(None);
+    }
+
+    let broker = event
+        .message_broker
+        .as_ref()
+        .ok_or_else(|| MqttError::MissingBroker(source.to_string()))?;
+    let address = match environment.element(&broker.keys) {
+        Some(SubmodelElement::Property(property)) => property.value.lexical(),
+        _ => None,
+    }
+    .ok_or_else(|| MqttError::UnresolvedBroker(broker.to_string()))?;
+
+    let topic = event
+        .message_topic
+        .as_ref()
+        .ok_or_else(|| MqttError::MissingTopic(source.to_string()))?
+        .to_string();
+
+    Ok(Some(Route {
+        broker: address.parse()?,
+        topic,
+        source: source.clone(),
+    }))
+}
+
+fn connect(client_id: &str, broker: &BrokerAddress) -> (Client, Connection) {
+    let mut options = MqttOptions::new(client_id, &broker.host, broker.port);
+    options.set_keep_alive(Duration::from_secs(30));
+
+    Client::new(options, 64)
+}
+
+/// Drives the connection until the client disconnects, reconnecting on errors.
+/// `on_connect` is called on every (re)connect, sessions are clean so subscriptions have to be
+/// renewed there.
+fn drive(
+    mut connection: Connection,
+    mut on_connect: impl FnMut(),
+    mut on_publish: impl FnMut(&str, &[u8]),
+) {
+    for notification in connection.iter() {
+        match notification {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => on_connect(),
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                on_publish(&publish.topic, &publish.payload)
+            }
+            Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+            Ok(_) => {}
+            Err(_) => thread::sleep(Duration::from_millis(500)),
+        }
+    }
+}
+
+/// Publishes output events to the broker and topic of their event element.
+/// One connection is opened per broker.
+pub struct MqttPublisher {
+    clients: HashMap<BrokerAddress, Client>,
+    routes: HashMap<String, (BrokerAddress, String)>,
+    skipped: Skipped,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl MqttPublisher {
+    /// Connects to the brokers of all output event elements of the environment.
+    pub fn connect(environment: &Environment, client_id: &str) -> Result<Self, MqttError> {
+        let (routes, skipped) = routes(environment, Direction::Output);
+        let mut publisher = MqttPublisher {
+            clients: HashMap::new(),
+            routes: HashMap::new(),
+            skipped,
+            workers: Vec::new(),
+        };
+
+        for route in routes {
+            if !publisher.clients.contains_key(&route.broker) {
+                let (client, connection) = connect(client_id, &route.broker);
+                publisher
+                    .workers
+                    .push(thread::spawn(move || drive(connection, || {}, |_, _| {})));
+                publisher.clients.insert(route.broker.clone(), client);
+            }
+
+            publisher
+                .routes
+                .insert(route.source.to_string(), (route.broker, route.topic));
+        }
+
+        Ok(publisher)
+    }
+
+    /// Output event elements which were not connected.
+    pub fn skipped(&self) -> &Skipped {
+        &self.skipped
+    }
+
+    /// Disconnects from all brokers after pending messages were sent.
+    pub fn disconnect(self) {
+        for client in self.clients.values() {
+            let _ = client.disconnect();
+        }
+        drop(self.clients);
+
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl EventSink for MqttPublisher {
+    fn publish(&self, event: &EventPayload) -> Result<(), EventError> {
+        let source = event.source.to_string();
+        let (broker, topic) = self
+            .routes
+            .get(&source)
+            .ok_or_else(|| EventError::Sink(format!("no broker connected for {source}")))?;
+
+        let payload = serde_json::to_vec(event).map_err(|err| EventError::Sink(err.to_string()))?;
+
+        self.clients[broker]
+            .try_publish(topic, QoS::AtLeastOnce, false, payload)
+            .map_err(|err| EventError::Sink(err.to_string()))
+    }
+}
+
+/// Subscribes the topics of input events and applies received messages to the environment.
+pub struct MqttSubscriber {
+    clients: Vec<Client>,
+    skipped: Skipped,
+    failed: Arc<Mutex<Skipped>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl MqttSubscriber {
+    /// Connects to the brokers of all input event elements of the environment.
+    ///
+    /// Messages which can't be applied, i.e. with a payload not matching the value type, are
+    /// skipped and recorded, see [`take_failed`](MqttSubscriber::take_failed). The messageTopic
+    /// may contain MQTT wildcards.
+    pub fn connect(
+        environment: Arc<Mutex<Environment>>,
+        client_id: &str,
+    ) -> Result<Self, MqttError> {
+        let (routes, skipped) = routes(
+            &environment.lock().unwrap_or_else(|err| err.into_inner()),
+            Direction::Input,
+        );
+
+        let mut by_broker: HashMap<BrokerAddress, Vec<(ModelReference, String)>> = HashMap::new();
+        for route in routes {
+            by_broker
+                .entry(route.broker)
+                .or_default()
+                .push((route.source, route.topic));
+        }
+
+        let mut subscriber = MqttSubscriber {
+            clients: Vec::new(),
+            skipped,
+            failed: Default::default(),
+            workers: Vec::new(),
+        };
+
+        for (broker, routes) in by_broker {
+            let (client, connection) = connect(client_id, &broker);
+
+            let resubscribe = client.clone();
+            let environment = environment.clone();
+            let failed = subscriber.failed.clone();
+            subscriber.workers.push(thread::spawn(move || {
+                let topics: Vec<String> = routes.iter().map(|(_, topic)| topic.clone()).collect();
+                let subscribe = move || {
+                    for topic in &topics {
+                        let _ = resubscribe.try_subscribe(topic, QoS::AtLeastOnce);
+                    }
+                };
+
+                drive(connection, subscribe, move |topic, message| {
+                    let mut environment = environment.lock().unwrap_or_else(|err| err.into_inner());
+
+                    for (source, _) in routes
+                        .iter()
+                        .filter(|(_, filter)| rumqttc::matches(topic, filter))
+                    {
+                        if let Err(err) = receive(&mut environment, &event_payload(source, message))
+                        {
+                            failed
+                                .lock()
+                                .unwrap_or_else(|err| err.into_inner())
+                                .push((source.clone(), err.into()));
+                        }
+                    }
+                })
+            }));
+            subscriber.clients.push(client);
+        }
+
+        Ok(subscriber)
+    }
+
+    /// Input event elements which were not subscribed.
+    pub fn skipped(&self) -> &Skipped {
+        &self.skipped
+    }
+
+    /// Messages which could not be applied since the last call, with the input event element
+    /// they were received for.
+    pub fn take_failed(&self) -> Skipped {
+        std::mem::take(&mut self.failed.lock().unwrap_or_else(|err| err.into_inner()))
+    }
+
+    pub fn disconnect(self) {
+        for client in &self.clients {
+            let _ = client.disconnect();
+        }
+        drop(self.clients);
+
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The received message as event of `source`.
+fn event_payload(source: &ModelReference, message: &[u8]) -> EventPayload {
+    match serde_json::from_slice::<EventPayload>(message) {
+        Ok(event) => EventPayload {
+            source: source.clone(),
+            ..event
+        },
+        Err(_) => EventPayload {
+            source: source.clone(),
+            source_semantic_id: None,
+            observable_reference: source.clone(),
+            observable_semantic_id: None,
+            topic: None,
+            subject_id: None,
+            time_stamp: Utc::now(),
+            payload: Some(message.to_vec()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::events::{Emission, EventEmitter};
+    use rumqttd::{Broker, Config, ConnectionSettings, RouterConfig, ServerSettings};
+    use std::net::{SocketAddr, TcpListener};
+    use std::time::Instant;
+
+    /// Starts an embedded broker on a free local port.
+    fn broker() -> SocketAddr {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap();
+
+        let server = ServerSettings {
+            name: "v4".to_string(),
+            listen: address,
+            tls: None,
+            next_connection_delay_ms: 1,
+            connections: ConnectionSettings {
+                connection_timeout_ms: 5000,
+                max_payload_size: 20480,
+                max_inflight_count: 100,
+                auth: None,
+                external_auth: None,
+                dynamic_filters: true,
+            },
+        };
+        let config = Config {
+            router: RouterConfig {
+                max_connections: 100,
+                max_outgoing_packet_count: 200,
+                max_segment_size: 1024 * 1024,
+                max_segment_count: 10,
+                ..Default::default()
+            },
+            v4: Some(HashMap::from([("v4".to_string(), server)])),
+            ..Default::default()
+        };
+
+        thread::spawn(move || {
+            let _ = Broker::new(config).start();
+        });
+
+        let started = Instant::now();
+        while std::net::TcpStream::connect(address).is_err() {
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "broker not started"
+            );
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        address
+    }
+
+    fn environment(broker: SocketAddr) -> Environment {
+        let json = r#"
+        {
+          "submodels": [
+            {
+              "modelType": "Submodel",
+              "id": "urn:x:sm:condition",
+              "submodelElements": [
+                {
+                  "modelType": "Property",
+                  "idShort": "Broker",
+                  "valueType": "xs:string",
+                  "value": "BROKER"
+                },
+                {
+                  "modelType": "Property",
+                  "idShort": "Temperature",
+                  "valueType": "xs:double"
+                },
+                {
+                  "modelType": "BasicEventElement",
+                  "idShort": "TemperatureChanged",
+                  "observed": {
+                    "type": "ModelReference",
+                    "keys": [
+                      { "type": "Submodel", "value": "urn:x:sm:condition" },
+                      { "type": "Property", "value": "Temperature" }
+                    ]
+                  },
+                  "direction": "output",
+                  "state": "on",
+                  "messageTopic": "condition/temperature",
+                  "messageBroker": {
+                    "type": "ModelReference",
+                    "keys": [
+                      { "type": "Submodel", "value": "urn:x:sm:condition" },
+                      { "type": "Property", "value": "Broker" }
+                    ]
+                  }
+                },
+                {
+                  "modelType": "BasicEventElement",
+                  "idShort": "SetTemperature",
+                  "observed": {
+                    "type": "ModelReference",
+                    "keys": [
+                      { "type": "Submodel", "value": "urn:x:sm:condition" },
+                      { "type": "Property", "value": "Temperature" }
+                    ]
+                  },
+                  "direction": "input",
+                  "state": "on",
+                  "messageTopic": "condition/temperature/set",
+                  "messageBroker": {
+                    "type": "ModelReference",
+                    "keys": [
+                      { "type": "Submodel", "value": "urn:x:sm:condition" },
+                      { "type": "Property", "value": "Broker" }
+                    ]
+                  }
+                }
+              ]
+            }
+          ]
+        }
+        "#;
+
+        serde_json::from_str(&json.replace("BROKER", &format!("mqtt://{broker}"))).unwrap()
+    }
+
+    fn eventually(mut condition: impl FnMut() -> bool) {
+        let started = Instant::now();
+        while !condition() {
+            assert!(started.elapsed() < Duration::from_secs(10), "timed out");
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn broker_address() {
+        assert_eq!(
+            BrokerAddress::from_str("mqtt://broker.local:1884/").unwrap(),
+            BrokerAddress {
+                host: "broker.local".into(),
+                port: 1884
+            }
+        );
+        assert_eq!(BrokerAddress::from_str("broker.local").unwrap().port, 1883);
+        assert!(BrokerAddress::from_str("mqtt://:1883").is_err());
+        assert!(BrokerAddress::from_str("mqtt://broker:port").is_err());
+    }
+
+    #[test]
+    fn skip_unroutable() {
+        let mut environment = environment("127.0.0.1:1883".parse().unwrap());
+        let source = environment
+            .submodel("urn:x:sm:condition")
+            .and_then(|submodel| submodel.element_reference("SetTemperature"))
+            .unwrap();
+        if let Some(SubmodelElement::BasicEventElement(event)) =
+            environment.element_mut(&source.keys)
+        {
+            event.message_topic = None;
+        }
+
+        let (output, skipped) = routes(&environment, Direction::Output);
+        assert_eq!(output.len(), 1);
+        assert!(skipped.is_empty());
+
+        let (input, skipped) = routes(&environment, Direction::Input);
+        assert!(input.is_empty());
+        assert!(matches!(
+            skipped.as_slice(),
+            [(skipped, MqttError::MissingTopic(_))] if *skipped == source
+        ));
+    }
+
+    #[test]
+    fn publish_and_subscribe() {
+        let broker = broker();
+        let environment = Arc::new(Mutex::new(environment(broker)));
+
+        // an external client listening to output events and sending input events
+        let (client, mut connection) = connect(
+            "external",
+            &BrokerAddress {
+                host: broker.ip().to_string(),
+                port: broker.port(),
+            },
+        );
+        client
+            .subscribe("condition/temperature", QoS::AtLeastOnce)
+            .unwrap();
+        let received = Arc::new(Mutex::new(Vec::<EventPayload>::new()));
+        let sink = received.clone();
+        thread::spawn(move || {
+            for notification in connection.iter() {
+                match notification {
+                    Ok(Event::Incoming(Packet::Publish(publish))) => sink
+                        .lock()
+                        .unwrap()
+                        .push(serde_json::from_slice(&publish.payload).unwrap()),
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let subscriber = MqttSubscriber::connect(environment.clone(), "aas-subscriber").unwrap();
+        let emitter = EventEmitter::new(
+            MqttPublisher::connect(&environment.lock().unwrap(), "aas-publisher").unwrap(),
+        );
+
+        let source = environment
+            .lock()
+            .unwrap()
+            .submodel("urn:x:sm:condition")
+            .and_then(|submodel| submodel.element_reference("TemperatureChanged"))
+            .unwrap();
+
+        eventually(|| {
+            let emission = emitter
+                .emit(
+                    &mut environment.lock().unwrap(),
+                    &source,
+                    Some(b"21.5".to_vec()),
+                )
+                .unwrap();
+            assert!(matches!(emission, Emission::Sent(_)));
+
+            !received.lock().unwrap().is_empty()
+        });
+        assert_eq!(received.lock().unwrap()[0].source, source);
+
+        eventually(|| {
+            client
+                .publish("condition/temperature/set", QoS::AtLeastOnce, false, "42.5")
+                .unwrap();
+
+            let environment = environment.lock().unwrap();
+            let temperature = environment
+                .submodel("urn:x:sm:condition")
+                .and_then(|submodel| submodel.element("Temperature"));
+
+            matches!(temperature, Some(SubmodelElement::Property(property))
+                if property.get::<f64>() == Ok(Some(42.5)))
+        });
+        assert!(subscriber.take_failed().is_empty());
+
+        client
+            .publish("condition/temperature/set", QoS::AtLeastOnce, false, "warm")
+            .unwrap();
+        let mut failed = Vec::new();
+        eventually(|| {
+            failed.extend(subscriber.take_failed());
+            !failed.is_empty()
+        });
+        assert!(matches!(
+            failed.as_slice(),
+            [(source, MqttError::Event(_))] if source.to_string().ends_with("SetTemperature")
+        ));
+
+        subscriber.disconnect();
+        emitter.into_sink().disconnect();
+        let _ = client.disconnect();
+    }
+}
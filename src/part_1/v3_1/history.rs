@@ -0,0 +1,543 @@
+//! Versioned history of submodels.
+//!
+//! A [`SubmodelHistory`] keeps a snapshot of a submodel for every committed change, labelled by
+//! the version and revision of its `AdministrativeInformation` and the time of the commit.
+//! Committing bumps the revision, unless the version was changed by the caller, which restarts
+//! the revision at `0`. Past states can be looked up by version or by time and two versions can
+//! be compared, see [`diff`].
+//!
+//! ```
+//! use aas::part_1::v3_1::core::Submodel;
+//! use aas::part_1::v3_1::history::SubmodelHistory;
+//! use aas::part_1::v3_1::submodel_elements::SubmodelElement;
+//!
+//! let submodel: Submodel = serde_json::from_str(r#"{
+//!     "id": "https://example.com/submodels/1",
+//!     "submodelElements": [
+//!         {"modelType": "Property", "idShort": "Capacity", "valueType": "xs:string", "value": "100"}
+//!     ]
+//! }"#).unwrap();
+//!
+//! let mut history = SubmodelHistory::new(submodel);
+//!
+//! let mut changed = history.latest().clone();
+//! if let Some(SubmodelElement::Property(capacity)) = changed.element_mut("Capacity") {
+//!     capacity.set("95".to_string()).unwrap();
+//! }
+//! let version = history.commit(changed).unwrap();
+//! assert_eq!((version.version.as_str(), version.revision.as_str()), ("1", "1"));
+//!
+//! let changes = history.diff(("1", "0"), ("1", "1")).unwrap();
+//! assert_eq!(changes[0].path, "Capacity");
+//! ```
+
+use crate::part_1::v3_1::attributes::administrative_information::{
+    AdministrativeInformation, Version,
+};
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::observable::{Difference, diff};
+use crate::part_1::v3_1::primitives::DateTimeUTC;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum HistoryError {
+    #[error("The history is kept for submodel {expected}, not for {found}")]
+    IdMismatch { expected: String, found: String },
+
+    #[error("The commit at {timestamp} is older than the latest one at {latest}")]
+    OutOfOrder {
+        latest: DateTimeUTC,
+        timestamp: DateTimeUTC,
+    },
+
+    #[error("The submodel did not change")]
+    Unchanged,
+
+    #[error("The revision {0} can't be bumped, expected a number")]
+    InvalidRevision(String),
+
+    #[error("Version {version} revision {revision} is already in the history")]
+    DuplicateVersion { version: String, revision: String },
+
+    #[error("No version {version} revision {revision} in the history")]
+    UnknownVersion { version: String, revision: String },
+
+    #[error("A history needs at least one snapshot")]
+    Empty,
+}
+
+/// Label of a snapshot.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SubmodelVersion {
+    pub version: String,
+    pub revision: String,
+
+    /// Time of the commit.
+    pub timestamp: DateTimeUTC,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(flatten)]
+    version: SubmodelVersion,
+    submodel: Submodel,
+}
+
+/// Snapshots of a submodel, oldest first.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "Snapshots")]
+pub struct SubmodelHistory {
+    snapshots: Vec<Snapshot>,
+}
+
+/// A deserialized history before it is checked like the commits building it.
+#[derive(Deserialize)]
+struct Snapshots {
+    snapshots: Vec<Snapshot>,
+}
+
+impl TryFrom<Snapshots> for SubmodelHistory {
+    type Error = HistoryError;
+
+    fn try_from(value: Snapshots) -> Result<Self, Self::Error> {
+        if value.snapshots.is_empty() {
+            return Err(HistoryError::Empty);
+        }
+        for (index, snapshot) in value.snapshots.iter().enumerate().skip(1) {
+            let latest = &value.snapshots[index - 1];
+            check_successor(latest, &snapshot.submodel, snapshot.version.timestamp)?;
+
+            let version = &snapshot.version;
+            if value.snapshots[..index].iter().any(|earlier| {
+                earlier.version.version == version.version
+                    && earlier.version.revision == version.revision
+            }) {
+                return Err(HistoryError::DuplicateVersion {
+                    version: version.version.clone(),
+                    revision: version.revision.clone(),
+                });
+            }
+        }
+
+        Ok(SubmodelHistory {
+            snapshots: value.snapshots,
+        })
+    }
+}
+
+impl SubmodelHistory {
+    /// Starts the history with `submodel` as of now.
+    pub fn new(submodel: Submodel) -> Self {
+        Self::new_at(submodel, Utc::now())
+    }
+
+    /// Starts the history with `submodel` as of `timestamp`. Without a version, the submodel
+    /// gets version `1` revision `0`.
+    pub fn new_at(submodel: Submodel, timestamp: DateTimeUTC) -> Self {
+        let current = version(&submodel);
+        let version = SubmodelVersion {
+            version: current.version.unwrap_or_else(|| "1".to_string()),
+            revision: current.revision.unwrap_or_else(|| "0".to_string()),
+            timestamp,
+            author: None,
+        };
+
+        Self {
+            snapshots: vec![Snapshot::new(submodel, version)],
+        }
+    }
+
+    /// Records the current state of the submodel, see [`SubmodelHistory::commit_at`].
+    pub fn commit(&mut self, submodel: Submodel) -> Result<&SubmodelVersion, HistoryError> {
+        self.commit_at(submodel, Utc::now(), None)
+    }
+
+    /// Records the state of the submodel as of `timestamp`.
+    ///
+    /// The revision is bumped, unless the version of `submodel` differs from the latest one,
+    /// which starts that version at revision `0`. The version and revision are written into the
+    /// `AdministrativeInformation` of the snapshot.
+    pub fn commit_at(
+        &mut self,
+        submodel: Submodel,
+        timestamp: DateTimeUTC,
+        author: Option<String>,
+    ) -> Result<&SubmodelVersion, HistoryError> {
+        let latest = self.latest_snapshot();
+
+        check_successor(latest, &submodel, timestamp)?;
+        if unversioned(&submodel) == unversioned(&latest.submodel) {
+            return Err(HistoryError::Unchanged);
+        }
+
+        let (version, revision) = match version(&submodel).version {
+            Some(version) if version != latest.version.version => (version, "0".to_string()),
+            _ => {
+                let revision = &latest.version.revision;
+                let next = revision
+                    .parse::<u64>()
+                    .map_err(|_| HistoryError::InvalidRevision(revision.clone()))?
+                    + 1;
+                (latest.version.version.clone(), next.to_string())
+            }
+        };
+
+        if self.get(&version, &revision).is_some() {
+            return Err(HistoryError::DuplicateVersion { version, revision });
+        }
+
+        let version = SubmodelVersion {
+            version,
+            revision,
+            timestamp,
+            author,
+        };
+        self.snapshots.push(Snapshot::new(submodel, version));
+        Ok(&self.latest_snapshot().version)
+    }
+
+    pub fn latest(&self) -> &Submodel {
+        &self.latest_snapshot().submodel
+    }
+
+    pub fn latest_version(&self) -> &SubmodelVersion {
+        &self.latest_snapshot().version
+    }
+
+    /// All versions, oldest first.
+    pub fn versions(&self) -> impl Iterator<Item = &SubmodelVersion> {
+        self.snapshots.iter().map(|snapshot| &snapshot.version)
+    }
+
+    pub fn get(&self, version: &str, revision: &str) -> Option<&Submodel> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| {
+                snapshot.version.version == version && snapshot.version.revision == revision
+            })
+            .map(|snapshot| &snapshot.submodel)
+    }
+
+    /// The submodel as it was at `time`, `None` before the history started.
+    pub fn at(&self, time: DateTimeUTC) -> Option<&Submodel> {
+        self.snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.version.timestamp <= time)
+            .map(|snapshot| &snapshot.submodel)
+    }
+
+    /// The changes leading from one `(version, revision)` to another.
+    /// The version and revision themselves are not reported.
+    pub fn diff(
+        &self,
+        from: (&str, &str),
+        to: (&str, &str),
+    ) -> Result<Vec<Difference>, HistoryError> {
+        let get = |(version, revision): (&str, &str)| {
+            self.get(version, revision)
+                .ok_or_else(|| HistoryError::UnknownVersion {
+                    version: version.to_string(),
+                    revision: revision.to_string(),
+                })
+        };
+
+        Ok(diff(&unversioned(get(from)?), &unversioned(get(to)?)))
+    }
+
+    fn latest_snapshot(&self) -> &Snapshot {
+        self.snapshots
+            .last()
+            .expect("a history always has a snapshot")
+    }
+}
+
+impl Snapshot {
+    fn new(mut submodel: Submodel, version: SubmodelVersion) -> Self {
+        let administration = submodel
+            .identifiable
+            .administrative_information
+            .get_or_insert_with(AdministrativeInformation::default);
+        administration.version = Version {
+            version: Some(version.version.clone()),
+            revision: Some(version.revision.clone()),
+        };
+
+        Self { version, submodel }
+    }
+}
+
+/// A snapshot following `latest` has to be of the same submodel and must not be older.
+fn check_successor(
+    latest: &Snapshot,
+    submodel: &Submodel,
+    timestamp: DateTimeUTC,
+) -> Result<(), HistoryError> {
+    if submodel.id() != latest.submodel.id() {
+        return Err(HistoryError::IdMismatch {
+            expected: latest.submodel.id().to_string(),
+            found: submodel.id().to_string(),
+        });
+    }
+    if timestamp < latest.version.timestamp {
+        return Err(HistoryError::OutOfOrder {
+            latest: latest.version.timestamp,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+fn version(submodel: &Submodel) -> Version {
+    submodel
+        .identifiable
+        .administrative_information
+        .as_ref()
+        .map(|administration| administration.version.clone())
+        .unwrap_or_default()
+}
+
+/// The submodel without version and revision, to compare the content only.
+fn unversioned(submodel: &Submodel) -> Submodel {
+    let mut submodel = submodel.clone();
+    let administration = &mut submodel.identifiable.administrative_information;
+    if let Some(information) = administration {
+        information.version = Version::default();
+        if *information == AdministrativeInformation::default() {
+            *administration = None;
+        }
+    }
+    submodel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::observable::ChangeKind;
+    use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+    use chrono::DateTime;
+
+    fn submodel() -> Submodel {
+        serde_json::from_str(
+            r#"{
+                "id": "https://example.com/passport/1",
+                "submodelElements": [
+                    {"modelType": "Property", "idShort": "StateOfHealth",
+                     "valueType": "xs:string", "value": "100"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn with_health(submodel: &Submodel, value: &str) -> Submodel {
+        let mut submodel = submodel.clone();
+        let Some(SubmodelElement::Property(health)) = submodel.element_mut("StateOfHealth") else {
+            panic!("expected a Property")
+        };
+        health.set(value.to_string()).unwrap();
+        submodel
+    }
+
+    fn time(seconds: i64) -> DateTimeUTC {
+        DateTime::from_timestamp(seconds, 0).unwrap()
+    }
+
+    fn labels(history: &SubmodelHistory) -> Vec<(String, String)> {
+        history
+            .versions()
+            .map(|version| (version.version.clone(), version.revision.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn revisions_and_versions() {
+        let mut history = SubmodelHistory::new_at(submodel(), time(0));
+
+        let changed = with_health(history.latest(), "98");
+        let version = history
+            .commit_at(changed, time(10), Some("service".into()))
+            .unwrap();
+        assert_eq!(version.author.as_deref(), Some("service"));
+
+        // Changing the version by hand starts a new revision count.
+        let mut changed = with_health(history.latest(), "97");
+        changed
+            .identifiable
+            .administrative_information
+            .as_mut()
+            .unwrap()
+            .version
+            .version = Some("2".into());
+        history.commit_at(changed, time(20), None).unwrap();
+        history
+            .commit_at(with_health(history.latest(), "96"), time(30), None)
+            .unwrap();
+
+        assert_eq!(
+            labels(&history),
+            vec![
+                ("1".into(), "0".into()),
+                ("1".into(), "1".into()),
+                ("2".into(), "0".into()),
+                ("2".into(), "1".into()),
+            ]
+        );
+
+        let administration = history
+            .latest()
+            .identifiable
+            .administrative_information
+            .clone()
+            .unwrap();
+        assert_eq!(administration.version.version.as_deref(), Some("2"));
+        assert_eq!(administration.version.revision.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn time_travel() {
+        let mut history = SubmodelHistory::new_at(submodel(), time(100));
+        history
+            .commit_at(with_health(history.latest(), "90"), time(200), None)
+            .unwrap();
+
+        assert_eq!(history.at(time(99)), None);
+        assert_eq!(history.at(time(150)), history.get("1", "0"));
+        assert_eq!(history.at(time(200)), Some(history.latest()));
+        assert_eq!(
+            history.at(time(150)).unwrap().element("StateOfHealth"),
+            submodel().element("StateOfHealth")
+        );
+    }
+
+    #[test]
+    fn diff_versions() {
+        let mut history = SubmodelHistory::new_at(submodel(), time(0));
+        history
+            .commit_at(with_health(history.latest(), "90"), time(1), None)
+            .unwrap();
+
+        let changes = history.diff(("1", "0"), ("1", "1")).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::ValueChanged);
+        assert_eq!(changes[0].new, Some(serde_json::json!({"value": "90"})));
+
+        assert_eq!(
+            history.diff(("1", "0"), ("3", "0")),
+            Err(HistoryError::UnknownVersion {
+                version: "3".into(),
+                revision: "0".into()
+            })
+        );
+    }
+
+    #[test]
+    fn rejected_commits() {
+        let mut history = SubmodelHistory::new_at(submodel(), time(10));
+
+        assert_eq!(
+            history.commit_at(submodel(), time(20), None),
+            Err(HistoryError::Unchanged)
+        );
+        assert!(matches!(
+            history.commit_at(with_health(&submodel(), "1"), time(5), None),
+            Err(HistoryError::OutOfOrder { .. })
+        ));
+
+        let mut other = with_health(&submodel(), "1");
+        other.identifiable.id = "https://example.com/passport/2".try_into().unwrap();
+        assert!(matches!(
+            history.commit_at(other, time(20), None),
+            Err(HistoryError::IdMismatch { .. })
+        ));
+        assert_eq!(history.versions().count(), 1);
+
+        history
+            .commit_at(with_health(&submodel(), "1"), time(20), None)
+            .unwrap();
+        let set_version = |submodel: &Submodel, version: &str, health: &str| {
+            let mut submodel = with_health(submodel, health);
+            let administration = submodel.identifiable.administrative_information.as_mut();
+            administration.unwrap().version.version = Some(version.into());
+            submodel
+        };
+        let downgraded = set_version(history.latest(), "0", "2");
+        history.commit_at(downgraded, time(30), None).unwrap();
+        assert_eq!(
+            history.commit_at(set_version(history.latest(), "1", "3"), time(40), None),
+            Err(HistoryError::DuplicateVersion {
+                version: "1".into(),
+                revision: "0".into()
+            })
+        );
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let mut history = SubmodelHistory::new_at(submodel(), time(0));
+        history
+            .commit_at(with_health(history.latest(), "90"), time(1), None)
+            .unwrap();
+
+        let json = serde_json::to_string(&history).unwrap();
+        let actual: SubmodelHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(actual, history);
+    }
+
+    #[test]
+    fn deserialize_empty() {
+        let actual = serde_json::from_str::<SubmodelHistory>(r#"{"snapshots":[]}"#);
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            HistoryError::Empty.to_string()
+        );
+    }
+
+    #[test]
+    fn deserialize_inconsistent() {
+        let mut history = SubmodelHistory::new_at(submodel(), time(10));
+        history
+            .commit_at(with_health(history.latest(), "90"), time(20), None)
+            .unwrap();
+        let json = serde_json::to_value(&history).unwrap();
+
+        let deserialize = |change: &dyn Fn(&mut serde_json::Value)| {
+            let mut json = json.clone();
+            change(&mut json["snapshots"][1]);
+            serde_json::from_value::<SubmodelHistory>(json)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            deserialize(&|snapshot| snapshot["timestamp"] = "1970-01-01T00:00:05Z".into()),
+            HistoryError::OutOfOrder {
+                latest: time(10),
+                timestamp: time(5)
+            }
+            .to_string()
+        );
+        assert_eq!(
+            deserialize(&|snapshot| snapshot["submodel"]["id"] = "urn:other".into()),
+            HistoryError::IdMismatch {
+                expected: "https://example.com/passport/1".into(),
+                found: "urn:other".into()
+            }
+            .to_string()
+        );
+        assert_eq!(
+            deserialize(&|snapshot| snapshot["revision"] = "0".into()),
+            HistoryError::DuplicateVersion {
+                version: "1".into(),
+                revision: "0".into()
+            }
+            .to_string()
+        );
+    }
+}
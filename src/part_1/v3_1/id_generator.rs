@@ -0,0 +1,491 @@
+//! Minting of identifiers for new shells, submodels and concept descriptions.
+//!
+//! An [`IdGenerator`] stands for an id scheme: it creates new ids and tells whether an existing
+//! id follows the scheme, so ids can be checked in bulk with [`nonconforming_ids`]. Generated ids
+//! are made unique within an environment by [`IdGenerator::generate_unique`].
+//!
+//! ```
+//! use aas::part_1::v3_1::Environment;
+//! use aas::part_1::v3_1::id_generator::{IdGenerator, IdentifiableKind, IriTemplate};
+//!
+//! let environment: Environment = serde_json::from_str(r#"{
+//!     "submodels": [{ "modelType": "Submodel", "id": "https://example.com/ids/sm/1" }]
+//! }"#).unwrap();
+//!
+//! let mut ids = IriTemplate::new("https://example.com/ids/{kind}/{seq}").unwrap();
+//! let id = ids.generate_unique(IdentifiableKind::Submodel, &environment).unwrap();
+//! assert_eq!(id.as_ref(), "https://example.com/ids/sm/2");
+//! assert!(ids.matches(IdentifiableKind::Submodel, "https://example.com/ids/sm/17"));
+//! assert!(!ids.matches(IdentifiableKind::Submodel, "https://example.com/ids/aas/17"));
+//! ```
+
+use crate::part_1::v3_1::Environment;
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::primitives::{Identifier, IdentifierError};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// How many taken ids [`IdGenerator::generate_unique`] skips before giving up.
+pub const MAX_ATTEMPTS: usize = 1000;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum IdGeneratorError {
+    #[error("Invalid id template: {0}")]
+    Template(String),
+
+    #[error("{0} is not a valid IRI")]
+    InvalidIri(String),
+
+    #[error("{id} is not a valid identifier: {source}")]
+    InvalidIdentifier { id: String, source: IdentifierError },
+
+    #[error("No unused identifier found within {0} attempts")]
+    Exhausted(usize),
+
+    #[error("The sequence of ids is used up")]
+    SequenceEnd,
+}
+
+/// The kinds of identifiables ids are minted for.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum IdentifiableKind {
+    AssetAdministrationShell,
+    Submodel,
+    ConceptDescription,
+}
+
+impl IdentifiableKind {
+    /// All kinds, in the order of the `Environment`.
+    pub const ALL: [IdentifiableKind; 3] = [
+        IdentifiableKind::AssetAdministrationShell,
+        IdentifiableKind::Submodel,
+        IdentifiableKind::ConceptDescription,
+    ];
+
+    /// The short name used for `{kind}` in templates.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            IdentifiableKind::AssetAdministrationShell => "aas",
+            IdentifiableKind::Submodel => "sm",
+            IdentifiableKind::ConceptDescription => "cd",
+        }
+    }
+}
+
+/// An id scheme.
+pub trait IdGenerator {
+    /// A new id, generators may repeat ids that are in use, see [`IdGenerator::generate_unique`].
+    fn generate(&mut self, kind: IdentifiableKind) -> Result<Identifier, IdGeneratorError>;
+
+    /// Whether `id` follows the scheme.
+    fn matches(&self, kind: IdentifiableKind, id: &str) -> bool;
+
+    /// A new id no shell, submodel or concept description of `environment` has.
+    fn generate_unique(
+        &mut self,
+        kind: IdentifiableKind,
+        environment: &Environment,
+    ) -> Result<Identifier, IdGeneratorError> {
+        for _ in 0..MAX_ATTEMPTS {
+            let id = self.generate(kind)?;
+            if !environment.contains_identifier(&id) {
+                return Ok(id);
+            }
+        }
+
+        Err(IdGeneratorError::Exhausted(MAX_ATTEMPTS))
+    }
+}
+
+/// Random ids like `urn:uuid:9b2f7c4e-0d3a-4c8e-9a51-2f6d1e7b3c80`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct UuidUrn;
+
+impl IdGenerator for UuidUrn {
+    fn generate(&mut self, _kind: IdentifiableKind) -> Result<Identifier, IdGeneratorError> {
+        identifier(format!("urn:uuid:{}", Uuid::new_v4()))
+    }
+
+    fn matches(&self, _kind: IdentifiableKind, id: &str) -> bool {
+        id.strip_prefix("urn:uuid:")
+            .is_some_and(|uuid| uuid.len() == 36 && Uuid::parse_str(uuid).is_ok())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Placeholder {
+    Year,
+    Month,
+    Day,
+    Uuid,
+    Sequence,
+    Kind,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// IRIs from a template like `https://example.com/ids/sm/{yyyy}/{uuid}`.
+///
+/// Placeholders are `{yyyy}`, `{mm}` and `{dd}` for the current UTC date, `{uuid}` for a random
+/// UUID, `{seq}` for a counter and `{kind}` for the [`IdentifiableKind::abbreviation`]. A
+/// template needs `{uuid}` or `{seq}`, otherwise all ids of a day would be the same.
+#[derive(Clone, Debug)]
+pub struct IriTemplate {
+    segments: Vec<Segment>,
+    /// The pattern ids of each kind match, see [`IdGenerator::matches`].
+    patterns: HashMap<IdentifiableKind, Regex>,
+    next: u64,
+}
+
+impl IriTemplate {
+    pub fn new(template: &str) -> Result<Self, IdGeneratorError> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| IdGeneratorError::Template(format!("unclosed {{ in {template}")))?;
+            let placeholder = match &rest[start + 1..start + end] {
+                "yyyy" => Placeholder::Year,
+                "mm" => Placeholder::Month,
+                "dd" => Placeholder::Day,
+                "uuid" => Placeholder::Uuid,
+                "seq" => Placeholder::Sequence,
+                "kind" => Placeholder::Kind,
+                other => {
+                    return Err(IdGeneratorError::Template(format!(
+                        "unknown placeholder {{{other}}}"
+                    )));
+                }
+            };
+            segments.push(Segment::Placeholder(placeholder));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        let distinct = segments.iter().any(|segment| {
+            matches!(
+                segment,
+                Segment::Placeholder(Placeholder::Uuid | Placeholder::Sequence)
+            )
+        });
+        if !distinct {
+            return Err(IdGeneratorError::Template(format!(
+                "{template} needs {{uuid}} or {{seq}}"
+            )));
+        }
+
+        let patterns = IdentifiableKind::ALL
+            .into_iter()
+            .map(|kind| (kind, pattern(&segments, kind)))
+            .collect();
+        let generator = Self {
+            segments,
+            patterns,
+            next: 1,
+        };
+        let sample = generator.render(IdentifiableKind::Submodel, Utc::now(), Uuid::nil(), 0);
+        iref::Iri::new(&sample).map_err(|_| IdGeneratorError::InvalidIri(sample.clone()))?;
+
+        Ok(generator)
+    }
+
+    /// Lets `{seq}` start at `next` instead of `1`.
+    pub fn starting_at(mut self, next: u64) -> Self {
+        self.next = next;
+        self
+    }
+
+    fn render(&self, kind: IdentifiableKind, now: DateTime<Utc>, uuid: Uuid, seq: u64) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.clone(),
+                Segment::Placeholder(Placeholder::Year) => now.format("%Y").to_string(),
+                Segment::Placeholder(Placeholder::Month) => now.format("%m").to_string(),
+                Segment::Placeholder(Placeholder::Day) => now.format("%d").to_string(),
+                Segment::Placeholder(Placeholder::Uuid) => uuid.to_string(),
+                Segment::Placeholder(Placeholder::Sequence) => seq.to_string(),
+                Segment::Placeholder(Placeholder::Kind) => kind.abbreviation().to_string(),
+            })
+            .collect()
+    }
+}
+
+/// The ids of `kind` rendered from `segments`, whatever the date, UUID or counter.
+fn pattern(segments: &[Segment], kind: IdentifiableKind) -> Regex {
+    let pattern = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(literal) => regex::escape(literal),
+            Segment::Placeholder(Placeholder::Year) => r"\d{4}".to_string(),
+            Segment::Placeholder(Placeholder::Month | Placeholder::Day) => r"\d{2}".to_string(),
+            Segment::Placeholder(Placeholder::Uuid) => {
+                "[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}".to_string()
+            }
+            Segment::Placeholder(Placeholder::Sequence) => r"\d+".to_string(),
+            Segment::Placeholder(Placeholder::Kind) => regex::escape(kind.abbreviation()),
+        })
+        .collect::<String>();
+
+    Regex::new(&format!("^{pattern}$")).expect("escaped template is a valid regex")
+}
+
+impl IdGenerator for IriTemplate {
+    fn generate(&mut self, kind: IdentifiableKind) -> Result<Identifier, IdGeneratorError> {
+        let id = self.render(kind, Utc::now(), Uuid::new_v4(), self.next);
+        self.next += 1;
+        identifier(id)
+    }
+
+    fn matches(&self, kind: IdentifiableKind, id: &str) -> bool {
+        self.patterns[&kind].is_match(id)
+    }
+}
+
+/// Sequential IRDI-like ids `{rai}#{csi}-{code}#{version}`, i.e. `0173-1#02-AAA001#001`.
+///
+/// The code consists of three letters and three digits counting up from `AAA000`, the version is
+/// `001` unless set otherwise.
+#[derive(Clone, Debug)]
+pub struct SequentialIrdi {
+    registration_authority: String,
+    code_space: String,
+    /// The pattern generated ids match, see [`IdGenerator::matches`].
+    pattern: Regex,
+    next: u64,
+    version: u16,
+}
+
+impl SequentialIrdi {
+    /// Number of codes from `AAA000` to `ZZZ999`.
+    const CODES: u64 = 26 * 26 * 26 * 1000;
+
+    pub fn new(registration_authority: impl Into<String>, code_space: impl Into<String>) -> Self {
+        let registration_authority = registration_authority.into();
+        let code_space = code_space.into();
+        // Versions from 1000 on have more than three digits.
+        let pattern = format!(
+            r"^{}#{}-[A-Z]{{3}}\d{{3}}#\d{{3,}}$",
+            regex::escape(&registration_authority),
+            regex::escape(&code_space)
+        );
+
+        Self {
+            registration_authority,
+            code_space,
+            pattern: Regex::new(&pattern).expect("escaped ids make a valid regex"),
+            next: 0,
+            version: 1,
+        }
+    }
+
+    /// Lets the codes start at the `next`th one, `AAB000` being the 1000th.
+    pub fn starting_at(mut self, next: u64) -> Self {
+        self.next = next;
+        self
+    }
+
+    pub fn version(mut self, version: u16) -> Self {
+        self.version = version;
+        self
+    }
+
+    fn code(index: u64) -> String {
+        let letters = index / 1000;
+        let letter = |position: u32| (b'A' + (letters / 26u64.pow(position) % 26) as u8) as char;
+
+        format!("{}{}{}{:03}", letter(2), letter(1), letter(0), index % 1000)
+    }
+}
+
+impl IdGenerator for SequentialIrdi {
+    fn generate(&mut self, _kind: IdentifiableKind) -> Result<Identifier, IdGeneratorError> {
+        if self.next >= Self::CODES {
+            return Err(IdGeneratorError::SequenceEnd);
+        }
+
+        let id = format!(
+            "{}#{}-{}#{:03}",
+            self.registration_authority,
+            self.code_space,
+            Self::code(self.next),
+            self.version
+        );
+        self.next += 1;
+        identifier(id)
+    }
+
+    fn matches(&self, _kind: IdentifiableKind, id: &str) -> bool {
+        self.pattern.is_match(id)
+    }
+}
+
+/// The ids of the shells, submodels and concept descriptions of `environment` not following the
+/// scheme of `generator`.
+pub fn nonconforming_ids<'a>(
+    environment: &'a Environment,
+    generator: &impl IdGenerator,
+) -> Vec<(IdentifiableKind, &'a str)> {
+    let shells = environment.asset_administration_shells.iter().flatten();
+    let submodels = environment.submodels.iter().flatten();
+    let descriptions = environment.concept_descriptions.iter().flatten();
+
+    shells
+        .map(|shell| {
+            (
+                IdentifiableKind::AssetAdministrationShell,
+                &shell.identifiable,
+            )
+        })
+        .chain(submodels.map(|submodel| (IdentifiableKind::Submodel, &submodel.identifiable)))
+        .chain(descriptions.map(|description| {
+            (
+                IdentifiableKind::ConceptDescription,
+                &description.identifiable,
+            )
+        }))
+        .map(|(kind, identifiable)| (kind, identifiable.id.as_ref()))
+        .filter(|(kind, id)| !generator.matches(*kind, id))
+        .collect()
+}
+
+/// An instance of the submodel `template` with a new id unique within `environment`, see
+/// [`Submodel::instantiate`].
+pub fn instantiate(
+    template: &Submodel,
+    generator: &mut impl IdGenerator,
+    environment: &Environment,
+) -> Result<Submodel, IdGeneratorError> {
+    let id = generator.generate_unique(IdentifiableKind::Submodel, environment)?;
+    Ok(template.instantiate(id))
+}
+
+fn identifier(id: String) -> Result<Identifier, IdGeneratorError> {
+    Identifier::try_from(id.as_str())
+        .map_err(|source| IdGeneratorError::InvalidIdentifier { id, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::attributes::kind::ModellingKind;
+    use serde_json::json;
+
+    fn environment() -> Environment {
+        serde_json::from_value(json!({
+            "assetAdministrationShells": [{
+                "id": "urn:uuid:0b6d3c1e-3f4a-4e5b-8c7d-9e0f1a2b3c4d",
+                "assetInformation": { "assetKind": "Instance" }
+            }],
+            "submodels": [
+                { "modelType": "Submodel", "id": "0173-1#02-AAA000#001" },
+                { "modelType": "Submodel", "id": "0173-1#02-AAA001#001" },
+                {
+                    "modelType": "Submodel",
+                    "id": "https://example.com/templates/nameplate",
+                    "kind": "Template",
+                    "submodelElements": [{ "modelType": "Property", "idShort": "SerialNumber", "valueType": "xs:string" }]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn iri_template() {
+        let mut ids = IriTemplate::new("https://example.com/ids/{kind}/{yyyy}/{uuid}").unwrap();
+
+        let id = ids.generate(IdentifiableKind::Submodel).unwrap();
+        let year = Utc::now().format("%Y").to_string();
+        assert!(id.starts_with(&format!("https://example.com/ids/sm/{year}/")));
+        assert!(ids.matches(IdentifiableKind::Submodel, &id));
+        assert!(!ids.matches(IdentifiableKind::ConceptDescription, &id));
+        assert!(!ids.matches(
+            IdentifiableKind::Submodel,
+            "https://example.com/ids/sm/2024/1"
+        ));
+
+        assert!(matches!(
+            IriTemplate::new("https://example.com/ids/{date}/{uuid}"),
+            Err(IdGeneratorError::Template(_))
+        ));
+        assert!(matches!(
+            IriTemplate::new("https://example.com/ids/fixed"),
+            Err(IdGeneratorError::Template(_))
+        ));
+        assert!(matches!(
+            IriTemplate::new("no iri {uuid}"),
+            Err(IdGeneratorError::InvalidIri(_))
+        ));
+    }
+
+    #[test]
+    fn unique_sequential_irdi() {
+        let environment = environment();
+        let mut ids = SequentialIrdi::new("0173-1", "02");
+
+        let id = ids
+            .generate_unique(IdentifiableKind::Submodel, &environment)
+            .unwrap();
+        assert_eq!(id.as_ref(), "0173-1#02-AAA002#001");
+        assert_eq!(SequentialIrdi::code(26_999), "ABA999");
+
+        let mut last = SequentialIrdi::new("0173-1", "02").starting_at(SequentialIrdi::CODES - 1);
+        assert_eq!(
+            last.generate(IdentifiableKind::Submodel).unwrap().as_ref(),
+            "0173-1#02-ZZZ999#001"
+        );
+        assert_eq!(
+            last.generate(IdentifiableKind::Submodel),
+            Err(IdGeneratorError::SequenceEnd)
+        );
+
+        let mut revised = SequentialIrdi::new("0173-1", "02").version(1000);
+        let id = revised.generate(IdentifiableKind::Submodel).unwrap();
+        assert_eq!(id.as_ref(), "0173-1#02-AAA000#1000");
+        assert!(revised.matches(IdentifiableKind::Submodel, &id));
+        assert!(!revised.matches(IdentifiableKind::Submodel, "0173-1#02-AAA000#01"));
+
+        assert_eq!(
+            nonconforming_ids(&environment, &ids),
+            [
+                (
+                    IdentifiableKind::AssetAdministrationShell,
+                    "urn:uuid:0b6d3c1e-3f4a-4e5b-8c7d-9e0f1a2b3c4d"
+                ),
+                (
+                    IdentifiableKind::Submodel,
+                    "https://example.com/templates/nameplate"
+                )
+            ]
+        );
+        assert_eq!(nonconforming_ids(&environment, &UuidUrn).len(), 3);
+    }
+
+    #[test]
+    fn instantiate_template() {
+        let environment = environment();
+        let template = environment
+            .submodel("https://example.com/templates/nameplate")
+            .unwrap();
+
+        let instance = instantiate(template, &mut UuidUrn, &environment).unwrap();
+        assert!(UuidUrn.matches(IdentifiableKind::Submodel, &instance.identifiable.id));
+        assert_eq!(instance.kind, Some(ModellingKind::Instance));
+        assert!(instance.element("SerialNumber").is_some());
+    }
+}
@@ -0,0 +1,479 @@
+use super::{InvocationError, InvocationResult, OperationRegistry, finish, prepare};
+use crate::part_1::v3_1::submodel_elements::{Operation, OperationVariable};
+use crate::part_2::v3_1::{ExecutionState, Message, OperationResult};
+use crate::utilities::parse_xs_duration;
+use std::collections::HashMap;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+impl From<InvocationResult> for OperationResult {
+    fn from(result: InvocationResult) -> Self {
+        OperationResult {
+            output_arguments: Some(result.output_arguments),
+            inoutput_arguments: Some(result.inoutput_arguments),
+            ..OperationResult::with_state(ExecutionState::Completed)
+        }
+    }
+}
+
+/// Executes operations of a [`OperationRegistry`] in the background.
+///
+/// Every execution is identified by a handle id and follows the [`ExecutionState`] lifecycle.
+/// Cancellation is cooperative: the state changes immediately, the handler is informed via
+/// [`Invocation::is_canceled`](super::Invocation::is_canceled) and its result is discarded.
+/// The same applies once the client timeout elapsed, whether or not the execution is polled.
+///
+/// Finished executions are kept until they are [removed](OperationExecutor::remove).
+#[derive(Default)]
+pub struct OperationExecutor {
+    registry: OperationRegistry,
+    executions: Mutex<HashMap<String, Arc<Execution>>>,
+    next_handle: AtomicU64,
+}
+
+impl OperationExecutor {
+    pub fn new(registry: OperationRegistry) -> Self {
+        Self {
+            registry,
+            ..Default::default()
+        }
+    }
+
+    pub fn registry(&self) -> &OperationRegistry {
+        &self.registry
+    }
+
+    pub fn registry_mut(&mut self) -> &mut OperationRegistry {
+        &mut self.registry
+    }
+
+    /// Validates the arguments and starts the execution, returning its handle id.
+    ///
+    /// `client_timeout_duration` is a `xs:duration`, i.e. `PT30S`.
+    pub fn invoke_async(
+        &self,
+        id_short_path: &str,
+        operation: &Operation,
+        input_arguments: Vec<OperationVariable>,
+        inoutput_arguments: Vec<OperationVariable>,
+        client_timeout_duration: Option<&str>,
+    ) -> Result<String, InvocationError> {
+        let timeout = client_timeout_duration
+            .map(|duration| {
+                parse_xs_duration(duration)
+                    .ok_or_else(|| InvocationError::InvalidTimeout(duration.to_string()))
+            })
+            .transpose()?;
+
+        let handler = self
+            .registry
+            .handler(id_short_path, operation)
+            .ok_or_else(|| InvocationError::NoHandler(id_short_path.to_string()))?;
+
+        let mut invocation = prepare(operation, input_arguments, inoutput_arguments)?;
+
+        let execution = Arc::new(Execution::new(timeout, invocation.canceled.clone()));
+        let handle = self.next_handle.fetch_add(1, Ordering::Relaxed).to_string();
+
+        self.executions().insert(handle.clone(), execution.clone());
+
+        // arms the timeout, waiting applies it once the deadline passed
+        if timeout.is_some() {
+            let execution = execution.clone();
+            thread::spawn(move || execution.wait());
+        }
+
+        let operation = operation.clone();
+        thread::spawn(move || {
+            if !execution.start() {
+                return;
+            }
+
+            let result = catch_unwind(AssertUnwindSafe(|| handler.call(&mut invocation)))
+                .unwrap_or_else(|_| Err(InvocationError::Failed("handler panicked".into())))
+                .and_then(|_| finish(&operation, invocation));
+
+            execution.complete(result);
+        });
+
+        Ok(handle)
+    }
+
+    /// The current state of the execution.
+    pub fn state(&self, handle: &str) -> Result<ExecutionState, InvocationError> {
+        Ok(self.execution(handle)?.status().state)
+    }
+
+    /// The current result of the execution.
+    /// Output arguments are only present if the execution completed.
+    pub fn result(&self, handle: &str) -> Result<OperationResult, InvocationError> {
+        Ok(self.execution(handle)?.status().result())
+    }
+
+    /// Blocks until the execution finished or its timeout elapsed.
+    pub fn wait(&self, handle: &str) -> Result<OperationResult, InvocationError> {
+        Ok(self.execution(handle)?.wait())
+    }
+
+    /// Cancels a running execution. Finished executions are not changed.
+    pub fn cancel(&self, handle: &str) -> Result<ExecutionState, InvocationError> {
+        let execution = self.execution(handle)?;
+        let mut status = execution.status();
+
+        execution.transition(&mut status, ExecutionState::Canceled);
+
+        Ok(status.state)
+    }
+
+    /// Removes a finished execution and returns its result.
+    pub fn remove(&self, handle: &str) -> Result<OperationResult, InvocationError> {
+        let mut executions = self.executions();
+        let execution = executions
+            .get(handle)
+            .ok_or_else(|| InvocationError::UnknownHandle(handle.to_string()))?;
+
+        let status = execution.status();
+        if !status.state.is_finished() {
+            return Err(InvocationError::Failed(format!(
+                "execution {handle} is still {}",
+                status.state
+            )));
+        }
+        let result = status.result();
+        drop(status);
+
+        executions.remove(handle);
+
+        Ok(result)
+    }
+
+    fn executions(&self) -> MutexGuard<'_, HashMap<String, Arc<Execution>>> {
+        self.executions
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn execution(&self, handle: &str) -> Result<Arc<Execution>, InvocationError> {
+        self.executions()
+            .get(handle)
+            .cloned()
+            .ok_or_else(|| InvocationError::UnknownHandle(handle.to_string()))
+    }
+}
+
+struct Execution {
+    status: Mutex<ExecutionStatus>,
+    finished: Condvar,
+    canceled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+struct ExecutionStatus {
+    state: ExecutionState,
+    result: OperationResult,
+}
+
+impl ExecutionStatus {
+    fn result(&self) -> OperationResult {
+        OperationResult {
+            execution_state: Some(self.state),
+            ..self.result.clone()
+        }
+    }
+}
+
+impl Execution {
+    fn new(timeout: Option<Duration>, canceled: Arc<AtomicBool>) -> Self {
+        Self {
+            status: Mutex::new(ExecutionStatus {
+                state: ExecutionState::Initiated,
+                result: OperationResult::with_state(ExecutionState::Initiated),
+            }),
+            finished: Condvar::new(),
+            canceled,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    /// Locks the status, applying an elapsed timeout first.
+    fn status(&self) -> MutexGuard<'_, ExecutionStatus> {
+        let mut status = self.status.lock().unwrap_or_else(|err| err.into_inner());
+
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.transition(&mut status, ExecutionState::Timeout);
+        }
+
+        status
+    }
+
+    /// Changes the state unless the execution already finished.
+    fn transition(&self, status: &mut ExecutionStatus, state: ExecutionState) -> bool {
+        if status.state.is_finished() {
+            return false;
+        }
+
+        status.state = state;
+        status.result = OperationResult::with_state(state);
+
+        if state.is_finished() {
+            if state != ExecutionState::Completed {
+                self.canceled.store(true, Ordering::Relaxed);
+            }
+            self.finished.notify_all();
+        }
+
+        true
+    }
+
+    /// Initiated -> Running, false if canceled in between.
+    fn start(&self) -> bool {
+        self.transition(&mut self.status(), ExecutionState::Running)
+    }
+
+    fn complete(&self, result: Result<InvocationResult, InvocationError>) {
+        let mut status = self.status();
+
+        match result {
+            Ok(result) => {
+                if self.transition(&mut status, ExecutionState::Completed) {
+                    status.result = result.into();
+                }
+            }
+            Err(err) => {
+                if self.transition(&mut status, ExecutionState::Failed) {
+                    status.result.messages = Some(vec![Message::error(err.to_string())]);
+                }
+            }
+        }
+    }
+
+    fn wait(&self) -> OperationResult {
+        let mut status = self.status();
+
+        while !status.state.is_finished() {
+            status = match self.deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    drop(
+                        self.finished
+                            .wait_timeout(status, timeout)
+                            .unwrap_or_else(|err| err.into_inner()),
+                    );
+                    self.status()
+                }
+                None => self
+                    .finished
+                    .wait(status)
+                    .unwrap_or_else(|err| err.into_inner()),
+            };
+        }
+
+        status.result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::invocation::Invocation;
+    use crate::part_1::v3_1::invocation::tests::property;
+    use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+    use std::sync::mpsc;
+
+    fn firmware_update() -> Operation {
+        Operation {
+            input_variables: Some(vec![property("version", DataXsd::String(None))]),
+            output_variables: Some(vec![property("installed", DataXsd::String(None))]),
+            ..Default::default()
+        }
+    }
+
+    fn version() -> Vec<OperationVariable> {
+        vec![property("version", DataXsd::String(Some("1.2.0".into())))]
+    }
+
+    /// Handler blocking until released or canceled.
+    fn executor() -> (OperationExecutor, mpsc::Sender<()>) {
+        let (release, released) = mpsc::channel::<()>();
+        let released = Mutex::new(released);
+
+        let mut registry = OperationRegistry::new();
+        registry.bind_id_short_path("Firmware.Update", move |invocation: &mut Invocation| {
+            while !invocation.is_canceled() {
+                if released
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_millis(5))
+                    .is_ok()
+                {
+                    let version: String = invocation.input_value("version")?.unwrap_or_default();
+                    return invocation.set_output("installed", version);
+                }
+            }
+            Err(InvocationError::Failed("canceled".into()))
+        });
+
+        (OperationExecutor::new(registry), release)
+    }
+
+    #[test]
+    fn complete() {
+        let (executor, release) = executor();
+
+        let handle = executor
+            .invoke_async(
+                "Firmware.Update",
+                &firmware_update(),
+                version(),
+                vec![],
+                None,
+            )
+            .unwrap();
+
+        assert!(!executor.state(&handle).unwrap().is_finished());
+        assert_eq!(executor.result(&handle).unwrap().output_arguments, None);
+
+        release.send(()).unwrap();
+        let result = executor.wait(&handle).unwrap();
+
+        assert_eq!(result.execution_state, Some(ExecutionState::Completed));
+        assert_eq!(result.success, Some(true));
+        assert_eq!(
+            result.output_arguments,
+            Some(vec![property(
+                "installed",
+                DataXsd::String(Some("1.2.0".into()))
+            )])
+        );
+
+        assert_eq!(executor.remove(&handle).unwrap(), result);
+        assert_eq!(
+            executor.state(&handle),
+            Err(InvocationError::UnknownHandle(handle))
+        );
+    }
+
+    #[test]
+    fn cancel() {
+        let (executor, release) = executor();
+
+        let handle = executor
+            .invoke_async(
+                "Firmware.Update",
+                &firmware_update(),
+                version(),
+                vec![],
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(executor.cancel(&handle), Ok(ExecutionState::Canceled));
+        let _ = release.send(());
+
+        let result = executor.wait(&handle).unwrap();
+        assert_eq!(result.execution_state, Some(ExecutionState::Canceled));
+        assert_eq!(result.success, Some(false));
+        assert_eq!(result.output_arguments, None);
+    }
+
+    #[test]
+    fn timeout() {
+        let (executor, _release) = executor();
+
+        let handle = executor
+            .invoke_async(
+                "Firmware.Update",
+                &firmware_update(),
+                version(),
+                vec![],
+                Some("PT0.05S"),
+            )
+            .unwrap();
+
+        let result = executor.wait(&handle).unwrap();
+        assert_eq!(result.execution_state, Some(ExecutionState::Timeout));
+        assert_eq!(executor.cancel(&handle), Ok(ExecutionState::Timeout));
+    }
+
+    #[test]
+    fn timeout_without_polling() {
+        let (canceled, notified) = mpsc::channel::<()>();
+        let canceled = Mutex::new(canceled);
+
+        let mut registry = OperationRegistry::new();
+        registry.bind_id_short_path("Firmware.Update", move |invocation: &mut Invocation| {
+            while !invocation.is_canceled() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            let _ = canceled.lock().unwrap().send(());
+            Err(InvocationError::Failed("canceled".into()))
+        });
+        let executor = OperationExecutor::new(registry);
+
+        let handle = executor
+            .invoke_async(
+                "Firmware.Update",
+                &firmware_update(),
+                version(),
+                vec![],
+                Some("PT0.05S"),
+            )
+            .unwrap();
+
+        // the handler is informed without anyone asking for the state
+        assert!(notified.recv_timeout(Duration::from_secs(5)).is_ok());
+        assert_eq!(executor.state(&handle), Ok(ExecutionState::Timeout));
+    }
+
+    #[test]
+    fn failed() {
+        let mut registry = OperationRegistry::new();
+        registry.bind_id_short_path("Firmware.Update", |_: &mut Invocation| {
+            Err(InvocationError::Failed("no connection".into()))
+        });
+        let executor = OperationExecutor::new(registry);
+
+        let handle = executor
+            .invoke_async(
+                "Firmware.Update",
+                &firmware_update(),
+                version(),
+                vec![],
+                None,
+            )
+            .unwrap();
+
+        let result = executor.wait(&handle).unwrap();
+        assert_eq!(result.execution_state, Some(ExecutionState::Failed));
+        assert_eq!(
+            result.messages.unwrap()[0].text,
+            "Operation failed: no connection"
+        );
+    }
+
+    #[test]
+    fn invalid_request() {
+        let (executor, _release) = executor();
+
+        assert_eq!(
+            executor.invoke_async("Firmware.Update", &firmware_update(), vec![], vec![], None),
+            Err(InvocationError::MissingArgument("version".into()))
+        );
+        assert_eq!(
+            executor.invoke_async(
+                "Firmware.Update",
+                &firmware_update(),
+                version(),
+                vec![],
+                Some("30 seconds")
+            ),
+            Err(InvocationError::InvalidTimeout("30 seconds".into()))
+        );
+    }
+}
@@ -0,0 +1,627 @@
+//! Binding of Rust handlers to `Operation` elements.
+//!
+//! Handlers are registered in an [`OperationRegistry`] by the idShort path of the operation
+//! or by its semantic id. An invocation validates the given arguments against the declared
+//! operation variables, runs the handler and returns the output variables.
+//!
+//! ```
+//! use aas::part_1::v3_1::invocation::{Invocation, OperationRegistry};
+//! use aas::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+//! use aas::part_1::v3_1::submodel_elements::{Operation, OperationVariable, Property};
+//!
+//! let property = |id_short: &str, value| {
+//!     OperationVariable::from(Property::new(id_short.try_into().unwrap(), value))
+//! };
+//!
+//! let operation = Operation {
+//!     input_variables: Some(vec![property("a", DataXsd::Int(None))]),
+//!     output_variables: Some(vec![property("sum", DataXsd::Long(None))]),
+//!     ..Default::default()
+//! };
+//!
+//! let mut registry = OperationRegistry::new();
+//! registry.bind_id_short_path("Counter.Increment", |invocation: &mut Invocation| {
+//!     let a: i64 = invocation.input_value("a")?.unwrap_or_default();
+//!     invocation.set_output("sum", a + 1)
+//! });
+//!
+//! let result = registry
+//!     .invoke("Counter.Increment", &operation, vec![property("a", DataXsd::Int(Some(41)))], vec![])
+//!     .unwrap();
+//!
+//! assert_eq!(result.output_value::<i64>("sum"), Ok(Some(42)));
+//! ```
+
+use crate::part_1::v3_1::primitives::{DataXsdError, FromDataXsd, IntoDataXsd};
+use crate::part_1::v3_1::reference::Reference;
+use crate::part_1::v3_1::submodel_elements::{Operation, OperationVariable, SubmodelElement};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+
+mod execution;
+pub use execution::*;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum InvocationError {
+    #[error("No handler bound to operation {0}")]
+    NoHandler(String),
+
+    #[error("Missing argument {0}")]
+    MissingArgument(String),
+
+    #[error("Unknown argument {0}")]
+    UnknownArgument(String),
+
+    #[error("The handler did not set the output {0}")]
+    MissingOutput(String),
+
+    #[error("Argument {name} has to be {expected}, found {found}")]
+    TypeMismatch {
+        name: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error("Invalid value for argument {name}: {source}")]
+    InvalidValue {
+        name: String,
+        #[source]
+        source: DataXsdError,
+    },
+
+    #[error("Operation failed: {0}")]
+    Failed(String),
+
+    #[error("Invalid client timeout {0}, expected a xs:duration")]
+    InvalidTimeout(String),
+
+    #[error("No execution with handle {0}")]
+    UnknownHandle(String),
+}
+
+/// A handler executing an operation.
+/// Implemented for every `Fn(&mut Invocation) -> Result<(), InvocationError>`.
+pub trait OperationHandler: Send + Sync {
+    fn call(&self, invocation: &mut Invocation) -> Result<(), InvocationError>;
+}
+
+impl<F> OperationHandler for F
+where
+    F: Fn(&mut Invocation) -> Result<(), InvocationError> + Send + Sync,
+{
+    fn call(&self, invocation: &mut Invocation) -> Result<(), InvocationError> {
+        self(invocation)
+    }
+}
+
+/// How a handler is bound to an operation.
+#[derive(Clone, PartialEq, Debug)]
+pub enum OperationBinding {
+    /// idShort path of the operation inside its submodel, i.e. `Maintenance.ResetCounter`
+    IdShortPath(String),
+
+    /// semantic id of the operation, matched by [`Reference::matches_semantic_id`]
+    SemanticId(Reference),
+}
+
+impl OperationBinding {
+    fn matches(&self, id_short_path: &str, operation: &Operation) -> bool {
+        match self {
+            OperationBinding::IdShortPath(path) => path == id_short_path,
+            OperationBinding::SemanticId(semantic_id) => {
+                operation.semantics.has_semantic_id(semantic_id)
+            }
+        }
+    }
+}
+
+/// Arguments of a running invocation, handed to the [`OperationHandler`].
+///
+/// Outputs are empty until the handler sets them, each starting as a copy of its declared
+/// output variable.
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    input_arguments: Vec<OperationVariable>,
+    inoutput_arguments: Vec<OperationVariable>,
+    declared_outputs: Vec<OperationVariable>,
+    output_arguments: Vec<OperationVariable>,
+    canceled: Arc<AtomicBool>,
+}
+
+impl Invocation {
+    /// Set when an asynchronous execution was canceled or timed out.
+    /// Long-running handlers should check it and stop early.
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Relaxed)
+    }
+
+    pub fn input(&self, id_short: &str) -> Option<&SubmodelElement> {
+        find(&self.input_arguments, id_short)
+    }
+
+    /// Typed value of an input `Property`.
+    pub fn input_value<T: FromDataXsd>(
+        &self,
+        id_short: &str,
+    ) -> Result<Option<T>, InvocationError> {
+        property_value(&self.input_arguments, id_short)
+    }
+
+    pub fn inoutput(&self, id_short: &str) -> Option<&SubmodelElement> {
+        find(&self.inoutput_arguments, id_short)
+    }
+
+    pub fn inoutput_mut(&mut self, id_short: &str) -> Option<&mut SubmodelElement> {
+        find_mut(&mut self.inoutput_arguments, id_short)
+    }
+
+    /// Typed value of an inoutput `Property`.
+    pub fn inoutput_value<T: FromDataXsd>(
+        &self,
+        id_short: &str,
+    ) -> Result<Option<T>, InvocationError> {
+        property_value(&self.inoutput_arguments, id_short)
+    }
+
+    /// Set the value of an inoutput `Property`, keeping its value type.
+    pub fn set_inoutput<T: IntoDataXsd>(
+        &mut self,
+        id_short: &str,
+        value: T,
+    ) -> Result<(), InvocationError> {
+        set_property_value(&mut self.inoutput_arguments, id_short, value)
+    }
+
+    /// The output, added as a copy of its declared variable if it was not set before.
+    /// `None` if no such output is declared.
+    pub fn output_mut(&mut self, id_short: &str) -> Option<&mut SubmodelElement> {
+        if find(&self.output_arguments, id_short).is_none() {
+            let declared = self
+                .declared_outputs
+                .iter()
+                .find(|variable| variable.id_short() == Some(id_short))?;
+            self.output_arguments.push(declared.clone());
+        }
+
+        find_mut(&mut self.output_arguments, id_short)
+    }
+
+    /// Set the value of an output `Property`, keeping its declared value type.
+    pub fn set_output<T: IntoDataXsd>(
+        &mut self,
+        id_short: &str,
+        value: T,
+    ) -> Result<(), InvocationError> {
+        self.output_mut(id_short);
+        set_property_value(&mut self.output_arguments, id_short, value)
+    }
+}
+
+/// Output and inoutput arguments of a successful invocation.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct InvocationResult {
+    pub output_arguments: Vec<OperationVariable>,
+    pub inoutput_arguments: Vec<OperationVariable>,
+}
+
+impl InvocationResult {
+    pub fn output(&self, id_short: &str) -> Option<&SubmodelElement> {
+        find(&self.output_arguments, id_short)
+    }
+
+    pub fn output_value<T: FromDataXsd>(
+        &self,
+        id_short: &str,
+    ) -> Result<Option<T>, InvocationError> {
+        property_value(&self.output_arguments, id_short)
+    }
+
+    pub fn inoutput_value<T: FromDataXsd>(
+        &self,
+        id_short: &str,
+    ) -> Result<Option<T>, InvocationError> {
+        property_value(&self.inoutput_arguments, id_short)
+    }
+}
+
+/// Handlers bound to operations. Bindings by idShort path take precedence over semantic ids.
+#[derive(Clone, Default)]
+pub struct OperationRegistry {
+    bindings: Vec<(OperationBinding, Arc<dyn OperationHandler>)>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind(&mut self, binding: OperationBinding, handler: impl OperationHandler + 'static) {
+        self.bindings.push((binding, Arc::new(handler)));
+    }
+
+    pub fn bind_id_short_path(
+        &mut self,
+        id_short_path: impl Into<String>,
+        handler: impl OperationHandler + 'static,
+    ) {
+        self.bind(OperationBinding::IdShortPath(id_short_path.into()), handler);
+    }
+
+    pub fn bind_semantic_id(
+        &mut self,
+        semantic_id: Reference,
+        handler: impl OperationHandler + 'static,
+    ) {
+        self.bind(OperationBinding::SemanticId(semantic_id), handler);
+    }
+
+    /// The handler for the operation at `id_short_path`.
+    pub fn handler(
+        &self,
+        id_short_path: &str,
+        operation: &Operation,
+    ) -> Option<Arc<dyn OperationHandler>> {
+        let by_path = self.bindings.iter().find(|(binding, _)| {
+            matches!(binding, OperationBinding::IdShortPath(_))
+                && binding.matches(id_short_path, operation)
+        });
+
+        by_path
+            .or_else(|| {
+                self.bindings
+                    .iter()
+                    .find(|(binding, _)| binding.matches(id_short_path, operation))
+            })
+            .map(|(_, handler)| handler.clone())
+    }
+
+    /// Validates the arguments against the declared variables, runs the handler and
+    /// validates the output.
+    pub fn invoke(
+        &self,
+        id_short_path: &str,
+        operation: &Operation,
+        input_arguments: Vec<OperationVariable>,
+        inoutput_arguments: Vec<OperationVariable>,
+    ) -> Result<InvocationResult, InvocationError> {
+        let handler = self
+            .handler(id_short_path, operation)
+            .ok_or_else(|| InvocationError::NoHandler(id_short_path.to_string()))?;
+
+        let mut invocation = prepare(operation, input_arguments, inoutput_arguments)?;
+
+        handler.call(&mut invocation)?;
+
+        finish(operation, invocation)
+    }
+}
+
+/// Validates the arguments, outputs are left to the handler.
+pub(crate) fn prepare(
+    operation: &Operation,
+    input_arguments: Vec<OperationVariable>,
+    inoutput_arguments: Vec<OperationVariable>,
+) -> Result<Invocation, InvocationError> {
+    validate_arguments(operation.input_variables.as_deref(), &input_arguments)?;
+    validate_arguments(operation.inoutput_variables.as_deref(), &inoutput_arguments)?;
+
+    Ok(Invocation {
+        input_arguments,
+        inoutput_arguments,
+        declared_outputs: operation.output_variables.clone().unwrap_or_default(),
+        output_arguments: Vec::new(),
+        canceled: Default::default(),
+    })
+}
+
+/// Validates the outputs set by the handler, every declared output has to be set.
+pub(crate) fn finish(
+    operation: &Operation,
+    invocation: Invocation,
+) -> Result<InvocationResult, InvocationError> {
+    if let Some(missing) = invocation
+        .declared_outputs
+        .iter()
+        .filter_map(OperationVariable::id_short)
+        .find(|id_short| find(&invocation.output_arguments, id_short).is_none())
+    {
+        return Err(InvocationError::MissingOutput(missing.to_string()));
+    }
+
+    validate_arguments(
+        operation.output_variables.as_deref(),
+        &invocation.output_arguments,
+    )?;
+    validate_arguments(
+        operation.inoutput_variables.as_deref(),
+        &invocation.inoutput_arguments,
+    )?;
+
+    Ok(InvocationResult {
+        output_arguments: invocation.output_arguments,
+        inoutput_arguments: invocation.inoutput_arguments,
+    })
+}
+
+/// Every declared variable needs an argument of the same model type (and value type),
+/// unknown arguments are refused.
+fn validate_arguments(
+    declared: Option<&[OperationVariable]>,
+    arguments: &[OperationVariable],
+) -> Result<(), InvocationError> {
+    let declared = declared.unwrap_or_default();
+
+    if let Some(unknown) = arguments
+        .iter()
+        .find(|argument| find(declared, argument.id_short().unwrap_or_default()).is_none())
+    {
+        return Err(InvocationError::UnknownArgument(
+            unknown.id_short().unwrap_or_default().to_string(),
+        ));
+    }
+
+    for variable in declared {
+        let name = variable.id_short().unwrap_or_default();
+        let argument =
+            find(arguments, name).ok_or_else(|| InvocationError::MissingArgument(name.into()))?;
+
+        validate_argument(name, &variable.value, argument)?;
+    }
+
+    Ok(())
+}
+
+fn validate_argument(
+    name: &str,
+    declared: &SubmodelElement,
+    argument: &SubmodelElement,
+) -> Result<(), InvocationError> {
+    let mismatch = |expected: String, found: String| InvocationError::TypeMismatch {
+        name: name.to_string(),
+        expected,
+        found,
+    };
+
+    if declared.to_string() != argument.to_string() {
+        return Err(mismatch(declared.to_string(), argument.to_string()));
+    }
+
+    match (declared, argument) {
+        (SubmodelElement::Property(declared), SubmodelElement::Property(argument)) => {
+            if declared.value.to_string() != argument.value.to_string() {
+                return Err(mismatch(
+                    declared.value.to_string(),
+                    argument.value.to_string(),
+                ));
+            }
+
+            argument
+                .value
+                .validate()
+                .map_err(|source| InvocationError::InvalidValue {
+                    name: name.to_string(),
+                    source,
+                })
+        }
+        (SubmodelElement::Range(declared), SubmodelElement::Range(argument))
+            if declared.to_string() != argument.to_string() =>
+        {
+            Err(mismatch(declared.to_string(), argument.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn find<'a>(variables: &'a [OperationVariable], id_short: &str) -> Option<&'a SubmodelElement> {
+    variables
+        .iter()
+        .find(|variable| variable.id_short() == Some(id_short))
+        .map(|variable| &variable.value)
+}
+
+fn find_mut<'a>(
+    variables: &'a mut [OperationVariable],
+    id_short: &str,
+) -> Option<&'a mut SubmodelElement> {
+    variables
+        .iter_mut()
+        .find(|variable| variable.id_short() == Some(id_short))
+        .map(|variable| &mut variable.value)
+}
+
+fn property_value<T: FromDataXsd>(
+    variables: &[OperationVariable],
+    id_short: &str,
+) -> Result<Option<T>, InvocationError> {
+    match find(variables, id_short) {
+        Some(SubmodelElement::Property(property)) => {
+            property
+                .get()
+                .map_err(|source| InvocationError::InvalidValue {
+                    name: id_short.to_string(),
+                    source,
+                })
+        }
+        Some(other) => Err(InvocationError::TypeMismatch {
+            name: id_short.to_string(),
+            expected: "Property".to_string(),
+            found: other.to_string(),
+        }),
+        None => Err(InvocationError::UnknownArgument(id_short.to_string())),
+    }
+}
+
+fn set_property_value<T: IntoDataXsd>(
+    variables: &mut [OperationVariable],
+    id_short: &str,
+    value: T,
+) -> Result<(), InvocationError> {
+    match find_mut(variables, id_short) {
+        Some(SubmodelElement::Property(property)) => {
+            property
+                .set(value)
+                .map_err(|source| InvocationError::InvalidValue {
+                    name: id_short.to_string(),
+                    source,
+                })
+        }
+        Some(other) => Err(InvocationError::TypeMismatch {
+            name: id_short.to_string(),
+            expected: "Property".to_string(),
+            found: other.to_string(),
+        }),
+        None => Err(InvocationError::UnknownArgument(id_short.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+    use crate::part_1::v3_1::submodel_elements::Property;
+    use std::str::FromStr;
+
+    /// A `Property` variable, shared with the tests of the executor.
+    pub(super) fn property(id_short: &str, value: DataXsd) -> OperationVariable {
+        Property::new(id_short.try_into().unwrap(), value).into()
+    }
+
+    fn calibrate() -> Operation {
+        let mut operation = Operation {
+            input_variables: Some(vec![property("offset", DataXsd::Double(None))]),
+            output_variables: Some(vec![property("applied", DataXsd::Boolean(None))]),
+            inoutput_variables: Some(vec![property("counter", DataXsd::UnsignedInt(None))]),
+            ..Default::default()
+        };
+        operation.semantics.semantic_id =
+            Some(Reference::from_str("[ExternalRef](GlobalReference)urn:x:calibrate").unwrap());
+
+        operation
+    }
+
+    fn registry() -> OperationRegistry {
+        let mut registry = OperationRegistry::new();
+        registry.bind_semantic_id(
+            Reference::from_str("[ExternalRef](GlobalReference)urn:x:calibrate").unwrap(),
+            |invocation: &mut Invocation| {
+                let offset: f64 = invocation.input_value("offset")?.unwrap_or_default();
+                let counter: u32 = invocation.inoutput_value("counter")?.unwrap_or_default();
+
+                invocation.set_inoutput("counter", counter + 1)?;
+                invocation.set_output("applied", offset.abs() < 10.0)
+            },
+        );
+
+        registry
+    }
+
+    #[test]
+    fn invoke_by_semantic_id() {
+        let result = registry()
+            .invoke(
+                "Machine.Calibrate",
+                &calibrate(),
+                vec![property("offset", DataXsd::Double(Some(1.5)))],
+                vec![property("counter", DataXsd::UnsignedInt(Some(4)))],
+            )
+            .unwrap();
+
+        assert_eq!(result.output_value::<bool>("applied"), Ok(Some(true)));
+        assert_eq!(result.inoutput_value::<u32>("counter"), Ok(Some(5)));
+    }
+
+    #[test]
+    fn id_short_path_takes_precedence() {
+        let mut registry = registry();
+        registry.bind_id_short_path("Machine.Calibrate", |invocation: &mut Invocation| {
+            invocation.set_output("applied", false)
+        });
+
+        let result = registry
+            .invoke(
+                "Machine.Calibrate",
+                &calibrate(),
+                vec![property("offset", DataXsd::Double(Some(1.5)))],
+                vec![property("counter", DataXsd::UnsignedInt(Some(4)))],
+            )
+            .unwrap();
+
+        assert_eq!(result.output_value::<bool>("applied"), Ok(Some(false)));
+    }
+
+    #[test]
+    fn validate_inputs() {
+        let registry = registry();
+        let operation = calibrate();
+        let counter = || vec![property("counter", DataXsd::UnsignedInt(Some(4)))];
+
+        assert_eq!(
+            registry.invoke("Machine.Calibrate", &operation, vec![], counter()),
+            Err(InvocationError::MissingArgument("offset".into()))
+        );
+        assert_eq!(
+            registry.invoke(
+                "Machine.Calibrate",
+                &operation,
+                vec![property("offset", DataXsd::String(Some("1.5".into())))],
+                counter()
+            ),
+            Err(InvocationError::TypeMismatch {
+                name: "offset".into(),
+                expected: "xs:double".into(),
+                found: "xs:string".into(),
+            })
+        );
+        assert_eq!(
+            registry.invoke(
+                "Machine.Calibrate",
+                &operation,
+                vec![
+                    property("offset", DataXsd::Double(Some(1.5))),
+                    property("speed", DataXsd::Double(Some(1.5)))
+                ],
+                counter()
+            ),
+            Err(InvocationError::UnknownArgument("speed".into()))
+        );
+    }
+
+    #[test]
+    fn validate_outputs() {
+        let mut registry = OperationRegistry::new();
+        registry.bind_id_short_path("Machine.Calibrate", |invocation: &mut Invocation| {
+            invocation.set_inoutput("counter", 1)
+        });
+        let invoke = |registry: &OperationRegistry| {
+            registry.invoke(
+                "Machine.Calibrate",
+                &calibrate(),
+                vec![property("offset", DataXsd::Double(Some(1.5)))],
+                vec![property("counter", DataXsd::UnsignedInt(Some(4)))],
+            )
+        };
+
+        assert_eq!(
+            invoke(&registry),
+            Err(InvocationError::MissingOutput("applied".into()))
+        );
+
+        let mut registry = OperationRegistry::new();
+        registry.bind_id_short_path("Machine.Calibrate", |invocation: &mut Invocation| {
+            invocation.set_output("speed", 1.0)
+        });
+        assert_eq!(
+            invoke(&registry),
+            Err(InvocationError::UnknownArgument("speed".into()))
+        );
+    }
+
+    #[test]
+    fn no_handler() {
+        assert_eq!(
+            OperationRegistry::new().invoke("Machine.Reset", &Operation::default(), vec![], vec![]),
+            Err(InvocationError::NoHandler("Machine.Reset".into()))
+        );
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumString};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum::{Display, EnumDiscriminants, EnumString};
+use thiserror::Error;
 
 pub type KeyReference = String;
 
@@ -18,7 +22,13 @@ pub type KeyReference = String;
 /// Other key types identify specific model elements (like Submodel, SubmodelElement, or Asset)
 /// within the same or another AAS,
 /// explicitly naming the referenced element to enable precise navigation within the AAS environment.
-#[derive(EnumString, Display, Clone, PartialEq, Debug, Deserialize, Serialize)]
+///
+/// The text serialization of Part 2 is used by `Display` and `FromStr`, i.e. `(Submodel)urn:x:sm`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize, EnumDiscriminants)]
+#[strum_discriminants(
+    name(KeyTypes),
+    derive(EnumString, Display, Hash, Serialize, Deserialize)
+)]
 #[serde(tag = "type", content = "value")]
 pub enum Key {
     AnnotatedRelationshipElement(KeyReference),
@@ -47,6 +57,151 @@ pub enum Key {
     SubmodelElementList(KeyReference),
 }
 
+impl Key {
+    pub fn new(key_type: KeyTypes, value: KeyReference) -> Self {
+        match key_type {
+            KeyTypes::AnnotatedRelationshipElement => Key::AnnotatedRelationshipElement(value),
+            KeyTypes::AssetAdministrationShell => Key::AssetAdministrationShell(value),
+            KeyTypes::BasicEventElement => Key::BasicEventElement(value),
+            KeyTypes::Blob => Key::Blob(value),
+            KeyTypes::Capability => Key::Capability(value),
+            KeyTypes::ConceptDescription => Key::ConceptDescription(value),
+            KeyTypes::DataElement => Key::DataElement(value),
+            KeyTypes::Entity => Key::Entity(value),
+            KeyTypes::EventElement => Key::EventElement(value),
+            KeyTypes::File => Key::File(value),
+            KeyTypes::FragmentReference => Key::FragmentReference(value),
+            KeyTypes::GlobalReference => Key::GlobalReference(value),
+            KeyTypes::Identifiable => Key::Identifiable(value),
+            KeyTypes::MultiLanguageProperty => Key::MultiLanguageProperty(value),
+            KeyTypes::Operation => Key::Operation(value),
+            KeyTypes::Property => Key::Property(value),
+            KeyTypes::Range => Key::Range(value),
+            KeyTypes::Referable => Key::Referable(value),
+            KeyTypes::ReferenceElement => Key::ReferenceElement(value),
+            KeyTypes::RelationshipElement => Key::RelationshipElement(value),
+            KeyTypes::Submodel => Key::Submodel(value),
+            KeyTypes::SubmodelElement => Key::SubmodelElement(value),
+            KeyTypes::SubmodelElementCollection => Key::SubmodelElementCollection(value),
+            KeyTypes::SubmodelElementList => Key::SubmodelElementList(value),
+        }
+    }
+
+    pub fn key_type(&self) -> KeyTypes {
+        KeyTypes::from(self)
+    }
+
+    pub fn value(&self) -> &KeyReference {
+        match self {
+            Key::AnnotatedRelationshipElement(value)
+            | Key::AssetAdministrationShell(value)
+            | Key::BasicEventElement(value)
+            | Key::Blob(value)
+            | Key::Capability(value)
+            | Key::ConceptDescription(value)
+            | Key::DataElement(value)
+            | Key::Entity(value)
+            | Key::EventElement(value)
+            | Key::File(value)
+            | Key::FragmentReference(value)
+            | Key::GlobalReference(value)
+            | Key::Identifiable(value)
+            | Key::MultiLanguageProperty(value)
+            | Key::Operation(value)
+            | Key::Property(value)
+            | Key::Range(value)
+            | Key::Referable(value)
+            | Key::ReferenceElement(value)
+            | Key::RelationshipElement(value)
+            | Key::Submodel(value)
+            | Key::SubmodelElement(value)
+            | Key::SubmodelElementCollection(value)
+            | Key::SubmodelElementList(value) => value,
+        }
+    }
+
+    pub fn value_mut(&mut self) -> &mut KeyReference {
+        match self {
+            Key::AnnotatedRelationshipElement(value)
+            | Key::AssetAdministrationShell(value)
+            | Key::BasicEventElement(value)
+            | Key::Blob(value)
+            | Key::Capability(value)
+            | Key::ConceptDescription(value)
+            | Key::DataElement(value)
+            | Key::Entity(value)
+            | Key::EventElement(value)
+            | Key::File(value)
+            | Key::FragmentReference(value)
+            | Key::GlobalReference(value)
+            | Key::Identifiable(value)
+            | Key::MultiLanguageProperty(value)
+            | Key::Operation(value)
+            | Key::Property(value)
+            | Key::Range(value)
+            | Key::Referable(value)
+            | Key::ReferenceElement(value)
+            | Key::RelationshipElement(value)
+            | Key::Submodel(value)
+            | Key::SubmodelElement(value)
+            | Key::SubmodelElementCollection(value)
+            | Key::SubmodelElementList(value) => value,
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum KeyParseError {
+    #[error("A key has to be written as (KeyType)value")]
+    IncorrectFormat,
+
+    #[error("Unknown key type {0}")]
+    UnknownKeyType(String),
+
+    #[error("The key value can not be empty")]
+    EmptyValue,
+}
+
+impl Display for Key {
+    /// Formats as `(KeyType)value`, i.e. `(Submodel)urn:x:sm`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}){}", self.key_type(), self.value())
+    }
+}
+
+impl FromStr for Key {
+    type Err = KeyParseError;
+
+    /// Parse from `(KeyType)value`, i.e. `(Submodel)urn:x:sm`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use aas::part_1::v3_1::Key;
+    ///
+    /// let key = Key::from_str("(Property)temperature").unwrap();
+    ///
+    /// assert_eq!(key, Key::Property("temperature".to_string()));
+    /// assert_eq!(key.to_string(), "(Property)temperature");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key_type, value) = s
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.split_once(')'))
+            .ok_or(KeyParseError::IncorrectFormat)?;
+
+        let key_type = KeyTypes::from_str(key_type)
+            .map_err(|_| KeyParseError::UnknownKeyType(key_type.to_string()))?;
+
+        if value.is_empty() {
+            return Err(KeyParseError::EmptyValue);
+        }
+
+        Ok(Key::new(key_type, value.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,4 +221,29 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn display() {
+        let key = Key::Submodel("urn:x:sm".to_string());
+
+        assert_eq!(key.to_string(), "(Submodel)urn:x:sm");
+        assert_eq!(key.key_type(), KeyTypes::Submodel);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            Key::from_str("(GlobalReference)0173-1#02-AAO677#002"),
+            Ok(Key::GlobalReference("0173-1#02-AAO677#002".to_string()))
+        );
+        assert_eq!(
+            Key::from_str("(Unknown)x"),
+            Err(KeyParseError::UnknownKeyType("Unknown".to_string()))
+        );
+        assert_eq!(Key::from_str("(Submodel)"), Err(KeyParseError::EmptyValue));
+        assert_eq!(
+            Key::from_str("Submodel"),
+            Err(KeyParseError::IncorrectFormat)
+        );
+    }
 }
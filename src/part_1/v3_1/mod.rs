@@ -1,11 +1,26 @@
 mod environment;
+pub use environment::*;
 pub use primitives::lang_string::*;
-mod attributes;
+pub mod aid;
+pub mod attributes;
+pub mod binding;
 mod concept_description;
+pub use concept_description::*;
 pub mod core;
+pub mod events;
+pub mod history;
+pub mod id_generator;
+pub mod invocation;
 mod key;
+pub use key::*;
 mod level_type;
-mod primitives;
+pub use level_type::*;
+pub mod observable;
+pub mod primitives;
+pub mod redaction;
 mod reference;
-mod submodel_elements;
+mod references;
+pub use reference::*;
+pub mod submodel_elements;
 mod value_list;
+pub use value_list::*;
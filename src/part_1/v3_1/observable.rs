@@ -0,0 +1,946 @@
+//! Change notifications for submodels.
+//!
+//! [`ObservableSubmodel`] and [`ObservableEnvironment`] own the model and route every mutation
+//! through [`ObservableSubmodel::update`] and friends. The model before and after the mutation is
+//! compared and each difference is published as a [`ChangeEvent`] with the idShort path of the
+//! changed element. Subscribers receive the events matching their [`ChangeFilter`] on a channel.
+//!
+//! ```
+//! use aas::part_1::v3_1::core::Submodel;
+//! use aas::part_1::v3_1::observable::{ChangeFilter, ChangeKind, ObservableSubmodel};
+//! use aas::part_1::v3_1::submodel_elements::SubmodelElement;
+//!
+//! let submodel: Submodel = serde_json::from_str(r#"{
+//!     "id": "https://example.com/submodels/1",
+//!     "submodelElements": [
+//!         {"modelType": "Property", "idShort": "Speed", "valueType": "xs:string", "value": "1"}
+//!     ]
+//! }"#).unwrap();
+//!
+//! let mut observable = ObservableSubmodel::new(submodel);
+//! let changes = observable.subscribe(ChangeFilter::all());
+//!
+//! observable.update("Speed", |element| {
+//!     if let SubmodelElement::Property(property) = element {
+//!         property.set("2".to_string()).unwrap();
+//!     }
+//! }).unwrap();
+//!
+//! let change = changes.try_recv().unwrap();
+//! assert_eq!(change.kind, ChangeKind::ValueChanged);
+//! assert_eq!(change.path, "Speed");
+//! assert_eq!(change.new, Some(serde_json::json!({"value": "2"})));
+//! ```
+
+use crate::part_1::v3_1::Environment;
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::primitives::DateTimeUTC;
+use crate::part_1::v3_1::reference::Reference;
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::mpsc::{self, Receiver, Sender};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ObserveError {
+    #[error("No submodel found with id {0}")]
+    UnknownSubmodel(String),
+
+    #[error("A submodel with id {0} already exists")]
+    DuplicateSubmodel(String),
+
+    #[error("No submodel element found at {0}")]
+    UnknownElement(String),
+
+    #[error("The element at {0} can't contain other elements")]
+    NotAContainer(String),
+
+    #[error("An element with idShort {0} already exists")]
+    DuplicateIdShort(String),
+
+    #[error("Elements of collections need an idShort")]
+    MissingIdShort,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    ElementAdded,
+    ElementRemoved,
+
+    /// The value of the element changed, i.e. `value` of a `Property` or `min` and `max` of a
+    /// `Range`. Child elements of collections, lists and entities are reported on their own.
+    ValueChanged,
+
+    /// Any other attribute changed, i.e. `description` or `semanticId`.
+    MetadataChanged,
+}
+
+/// A single change of a submodel.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    /// Increases with every event of an observable model.
+    pub sequence: u64,
+    pub timestamp: DateTimeUTC,
+    pub submodel_id: String,
+
+    /// idShort path of the changed element, empty for the submodel itself.
+    pub path: String,
+
+    /// Semantic id of the changed element.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_id: Option<Reference>,
+    pub kind: ChangeKind,
+
+    /// Who made the change, see [`ObservableSubmodel::set_author`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// JSON of the changed part before the change: the whole element for removals, the value
+    /// attributes for value changes and all other attributes for metadata changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+
+    /// JSON of the changed part after the change, like `old`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Selects the events a subscriber receives. All conditions have to hold.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ChangeFilter {
+    pub submodel_id: Option<String>,
+
+    /// Only the element at this idShort path and its descendants.
+    pub path_prefix: Option<String>,
+
+    /// Only elements with this semantic id, see [`Reference::matches_semantic_id`].
+    pub semantic_id: Option<Reference>,
+}
+
+impl ChangeFilter {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn path_prefix(path_prefix: impl Into<String>) -> Self {
+        Self {
+            path_prefix: Some(path_prefix.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn semantic_id(semantic_id: Reference) -> Self {
+        Self {
+            semantic_id: Some(semantic_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, event: &ChangeEvent) -> bool {
+        self.submodel_id
+            .as_ref()
+            .is_none_or(|submodel_id| submodel_id == &event.submodel_id)
+            && self
+                .path_prefix
+                .as_ref()
+                .is_none_or(|prefix| strip_path_prefix(prefix, &event.path).is_some())
+            && self.semantic_id.as_ref().is_none_or(|semantic_id| {
+                event
+                    .semantic_id
+                    .as_ref()
+                    .is_some_and(|own| own.matches_semantic_id(semantic_id))
+            })
+    }
+}
+
+/// The rest of the idShort `path` below `prefix`, i.e. `.Speed` for `Motor.Speed`.
+/// `Motor` is a prefix of `Motor`, `Motor.Speed` and `Motor[0]`, but not of `MotorSpeed`.
+/// The empty prefix is a prefix of every path.
+pub(crate) fn strip_path_prefix<'a>(prefix: &str, path: &'a str) -> Option<&'a str> {
+    path.strip_prefix(prefix)
+        .filter(|rest| prefix.is_empty() || rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+/// A difference between two states of a submodel, see [`diff`]. Published as [`ChangeEvent`].
+#[derive(Clone, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Difference {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_id: Option<Reference>,
+    pub kind: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Subscribers and numbering, shared by the observable submodel and environment.
+#[derive(Default)]
+struct Publisher {
+    subscribers: Vec<(ChangeFilter, Sender<ChangeEvent>)>,
+    sequence: u64,
+    author: Option<String>,
+}
+
+impl Publisher {
+    fn subscribe(&mut self, filter: ChangeFilter) -> Receiver<ChangeEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push((filter, sender));
+        receiver
+    }
+
+    fn publish(&mut self, submodel_id: &str, changes: Vec<Difference>) {
+        let timestamp = Utc::now();
+
+        for change in changes {
+            self.sequence += 1;
+            let event = ChangeEvent {
+                sequence: self.sequence,
+                timestamp,
+                submodel_id: submodel_id.to_string(),
+                path: change.path,
+                semantic_id: change.semantic_id,
+                kind: change.kind,
+                author: self.author.clone(),
+                old: change.old,
+                new: change.new,
+            };
+
+            // Subscribers which dropped their receiver are removed.
+            self.subscribers.retain(|(filter, sender)| {
+                !filter.matches(&event) || sender.send(event.clone()).is_ok()
+            });
+        }
+    }
+}
+
+/// A submodel publishing its changes.
+pub struct ObservableSubmodel {
+    submodel: Submodel,
+    publisher: Publisher,
+}
+
+impl ObservableSubmodel {
+    pub fn new(submodel: Submodel) -> Self {
+        Self {
+            submodel,
+            publisher: Publisher::default(),
+        }
+    }
+
+    pub fn submodel(&self) -> &Submodel {
+        &self.submodel
+    }
+
+    pub fn into_inner(self) -> Submodel {
+        self.submodel
+    }
+
+    /// Receives every following change matching `filter`.
+    pub fn subscribe(&mut self, filter: ChangeFilter) -> Receiver<ChangeEvent> {
+        self.publisher.subscribe(filter)
+    }
+
+    /// Attributes the following changes to `author`, i.e. a user or a service.
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.publisher.author = author;
+    }
+
+    /// Mutates the element at `path` and publishes the resulting changes.
+    pub fn update<R>(
+        &mut self,
+        path: &str,
+        f: impl FnOnce(&mut SubmodelElement) -> R,
+    ) -> Result<R, ObserveError> {
+        let (result, changes) = update_element(&mut self.submodel, path, f)?;
+        self.publisher.publish(self.submodel.id(), changes);
+        Ok(result)
+    }
+
+    /// Mutates the submodel itself and publishes the resulting changes.
+    pub fn update_submodel<R>(&mut self, f: impl FnOnce(&mut Submodel) -> R) -> R {
+        let (result, changes) = update_submodel(&mut self.submodel, f);
+        self.publisher.publish(self.submodel.id(), changes);
+        result
+    }
+
+    /// Appends `element` to the collection, list or entity at `parent`, or to the submodel if
+    /// `parent` is empty.
+    pub fn add(&mut self, parent: &str, element: SubmodelElement) -> Result<(), ObserveError> {
+        let changes = add_element(&mut self.submodel, parent, element)?;
+        self.publisher.publish(self.submodel.id(), changes);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &str) -> Result<SubmodelElement, ObserveError> {
+        let (element, changes) = remove_element(&mut self.submodel, path)?;
+        self.publisher.publish(self.submodel.id(), changes);
+        Ok(element)
+    }
+}
+
+/// An environment publishing the changes of its submodels.
+pub struct ObservableEnvironment {
+    environment: Environment,
+    publisher: Publisher,
+}
+
+impl ObservableEnvironment {
+    pub fn new(environment: Environment) -> Self {
+        Self {
+            environment,
+            publisher: Publisher::default(),
+        }
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn into_inner(self) -> Environment {
+        self.environment
+    }
+
+    /// Receives every following change matching `filter`.
+    pub fn subscribe(&mut self, filter: ChangeFilter) -> Receiver<ChangeEvent> {
+        self.publisher.subscribe(filter)
+    }
+
+    /// Attributes the following changes to `author`, i.e. a user or a service.
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.publisher.author = author;
+    }
+
+    pub fn update<R>(
+        &mut self,
+        submodel_id: &str,
+        path: &str,
+        f: impl FnOnce(&mut SubmodelElement) -> R,
+    ) -> Result<R, ObserveError> {
+        let (result, changes) = update_element(self.submodel_mut(submodel_id)?, path, f)?;
+        self.publisher.publish(submodel_id, changes);
+        Ok(result)
+    }
+
+    pub fn update_submodel<R>(
+        &mut self,
+        submodel_id: &str,
+        f: impl FnOnce(&mut Submodel) -> R,
+    ) -> Result<R, ObserveError> {
+        let (result, changes) = update_submodel(self.submodel_mut(submodel_id)?, f);
+        self.publisher.publish(submodel_id, changes);
+        Ok(result)
+    }
+
+    pub fn add(
+        &mut self,
+        submodel_id: &str,
+        parent: &str,
+        element: SubmodelElement,
+    ) -> Result<(), ObserveError> {
+        let changes = add_element(self.submodel_mut(submodel_id)?, parent, element)?;
+        self.publisher.publish(submodel_id, changes);
+        Ok(())
+    }
+
+    pub fn remove(
+        &mut self,
+        submodel_id: &str,
+        path: &str,
+    ) -> Result<SubmodelElement, ObserveError> {
+        let (element, changes) = remove_element(self.submodel_mut(submodel_id)?, path)?;
+        self.publisher.publish(submodel_id, changes);
+        Ok(element)
+    }
+
+    /// Adds a submodel, published as an addition with an empty path.
+    pub fn add_submodel(&mut self, submodel: Submodel) -> Result<(), ObserveError> {
+        let submodel_id = submodel.id().to_string();
+        if self.environment.submodel(&submodel_id).is_some() {
+            return Err(ObserveError::DuplicateSubmodel(submodel_id));
+        }
+
+        let change = Difference {
+            path: String::new(),
+            semantic_id: submodel.semantics.semantic_id.clone(),
+            kind: ChangeKind::ElementAdded,
+            old: None,
+            new: Some(to_json(&submodel)),
+        };
+        self.environment
+            .submodels
+            .get_or_insert_default()
+            .push(submodel);
+        self.publisher.publish(&submodel_id, vec![change]);
+        Ok(())
+    }
+
+    pub fn remove_submodel(&mut self, submodel_id: &str) -> Result<Submodel, ObserveError> {
+        let submodels = self.environment.submodels.get_or_insert_default();
+        let index = submodels
+            .iter()
+            .position(|submodel| submodel.id().to_string() == submodel_id)
+            .ok_or_else(|| ObserveError::UnknownSubmodel(submodel_id.to_string()))?;
+        let submodel = submodels.remove(index);
+
+        let change = Difference {
+            path: String::new(),
+            semantic_id: submodel.semantics.semantic_id.clone(),
+            kind: ChangeKind::ElementRemoved,
+            old: Some(to_json(&submodel)),
+            new: None,
+        };
+        self.publisher.publish(submodel_id, vec![change]);
+        Ok(submodel)
+    }
+
+    fn submodel_mut(&mut self, submodel_id: &str) -> Result<&mut Submodel, ObserveError> {
+        self.environment
+            .submodel_mut(submodel_id)
+            .ok_or_else(|| ObserveError::UnknownSubmodel(submodel_id.to_string()))
+    }
+}
+
+fn update_element<R>(
+    submodel: &mut Submodel,
+    path: &str,
+    f: impl FnOnce(&mut SubmodelElement) -> R,
+) -> Result<(R, Vec<Difference>), ObserveError> {
+    let element = submodel
+        .element_mut(path)
+        .ok_or_else(|| ObserveError::UnknownElement(path.to_string()))?;
+
+    let old = element.clone();
+    let result = f(element);
+
+    let mut changes = Vec::new();
+    diff_element(path, &old, element, &mut changes);
+    Ok((result, changes))
+}
+
+/// The differences between two states of a submodel, as they would be published.
+pub fn diff(old: &Submodel, new: &Submodel) -> Vec<Difference> {
+    let mut changes = Vec::new();
+    let (old_metadata, new_metadata) = (
+        without(to_json(old), &["submodelElements"]),
+        without(to_json(new), &["submodelElements"]),
+    );
+    if old_metadata != new_metadata {
+        changes.push(Difference {
+            path: String::new(),
+            semantic_id: new.semantics.semantic_id.clone(),
+            kind: ChangeKind::MetadataChanged,
+            old: Some(old_metadata),
+            new: Some(new_metadata),
+        });
+    }
+
+    diff_children(
+        "",
+        old.submodel_elements.as_deref().unwrap_or_default(),
+        new.submodel_elements.as_deref().unwrap_or_default(),
+        false,
+        &mut changes,
+    );
+    changes
+}
+
+fn update_submodel<R>(
+    submodel: &mut Submodel,
+    f: impl FnOnce(&mut Submodel) -> R,
+) -> (R, Vec<Difference>) {
+    let old = submodel.clone();
+    let result = f(submodel);
+
+    (result, diff(&old, submodel))
+}
+
+pub(crate) fn add_element(
+    submodel: &mut Submodel,
+    parent: &str,
+    element: SubmodelElement,
+) -> Result<Vec<Difference>, ObserveError> {
+    if parent.is_empty() {
+        let (inserted, changes) = update_submodel(submodel, |submodel| {
+            insert(
+                submodel.submodel_elements.get_or_insert_default(),
+                false,
+                element,
+            )
+        });
+        return inserted.map(|()| changes);
+    }
+
+    let (inserted, changes) = update_element(submodel, parent, |container| {
+        let in_list = matches!(container, SubmodelElement::SubmodelElementList(_));
+        match children_mut(container) {
+            Some(children) => insert(children, in_list, element),
+            None => Err(ObserveError::NotAContainer(parent.to_string())),
+        }
+    })?;
+    inserted.map(|()| changes)
+}
+
+/// Appends `element`, the idShort has to be unique among the elements of a collection.
+fn insert(
+    elements: &mut Vec<SubmodelElement>,
+    in_list: bool,
+    element: SubmodelElement,
+) -> Result<(), ObserveError> {
+    if !in_list {
+        let id_short = element.id_short().ok_or(ObserveError::MissingIdShort)?;
+        if elements
+            .iter()
+            .any(|existing| existing.id_short() == Some(id_short))
+        {
+            return Err(ObserveError::DuplicateIdShort(id_short.to_string()));
+        }
+    }
+
+    elements.push(element);
+    Ok(())
+}
+
+pub(crate) fn remove_element(
+    submodel: &mut Submodel,
+    path: &str,
+) -> Result<(SubmodelElement, Vec<Difference>), ObserveError> {
+    let unknown = || ObserveError::UnknownElement(path.to_string());
+    let split = path.rfind(['.', '[']);
+    let parent = split.map_or("", |split| &path[..split]);
+    let last = split.map_or(path, |split| &path[split + 1..]);
+
+    let take = |elements: &mut Vec<SubmodelElement>| {
+        let index = match last.strip_suffix(']') {
+            Some(index) => index.parse().ok().filter(|index| *index < elements.len()),
+            None => elements
+                .iter()
+                .position(|element| element.id_short() == Some(last)),
+        };
+        index.map(|index| elements.remove(index))
+    };
+
+    let (removed, changes) = match parent {
+        "" => update_submodel(submodel, |submodel| {
+            submodel.submodel_elements.as_mut().and_then(take)
+        }),
+        parent => update_element(submodel, parent, |container| {
+            children_mut(container).and_then(take)
+        })
+        .map_err(|_| unknown())?,
+    };
+
+    Ok((removed.ok_or_else(unknown)?, changes))
+}
+
+/// The children of a collection, list or entity, created if absent.
+fn children_mut(element: &mut SubmodelElement) -> Option<&mut Vec<SubmodelElement>> {
+    match element {
+        SubmodelElement::SubmodelElementCollection(collection) => {
+            Some(collection.value.get_or_insert_default())
+        }
+        SubmodelElement::SubmodelElementList(list) => Some(list.value.get_or_insert_default()),
+        SubmodelElement::Entity(entity) => {
+            Some(entity.inner_mut().statement.get_or_insert_default())
+        }
+        _ => None,
+    }
+}
+
+/// Attributes holding the value of an element, as named in JSON.
+pub(crate) const VALUE_ATTRIBUTES: &[&str] = &[
+    "value",
+    "valueId",
+    "min",
+    "max",
+    "first",
+    "second",
+    "annotations",
+    "globalAssetId",
+    "specificAssetIds",
+];
+
+/// Attribute holding the children, reported as elements of their own.
+pub(crate) fn children_attribute(element: &SubmodelElement) -> Option<&'static str> {
+    match element {
+        SubmodelElement::SubmodelElementCollection(_) | SubmodelElement::SubmodelElementList(_) => {
+            Some("value")
+        }
+        SubmodelElement::Entity(_) => Some("statements"),
+        _ => None,
+    }
+}
+
+fn diff_element(
+    path: &str,
+    old: &SubmodelElement,
+    new: &SubmodelElement,
+    changes: &mut Vec<Difference>,
+) {
+    let split = |element: &SubmodelElement| {
+        let Value::Object(mut json) = to_json(element) else {
+            return (Map::new(), Map::new());
+        };
+        if let Some(children) = children_attribute(element) {
+            json.remove(children);
+        }
+
+        let value = VALUE_ATTRIBUTES
+            .iter()
+            .filter_map(|name| Some((name.to_string(), json.remove(*name)?)))
+            .collect::<Map<_, _>>();
+        (value, json)
+    };
+
+    let (old_value, old_metadata) = split(old);
+    let (new_value, new_metadata) = split(new);
+    let semantic_id = new.semantics().semantic_id.clone();
+
+    if old_metadata != new_metadata {
+        changes.push(Difference {
+            path: path.to_string(),
+            semantic_id: semantic_id.clone(),
+            kind: ChangeKind::MetadataChanged,
+            old: Some(Value::Object(old_metadata)),
+            new: Some(Value::Object(new_metadata)),
+        });
+    }
+    if old_value != new_value {
+        changes.push(Difference {
+            path: path.to_string(),
+            semantic_id,
+            kind: ChangeKind::ValueChanged,
+            old: Some(Value::Object(old_value)),
+            new: Some(Value::Object(new_value)),
+        });
+    }
+
+    if let (Some(old_children), Some(new_children)) = (old.children(), new.children()) {
+        let in_list = matches!(new, SubmodelElement::SubmodelElementList(_));
+        diff_children(path, old_children, new_children, in_list, changes);
+    }
+}
+
+/// Elements of lists are matched by index, all others by idShort.
+/// Elements without an idShort outside of lists have no counterpart.
+fn diff_children(
+    parent: &str,
+    old: &[SubmodelElement],
+    new: &[SubmodelElement],
+    in_list: bool,
+    changes: &mut Vec<Difference>,
+) {
+    let path = |index: usize, element: &SubmodelElement| match (in_list, parent) {
+        (true, _) => format!("{parent}[{index}]"),
+        (false, "") => element.id_short().unwrap_or_default().to_string(),
+        (false, _) => format!("{parent}.{}", element.id_short().unwrap_or_default()),
+    };
+    for (index, element) in old.iter().enumerate() {
+        if counterpart(new, index, element, in_list).is_none() {
+            changes.push(Difference {
+                path: path(index, element),
+                semantic_id: element.semantics().semantic_id.clone(),
+                kind: ChangeKind::ElementRemoved,
+                old: Some(to_json(element)),
+                new: None,
+            });
+        }
+    }
+
+    for (index, element) in new.iter().enumerate() {
+        match counterpart(old, index, element, in_list) {
+            Some(old) => diff_element(&path(index, element), old, element, changes),
+            None => changes.push(Difference {
+                path: path(index, element),
+                semantic_id: element.semantics().semantic_id.clone(),
+                kind: ChangeKind::ElementAdded,
+                old: None,
+                new: Some(to_json(element)),
+            }),
+        }
+    }
+}
+
+fn counterpart<'a>(
+    elements: &'a [SubmodelElement],
+    index: usize,
+    element: &SubmodelElement,
+    in_list: bool,
+) -> Option<&'a SubmodelElement> {
+    match in_list {
+        true => elements.get(index),
+        false => {
+            let id_short = element.id_short()?;
+            elements
+                .iter()
+                .find(|other| other.id_short() == Some(id_short))
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+fn without(value: Value, names: &[&str]) -> Value {
+    match value {
+        Value::Object(mut object) => {
+            names.iter().for_each(|name| {
+                object.remove(*name);
+            });
+            Value::Object(object)
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn submodel() -> Submodel {
+        serde_json::from_str(
+            r#"{
+                "id": "https://example.com/submodels/1",
+                "submodelElements": [
+                    {"modelType": "Property", "idShort": "Speed", "valueType": "xs:string",
+                     "semanticId": {"type": "ExternalReference",
+                        "keys": [{"type": "GlobalReference", "value": "urn:speed"}]}},
+                    {"modelType": "SubmodelElementCollection", "idShort": "Motor", "value": [
+                        {"modelType": "Property", "idShort": "Temperature",
+                         "valueType": "xs:string", "value": "20"}
+                    ]},
+                    {"modelType": "Property", "idShort": "MotorSpeed", "valueType": "xs:string"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn property(id_short: &str, value: &str) -> SubmodelElement {
+        serde_json::from_value(serde_json::json!({
+            "modelType": "Property", "idShort": id_short, "valueType": "xs:string", "value": value
+        }))
+        .unwrap()
+    }
+
+    fn set(element: &mut SubmodelElement, value: &str) {
+        let SubmodelElement::Property(property) = element else {
+            panic!("expected a Property")
+        };
+        property.set(value.to_string()).unwrap();
+    }
+
+    #[test]
+    fn value_and_metadata_changes() {
+        let mut observable = ObservableSubmodel::new(submodel());
+        let changes = observable.subscribe(ChangeFilter::all());
+        observable.set_author(Some("operator".into()));
+
+        observable.update("Speed", |speed| set(speed, "5")).unwrap();
+        observable
+            .update("Speed", |speed| {
+                speed.semantics_mut().semantic_id = None;
+            })
+            .unwrap();
+
+        let events: Vec<_> = changes.try_iter().collect();
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(events[0].sequence, 1);
+        assert_eq!(events[0].kind, ChangeKind::ValueChanged);
+        assert_eq!(events[0].author.as_deref(), Some("operator"));
+        assert_eq!(events[0].old, Some(serde_json::json!({"value": null})));
+        assert_eq!(events[0].new, Some(serde_json::json!({"value": "5"})));
+
+        assert_eq!(events[1].sequence, 2);
+        assert_eq!(events[1].kind, ChangeKind::MetadataChanged);
+        assert!(events[1].old.as_ref().unwrap().get("semanticId").is_some());
+        assert!(events[1].new.as_ref().unwrap().get("semanticId").is_none());
+
+        // Nothing changed, nothing published.
+        observable.update("Speed", |_| ()).unwrap();
+        assert!(changes.try_recv().is_err());
+
+        assert_eq!(
+            observable.update("Missing", |_| ()),
+            Err(ObserveError::UnknownElement("Missing".into()))
+        );
+    }
+
+    #[test]
+    fn additions_and_removals() {
+        let mut observable = ObservableSubmodel::new(submodel());
+        let motor = observable.subscribe(ChangeFilter::path_prefix("Motor"));
+
+        observable.add("Motor", property("Current", "1")).unwrap();
+        observable.add("", property("Torque", "2")).unwrap();
+        assert_eq!(
+            observable.add("Motor", property("Current", "1")),
+            Err(ObserveError::DuplicateIdShort("Current".into()))
+        );
+        assert_eq!(
+            observable.add("Speed", property("Current", "1")),
+            Err(ObserveError::NotAContainer("Speed".into()))
+        );
+
+        let removed = observable.remove("Motor.Temperature").unwrap();
+        assert_eq!(removed.id_short(), Some("Temperature"));
+        observable.remove("Torque").unwrap();
+        observable
+            .update("MotorSpeed", |speed| set(speed, "1"))
+            .unwrap();
+        assert_eq!(
+            observable.remove("Motor.Temperature"),
+            Err(ObserveError::UnknownElement("Motor.Temperature".into()))
+        );
+
+        let events: Vec<_> = motor
+            .try_iter()
+            .map(|event| (event.kind, event.path))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (ChangeKind::ElementAdded, "Motor.Current".to_string()),
+                (ChangeKind::ElementRemoved, "Motor.Temperature".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn elements_without_id_short() {
+        let mut old = submodel();
+        old.submodel_elements
+            .get_or_insert_default()
+            .push(property("Anonymous", "1"));
+        let mut new = old.clone();
+        for submodel in [&mut old, &mut new] {
+            submodel.submodel_elements.as_mut().unwrap()[3]
+                .referable_mut()
+                .id_short = None;
+        }
+        set(&mut new.submodel_elements.as_mut().unwrap()[3], "2");
+
+        let kinds: Vec<_> = diff(&old, &new)
+            .into_iter()
+            .map(|difference| difference.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![ChangeKind::ElementRemoved, ChangeKind::ElementAdded]
+        );
+    }
+
+    #[test]
+    fn nested_changes_and_semantic_filter() {
+        let mut observable = ObservableSubmodel::new(submodel());
+        let all = observable.subscribe(ChangeFilter::all());
+        let speed = observable.subscribe(ChangeFilter::semantic_id(
+            Reference::from_str("[ExternalRef](GlobalReference)urn:speed").unwrap(),
+        ));
+
+        // Changing the children of a collection is reported per child.
+        observable
+            .update("Motor", |motor| {
+                let children = motor.children_mut().unwrap();
+                set(&mut children[0], "25");
+                children.push(property("Current", "1"));
+            })
+            .unwrap();
+        observable.update_submodel(|submodel| {
+            let elements = submodel.submodel_elements.as_mut().unwrap();
+            set(&mut elements[0], "7");
+        });
+
+        let events: Vec<_> = all
+            .try_iter()
+            .map(|event| (event.kind, event.path))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (ChangeKind::ValueChanged, "Motor.Temperature".to_string()),
+                (ChangeKind::ElementAdded, "Motor.Current".to_string()),
+                (ChangeKind::ValueChanged, "Speed".to_string()),
+            ]
+        );
+
+        let events: Vec<_> = speed.try_iter().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, "Speed");
+    }
+
+    #[test]
+    fn environment() {
+        let mut observable = ObservableEnvironment::new(Environment {
+            asset_administration_shells: None,
+            submodels: Some(vec![submodel()]),
+            concept_descriptions: None,
+        });
+        let changes = observable.subscribe(ChangeFilter {
+            submodel_id: Some("https://example.com/submodels/2".into()),
+            ..Default::default()
+        });
+
+        observable
+            .update("https://example.com/submodels/1", "Speed", |speed| {
+                set(speed, "1")
+            })
+            .unwrap();
+
+        let mut second = submodel();
+        second.identifiable.id = "https://example.com/submodels/2".try_into().unwrap();
+        observable.add_submodel(second.clone()).unwrap();
+        assert_eq!(
+            observable.add_submodel(second),
+            Err(ObserveError::DuplicateSubmodel(
+                "https://example.com/submodels/2".into()
+            ))
+        );
+        observable
+            .add(
+                "https://example.com/submodels/2",
+                "",
+                property("Torque", "2"),
+            )
+            .unwrap();
+        observable
+            .remove_submodel("https://example.com/submodels/2")
+            .unwrap();
+        assert_eq!(
+            observable.update("https://example.com/submodels/2", "Speed", |_| ()),
+            Err(ObserveError::UnknownSubmodel(
+                "https://example.com/submodels/2".into()
+            ))
+        );
+
+        let events: Vec<_> = changes
+            .try_iter()
+            .map(|event| (event.kind, event.path))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (ChangeKind::ElementAdded, String::new()),
+                (ChangeKind::ElementAdded, "Torque".to_string()),
+                (ChangeKind::ElementRemoved, String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_prefix() {
+        assert_eq!(strip_path_prefix("Motor", "Motor"), Some(""));
+        assert_eq!(strip_path_prefix("Motor", "Motor.Speed"), Some(".Speed"));
+        assert_eq!(strip_path_prefix("Motor", "Motor[0]"), Some("[0]"));
+        assert_eq!(strip_path_prefix("Motor", "MotorSpeed"), None);
+        assert_eq!(strip_path_prefix("", "Speed"), Some("Speed"));
+    }
+}
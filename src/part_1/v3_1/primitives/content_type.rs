@@ -0,0 +1,413 @@
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Newtype-pattern around a media type according to RFC 2045, i.e. `text/plain; charset=utf-8`.
+/// Constraints:
+/// string with max 128 and min 1 characters
+///
+/// The media type is normalized: type, subtype and parameter names are lowercase,
+/// parameters are separated by `; `. Parameter values keep their case.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize)]
+pub struct ContentType(String);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContentTypeError {
+    #[error("The content type needs at lest 1 character")]
+    TooShort,
+
+    #[error("The content type can hold at most 128 characters")]
+    TooLong,
+
+    #[error("The content type needs a type and a subtype separated by '/'")]
+    MissingSubtype,
+
+    #[error("Invalid token found: {0}")]
+    InvalidToken(String),
+
+    #[error("Invalid parameter found: {0}")]
+    InvalidParameter(String),
+}
+
+impl Display for ContentType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// `application/octet-stream`, arbitrary binary data.
+impl Default for ContentType {
+    fn default() -> Self {
+        ContentType("application/octet-stream".to_string())
+    }
+}
+
+impl ContentType {
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// The media type without parameters, i.e. `text/plain`.
+    pub fn essence(&self) -> &str {
+        self.0.split(';').next().unwrap_or_default()
+    }
+
+    /// The top-level type, i.e. `text` for `text/plain`.
+    pub fn main_type(&self) -> &str {
+        self.essence().split('/').next().unwrap_or_default()
+    }
+
+    /// The subtype including a structured syntax suffix, i.e. `ld+json` for `application/ld+json`.
+    pub fn subtype(&self) -> &str {
+        self.essence().split('/').nth(1).unwrap_or_default()
+    }
+
+    /// The structured syntax suffix, i.e. `json` for `application/ld+json`.
+    pub fn suffix(&self) -> Option<&str> {
+        self.subtype().rsplit_once('+').map(|(_, suffix)| suffix)
+    }
+
+    /// All parameters as (lowercase name, value) pairs. Quoted values are unquoted.
+    pub fn parameters(&self) -> Vec<(&str, String)> {
+        split_parameters(&self.0)
+            .skip(1)
+            .filter_map(|parameter| parameter.split_once('='))
+            .map(|(name, value)| (name, unquote(value)))
+            .collect()
+    }
+
+    /// The value of a parameter, the name is matched case-insensitive.
+    pub fn parameter(&self, name: &str) -> Option<String> {
+        self.parameters()
+            .into_iter()
+            .find(|(parameter, _)| parameter.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    pub fn is_pdf(&self) -> bool {
+        self.essence() == "application/pdf"
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.main_type() == "image"
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.main_type() == "text"
+    }
+
+    /// `application/json` and every type with the `+json` suffix.
+    pub fn is_json(&self) -> bool {
+        self.essence() == "application/json" || self.suffix() == Some("json")
+    }
+
+    /// `application/xml`, `text/xml` and every type with the `+xml` suffix.
+    pub fn is_xml(&self) -> bool {
+        self.subtype() == "xml" || self.suffix() == Some("xml")
+    }
+}
+
+/// tspecials of RFC 2045
+fn is_token_char(c: char) -> bool {
+    c.is_ascii()
+        && !c.is_ascii_control()
+        && !matches!(
+            c,
+            ' ' | '('
+                | ')'
+                | '<'
+                | '>'
+                | '@'
+                | ','
+                | ';'
+                | ':'
+                | '\\'
+                | '"'
+                | '/'
+                | '['
+                | ']'
+                | '?'
+                | '='
+        )
+}
+
+fn is_token(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(is_token_char)
+}
+
+fn is_quoted_string(value: &str) -> bool {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return false;
+    };
+
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // quoted-pair
+            '\\' if chars.next().is_none() => return false,
+            '\\' => {}
+            '"' | '\r' => return false,
+            c if !c.is_ascii() => return false,
+            _ => {}
+        }
+    }
+
+    true
+}
+
+fn unquote(value: &str) -> String {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(inner) => {
+            let mut unquoted = String::with_capacity(inner.len());
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => unquoted.extend(chars.next()),
+                    c => unquoted.push(c),
+                }
+            }
+            unquoted
+        }
+        None => value.to_string(),
+    }
+}
+
+/// Splits at `;` outside of quoted strings.
+fn split_parameters(value: &str) -> impl Iterator<Item = &str> {
+    let mut parts = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                parts.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(value[start..].trim());
+
+    parts.into_iter()
+}
+
+impl TryFrom<String> for ContentType {
+    type Error = ContentTypeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            return Err(ContentTypeError::TooShort);
+        }
+
+        // Checked before normalizing to refuse overlong input early, and again afterwards as
+        // normalizing adds a space before every parameter.
+        if value.chars().count() > 128 {
+            return Err(ContentTypeError::TooLong);
+        }
+
+        let mut parts = split_parameters(value);
+
+        let (main_type, subtype) = parts
+            .next()
+            .and_then(|essence| essence.split_once('/'))
+            .ok_or(ContentTypeError::MissingSubtype)?;
+
+        for token in [main_type, subtype] {
+            if !is_token(token) {
+                return Err(ContentTypeError::InvalidToken(token.to_string()));
+            }
+        }
+
+        let mut normalized = format!(
+            "{}/{}",
+            main_type.to_ascii_lowercase(),
+            subtype.to_ascii_lowercase()
+        );
+
+        for parameter in parts {
+            let (name, value) = parameter
+                .split_once('=')
+                .map(|(name, value)| (name.trim(), value.trim()))
+                .ok_or_else(|| ContentTypeError::InvalidParameter(parameter.to_string()))?;
+
+            if !is_token(name) || !(is_token(value) || is_quoted_string(value)) {
+                return Err(ContentTypeError::InvalidParameter(parameter.to_string()));
+            }
+
+            normalized.push_str("; ");
+            normalized.push_str(&name.to_ascii_lowercase());
+            normalized.push('=');
+            normalized.push_str(value);
+        }
+
+        if normalized.chars().count() > 128 {
+            return Err(ContentTypeError::TooLong);
+        }
+
+        Ok(ContentType(normalized))
+    }
+}
+
+impl TryFrom<&str> for ContentType {
+    type Error = ContentTypeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_from(value.to_string())
+    }
+}
+
+impl FromStr for ContentType {
+    type Err = ContentTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl AsRef<str> for ContentType {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for ContentType {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ContentTypeVisitor;
+
+        impl<'de> Visitor<'de> for ContentTypeVisitor {
+            type Value = ContentType;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("a valid content type string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<ContentType, E>
+            where
+                E: de::Error,
+            {
+                ContentType::try_from(value)
+                    .map_err(|err| de::Error::custom(format!("Invalid ContentType: {}", err)))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<ContentType, E>
+            where
+                E: de::Error,
+            {
+                ContentType::try_from(value)
+                    .map_err(|err| de::Error::custom(format!("Invalid ContentType: {}", err)))
+            }
+        }
+
+        deserializer.deserialize_string(ContentTypeVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let content_type = ContentType::try_from("Text/HTML ;Charset=UTF-8").unwrap();
+
+        assert_eq!(content_type.as_ref(), "text/html; charset=UTF-8");
+        assert_eq!(content_type.essence(), "text/html");
+        assert_eq!(content_type.parameter("CHARSET"), Some("UTF-8".to_string()));
+        assert!(content_type.is_text());
+    }
+
+    #[test]
+    fn test_quoted_parameter() {
+        let content_type =
+            ContentType::try_from(r#"multipart/mixed; boundary="a;b \"c\"""#).unwrap();
+
+        assert_eq!(
+            content_type.parameters(),
+            vec![("boundary", r#"a;b "c""#.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_helpers() {
+        let pdf = ContentType::try_from("application/pdf").unwrap();
+        let json = ContentType::try_from("application/ld+json").unwrap();
+
+        assert!(pdf.is_pdf());
+        assert!(!pdf.is_json());
+        assert!(json.is_json());
+        assert_eq!(json.subtype(), "ld+json");
+        assert_eq!(json.suffix(), Some("json"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(ContentType::try_from(""), Err(ContentTypeError::TooShort));
+        assert_eq!(
+            ContentType::try_from("text"),
+            Err(ContentTypeError::MissingSubtype)
+        );
+        assert_eq!(
+            ContentType::try_from("te xt/plain"),
+            Err(ContentTypeError::InvalidToken("te xt".into()))
+        );
+        assert_eq!(
+            ContentType::try_from("text/plain; charset"),
+            Err(ContentTypeError::InvalidParameter("charset".into()))
+        );
+    }
+
+    #[test]
+    fn test_too_long() {
+        let subtype = "a".repeat(124);
+
+        assert!(ContentType::try_from(format!("app/{}", subtype)).is_ok());
+        assert_eq!(
+            ContentType::try_from(format!("appl/{}", subtype)),
+            Err(ContentTypeError::TooLong)
+        );
+
+        // normalized to `text/plain; a=…`, one character longer
+        let parameter = format!("text/plain;a={}", "x".repeat(115));
+        assert_eq!(parameter.len(), 128);
+        assert_eq!(
+            ContentType::try_from(parameter),
+            Err(ContentTypeError::TooLong)
+        );
+
+        let parameter = format!("text/plain;a={}", "x".repeat(114));
+        let content_type = ContentType::try_from(parameter).unwrap();
+        assert_eq!(content_type.len(), 128);
+
+        let json = serde_json::to_string(&content_type).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ContentType>(&json).unwrap(),
+            content_type
+        );
+    }
+}
@@ -2,9 +2,14 @@ use crate::part_1::v3_1::primitives::Iri;
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::base64::Base64;
+use serde_with::formats::Uppercase;
+use serde_with::hex::Hex;
+use serde_with::serde_as;
 use strum::{Display, EnumString};
 
 /// Type mapping of XSDef types.
+#[serde_as]
 #[derive(Clone, PartialEq, Debug, Display, Deserialize, Serialize)]
 #[strum(prefix = "xs:", serialize_all = "camelCase")]
 pub enum DataTypeXSDef {
@@ -31,7 +36,7 @@ pub enum DataTypeXSDef {
     PositiveInteger(BigDecimal),
 
     #[serde(rename = "xs:short")]
-    Short(u16),
+    Short(i16),
 
     #[serde(rename = "xs:string")]
     String(String),
@@ -99,21 +104,25 @@ pub enum DataTypeXSDef {
     GYearMonth(String),
 
     // binary
+    /// encoded as base64 string
     #[serde(rename = "xs:base64Binary")]
-    Base64Binary(Vec<u8>),
+    Base64Binary(#[serde_as(as = "Base64")] Vec<u8>),
 
+    /// encoded as (uppercase) hex string
     #[serde(rename = "xs:hexBinary")]
-    HexBinary(Vec<u8>),
+    HexBinary(#[serde_as(as = "Hex<Uppercase>")] Vec<u8>),
 
     // Miscellaneous types
     /// URI and IRI possible
     #[serde(rename = "xs:anyURI")]
+    #[strum(serialize = "anyURI")]
     AnyURI(Iri),
 }
 
 /// represents the valueType/value pair typesafe. Used i.e. by Extension or Property.
 /// ValueType has to be always present, value can be optional.
 /// Default: String(None)
+#[serde_as]
 #[derive(Clone, PartialEq, Debug, Display, Deserialize, Serialize, EnumString)]
 #[serde(tag = "valueType", content = "value")]
 #[strum(prefix = "xs:", serialize_all = "camelCase")]
@@ -141,7 +150,7 @@ pub enum DataXsd {
     PositiveInteger(Option<BigDecimal>),
 
     #[serde(rename = "xs:short")]
-    Short(Option<u16>),
+    Short(Option<i16>),
 
     #[serde(rename = "xs:string")]
     String(Option<String>),
@@ -210,15 +219,18 @@ pub enum DataXsd {
     GYearMonth(Option<String>),
 
     // binary
+    /// encoded as base64 string
     #[serde(rename = "xs:base64Binary")]
-    Base64Binary(Option<Vec<u8>>),
+    Base64Binary(#[serde_as(as = "Option<Base64>")] Option<Vec<u8>>),
 
+    /// encoded as (uppercase) hex string
     #[serde(rename = "xs:hexBinary")]
-    HexBinary(Option<Vec<u8>>),
+    HexBinary(#[serde_as(as = "Option<Hex<Uppercase>>")] Option<Vec<u8>>),
 
     // Miscellaneous types
     /// URI and IRI possible
     #[serde(rename = "xs:anyURI")]
+    #[strum(serialize = "anyURI")]
     AnyURI(Option<Iri>),
 }
 
@@ -231,4 +243,64 @@ impl Default for DataXsd {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn display_any_uri() {
+        assert_eq!(DataXsd::AnyURI(None).to_string(), "xs:anyURI");
+        assert_eq!(
+            DataXsd::from_value_type(&DataXsd::AnyURI(None).to_string()),
+            Some(DataXsd::AnyURI(None))
+        );
+        assert_eq!(
+            DataTypeXSDef::AnyURI("https://example.com".parse().unwrap()).to_string(),
+            "xs:anyURI"
+        );
+    }
+
+    #[test]
+    fn deserialize_negative_short() {
+        let json = r#"{"valueType":"xs:short","value":-12}"#;
+
+        let actual: DataXsd = serde_json::from_str(json).expect("Should deserialize");
+
+        assert_eq!(DataXsd::Short(Some(-12)), actual);
+    }
+
+    #[test]
+    fn serialize_binary_as_string() {
+        let base64 = DataXsd::Base64Binary(Some(b"AAS".to_vec()));
+        let hex = DataXsd::HexBinary(Some(vec![0x0f, 0xa0]));
+
+        assert_eq!(
+            serde_json::to_string(&base64).unwrap(),
+            r#"{"valueType":"xs:base64Binary","value":"QUFT"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&hex).unwrap(),
+            r#"{"valueType":"xs:hexBinary","value":"0FA0"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_binary_from_string() {
+        let base64: DataXsd =
+            serde_json::from_str(r#"{"valueType":"xs:base64Binary","value":"QUFT"}"#).unwrap();
+        let hex: DataXsd =
+            serde_json::from_str(r#"{"valueType":"xs:hexBinary","value":"0fA0"}"#).unwrap();
+
+        assert_eq!(base64, DataXsd::Base64Binary(Some(b"AAS".to_vec())));
+        assert_eq!(hex, DataXsd::HexBinary(Some(vec![0x0f, 0xa0])));
+    }
+
+    #[test]
+    fn deserialize_invalid_binary() {
+        let base64 =
+            serde_json::from_str::<DataXsd>(r#"{"valueType":"xs:base64Binary","value":"QUF"}"#);
+        let hex = serde_json::from_str::<DataXsd>(r#"{"valueType":"xs:hexBinary","value":"0G"}"#);
+        let array = serde_json::from_str::<DataXsd>(r#"{"valueType":"xs:hexBinary","value":[1]}"#);
+
+        assert!(base64.is_err());
+        assert!(hex.is_err());
+        assert!(array.is_err());
+    }
 }
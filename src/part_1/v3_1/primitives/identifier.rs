@@ -120,7 +120,7 @@ mod tests {
 
     #[test]
     fn test_try_from_max_length() {
-        let test_value = std::iter::repeat('0').take(2048).collect::<String>();
+        let test_value = "0".repeat(2048);
         let id = Identifier::try_from(test_value.to_string());
 
         assert!(id.is_ok());
@@ -148,7 +148,7 @@ mod tests {
 
     #[test]
     fn test_try_from_too_long() {
-        let test_value = std::iter::repeat("t").take(2049).collect::<String>();
+        let test_value = "t".repeat(2049);
         let id = Identifier::try_from(test_value);
 
         assert_eq!(id, Err(IdentifierError::TooLong));
@@ -156,7 +156,7 @@ mod tests {
 
     #[test]
     fn test_try_from_invalid() {
-        let test_value = std::iter::repeat('\0').take(2048).collect::<String>();
+        let test_value = "\0".repeat(2048);
         let id = Identifier::try_from(test_value);
 
         assert_eq!(id, Err(IdentifierError::InvalidCharacter));
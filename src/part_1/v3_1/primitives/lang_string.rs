@@ -3,6 +3,8 @@ use crate::utilities::{
 };
 use oxilangtag::{LanguageTag, LanguageTagParseError};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -41,11 +43,13 @@ impl LangString {
         let language = LanguageTag::parse_and_normalize(language)?;
         Ok(Self { language, text })
     }
+}
 
-    /// Returns String in the RDF format "Text@TAG"
+impl Display for LangString {
+    /// Formats in the RDF format "Text@TAG"
     /// i.e. "Speed"@en
-    pub fn to_string(&self) -> String {
-        format!(r#""{}"@{}"#, self.text, self.language.to_string())
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, r#""{}"@{}"#, self.text, self.language)
     }
 }
 
@@ -1,21 +1,22 @@
+mod content_type;
 pub mod data_type_def_xs;
 mod identifier;
 mod label;
 pub mod lang_string;
 mod message_topic;
+mod xsd_value;
 
+pub use content_type::*;
 pub use identifier::*;
 pub use label::*;
 pub use message_topic::*;
+pub use xsd_value::*;
 
 use crate::part_1::v3_1::LangString;
 
-// TODO: Base64 parsing
+/// Decoded content of a `Blob`, base64 encoded in JSON.
 pub type BlobType = Vec<u8>;
 
-// TODO: Mime Parsing?
-pub type ContentType = String;
-
 pub type DateTimeUTC = chrono::DateTime<chrono::Utc>;
 
 pub type LangStringSet = Vec<LangString>;
@@ -0,0 +1,756 @@
+use crate::part_1::v3_1::primitives::Iri;
+use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum DataXsdError {
+    #[error("A value of type {0} can not be converted losslessly into the requested type")]
+    IncompatibleType(String),
+
+    #[error("The value does not fit into the value space of {0}")]
+    OutOfValueSpace(String),
+
+    #[error("{value} is not a valid lexical representation of {value_type}")]
+    InvalidLexical { value_type: String, value: String },
+}
+
+/// Lossless conversion out of a typed `DataXsd` value.
+///
+/// Numbers convert between each other as long as the value is exactly representable
+/// in the target, i.e. `xs:double` 5.0 can be read as `i32`, but 5.5 can't.
+/// Floating point numbers are taken by their shortest round-trip representation,
+/// so `xs:decimal` 0.1 can be read as `f64` while 0.1000000000000000001 can't.
+pub trait FromDataXsd: Sized {
+    /// `Ok(None)` if the value type matches, but no value is present.
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError>;
+}
+
+/// Conversion into a given `valueType`.
+/// The value type is never changed, values not fitting into its value space are refused.
+pub trait IntoDataXsd {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError>;
+}
+
+impl DataXsd {
+    /// Read the value as `T`. See [`FromDataXsd`] for the conversion rules.
+    pub fn get<T: FromDataXsd>(&self) -> Result<Option<T>, DataXsdError> {
+        T::from_data_xsd(self)
+    }
+
+    /// Replace the value, keeping the value type.
+    /// Fails without touching the current value if `value` does not fit the value type.
+    pub fn set<T: IntoDataXsd>(&mut self, value: T) -> Result<(), DataXsdError> {
+        *self = value.into_data_xsd(self)?;
+        Ok(())
+    }
+
+    /// Check the value against the value space of its type,
+    /// i.e. `xs:positiveInteger` has to be an integer greater than 0.
+    pub fn validate(&self) -> Result<(), DataXsdError> {
+        match self.as_decimal() {
+            Ok(Some(decimal)) => self.with_decimal(decimal).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    /// An empty value of the given `valueType`, i.e. `DataXsd::Int(None)` for `xs:int`.
+    pub fn from_value_type(value_type: &str) -> Option<DataXsd> {
+        // the strum prefix only applies to the Display output
+        DataXsd::from_str(value_type.strip_prefix("xs:")?).ok()
+    }
+
+    /// Remove the value, keeping the value type.
+    pub fn clear(&mut self) {
+        if let Some(empty) = DataXsd::from_value_type(&self.to_string()) {
+            *self = empty;
+        }
+    }
+
+    /// The value in its lexical representation of XML Schema, i.e. `5`, `INF` or `2025-01-01`.
+    /// Binary values are base64 or hex encoded.
+    pub fn lexical(&self) -> Option<String> {
+        match self {
+            DataXsd::Int(value) => value.map(|value| value.to_string()),
+            DataXsd::Long(value) => value.map(|value| value.to_string()),
+            DataXsd::Short(value) => value.map(|value| value.to_string()),
+            DataXsd::Byte(value) => value.map(|value| value.to_string()),
+            DataXsd::UnsignedByte(value) => value.map(|value| value.to_string()),
+            DataXsd::UnsignedInt(value) => value.map(|value| value.to_string()),
+            DataXsd::UnsignedLong(value) => value.map(|value| value.to_string()),
+            DataXsd::UnsignedShort(value) => value.map(|value| value.to_string()),
+            DataXsd::Integer(value)
+            | DataXsd::NegativeInteger(value)
+            | DataXsd::NonNegativeInteger(value)
+            | DataXsd::NonPositiveInteger(value)
+            | DataXsd::PositiveInteger(value)
+            | DataXsd::Decimal(value) => value.as_ref().map(BigDecimal::to_plain_string),
+            DataXsd::Float(value) => value.map(|value| float_lexical(value.into())),
+            DataXsd::Double(value) => value.map(float_lexical),
+            DataXsd::Boolean(value) => value.map(|value| value.to_string()),
+            DataXsd::String(value)
+            | DataXsd::Duration(value)
+            | DataXsd::GDay(value)
+            | DataXsd::GMonth(value)
+            | DataXsd::GMonthDay(value)
+            | DataXsd::GYear(value)
+            | DataXsd::GYearMonth(value) => value.clone(),
+            DataXsd::Time(value) => value.map(|value| value.to_string()),
+            DataXsd::Date(value) => value.map(|value| value.to_string()),
+            DataXsd::DateTime(value) => {
+                value.map(|value| value.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            DataXsd::Base64Binary(value) => value.as_ref().map(|value| STANDARD.encode(value)),
+            DataXsd::HexBinary(value) => value
+                .as_ref()
+                .map(|value| value.iter().map(|byte| format!("{byte:02X}")).collect()),
+            DataXsd::AnyURI(value) => value.as_ref().map(|value| value.to_string()),
+        }
+    }
+
+    /// Replace the value by parsing its lexical representation, keeping the value type.
+    /// Leading and trailing whitespace is ignored for all types but the text based ones.
+    pub fn set_lexical(&mut self, lexical: &str) -> Result<(), DataXsdError> {
+        let invalid = || DataXsdError::InvalidLexical {
+            value_type: self.to_string(),
+            value: lexical.to_string(),
+        };
+        let trimmed = lexical.trim();
+
+        let parsed = match self {
+            DataXsd::Float(_) | DataXsd::Double(_) => {
+                let value = match trimmed {
+                    "INF" | "+INF" => f64::INFINITY,
+                    "-INF" => f64::NEG_INFINITY,
+                    "NaN" => f64::NAN,
+                    _ if trimmed.contains(['i', 'I', 'n', 'N']) => return Err(invalid()),
+                    _ => f64::from_str(trimmed).map_err(|_| invalid())?,
+                };
+
+                match self {
+                    DataXsd::Float(_) => DataXsd::Float(Some(value as f32)),
+                    _ => DataXsd::Double(Some(value)),
+                }
+            }
+            DataXsd::Int(_)
+            | DataXsd::Long(_)
+            | DataXsd::Short(_)
+            | DataXsd::Byte(_)
+            | DataXsd::UnsignedByte(_)
+            | DataXsd::UnsignedInt(_)
+            | DataXsd::UnsignedLong(_)
+            | DataXsd::UnsignedShort(_)
+            | DataXsd::Integer(_)
+            | DataXsd::NegativeInteger(_)
+            | DataXsd::NonNegativeInteger(_)
+            | DataXsd::NonPositiveInteger(_)
+            | DataXsd::PositiveInteger(_)
+            | DataXsd::Decimal(_) => {
+                // xs:decimal has no exponent, the integer types have no decimal point either
+                let integer = !matches!(self, DataXsd::Decimal(_));
+                if trimmed.contains(['e', 'E']) || (integer && trimmed.contains('.')) {
+                    return Err(invalid());
+                }
+
+                self.with_decimal(BigDecimal::from_str(trimmed).map_err(|_| invalid())?)?
+            }
+            DataXsd::Boolean(_) => DataXsd::Boolean(Some(match trimmed {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => return Err(invalid()),
+            })),
+            DataXsd::String(_)
+            | DataXsd::Duration(_)
+            | DataXsd::GDay(_)
+            | DataXsd::GMonth(_)
+            | DataXsd::GMonthDay(_)
+            | DataXsd::GYear(_)
+            | DataXsd::GYearMonth(_)
+            | DataXsd::AnyURI(_) => lexical.into_data_xsd(self)?,
+            DataXsd::Time(_) => DataXsd::Time(Some(trimmed.parse().map_err(|_| invalid())?)),
+            DataXsd::Date(_) => DataXsd::Date(Some(trimmed.parse().map_err(|_| invalid())?)),
+            DataXsd::DateTime(_) => {
+                DataXsd::DateTime(Some(trimmed.parse().map_err(|_| invalid())?))
+            }
+            DataXsd::Base64Binary(_) => {
+                DataXsd::Base64Binary(Some(STANDARD.decode(trimmed).map_err(|_| invalid())?))
+            }
+            DataXsd::HexBinary(_) => {
+                DataXsd::HexBinary(Some(decode_hex(trimmed).ok_or_else(invalid)?))
+            }
+        };
+
+        *self = parsed;
+        Ok(())
+    }
+
+    fn incompatible(&self) -> DataXsdError {
+        DataXsdError::IncompatibleType(self.to_string())
+    }
+
+    fn out_of_value_space(&self) -> DataXsdError {
+        DataXsdError::OutOfValueSpace(self.to_string())
+    }
+
+    /// Exact decimal value of numeric types. NaN and infinity can't be represented.
+    fn as_decimal(&self) -> Result<Option<BigDecimal>, DataXsdError> {
+        Ok(match self {
+            DataXsd::Int(value) => value.map(BigDecimal::from),
+            DataXsd::Long(value) => value.map(BigDecimal::from),
+            DataXsd::Short(value) => value.map(BigDecimal::from),
+            DataXsd::Byte(value) => value.map(BigDecimal::from),
+            DataXsd::UnsignedByte(value) => value.map(BigDecimal::from),
+            DataXsd::UnsignedInt(value) => value.map(BigDecimal::from),
+            DataXsd::UnsignedLong(value) => value.map(BigDecimal::from),
+            DataXsd::UnsignedShort(value) => value.map(BigDecimal::from),
+            DataXsd::Integer(value)
+            | DataXsd::NegativeInteger(value)
+            | DataXsd::NonNegativeInteger(value)
+            | DataXsd::NonPositiveInteger(value)
+            | DataXsd::PositiveInteger(value)
+            | DataXsd::Decimal(value) => value.clone(),
+            DataXsd::Float(Some(value)) => {
+                Some(float_to_decimal(*value).ok_or(self.incompatible())?)
+            }
+            DataXsd::Double(Some(value)) => {
+                Some(float_to_decimal(*value).ok_or(self.incompatible())?)
+            }
+            DataXsd::Float(None) | DataXsd::Double(None) => None,
+            _ => return Err(self.incompatible()),
+        })
+    }
+
+    /// Builds the same variant as `self` holding `decimal`, if it fits into its value space.
+    fn with_decimal(&self, decimal: BigDecimal) -> Result<DataXsd, DataXsdError> {
+        let integer = || {
+            decimal
+                .is_integer()
+                .then_some(decimal.clone())
+                .ok_or(self.out_of_value_space())
+        };
+        let sign = decimal.sign();
+
+        Ok(match self {
+            DataXsd::Int(_) => {
+                DataXsd::Int(Some(integer()?.to_i32().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::Long(_) => {
+                DataXsd::Long(Some(integer()?.to_i64().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::Short(_) => {
+                DataXsd::Short(Some(integer()?.to_i16().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::Byte(_) => {
+                DataXsd::Byte(Some(integer()?.to_i8().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::UnsignedByte(_) => {
+                DataXsd::UnsignedByte(Some(integer()?.to_u8().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::UnsignedInt(_) => {
+                DataXsd::UnsignedInt(Some(integer()?.to_u32().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::UnsignedLong(_) => {
+                DataXsd::UnsignedLong(Some(integer()?.to_u64().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::UnsignedShort(_) => {
+                DataXsd::UnsignedShort(Some(integer()?.to_u16().ok_or(self.out_of_value_space())?))
+            }
+            DataXsd::Integer(_) => DataXsd::Integer(Some(integer()?)),
+            DataXsd::NegativeInteger(_) if sign == bigdecimal::num_bigint::Sign::Minus => {
+                DataXsd::NegativeInteger(Some(integer()?))
+            }
+            DataXsd::NonNegativeInteger(_) if sign != bigdecimal::num_bigint::Sign::Minus => {
+                DataXsd::NonNegativeInteger(Some(integer()?))
+            }
+            DataXsd::NonPositiveInteger(_) if sign != bigdecimal::num_bigint::Sign::Plus => {
+                DataXsd::NonPositiveInteger(Some(integer()?))
+            }
+            DataXsd::PositiveInteger(_) if sign == bigdecimal::num_bigint::Sign::Plus => {
+                DataXsd::PositiveInteger(Some(integer()?))
+            }
+            DataXsd::NegativeInteger(_)
+            | DataXsd::NonNegativeInteger(_)
+            | DataXsd::NonPositiveInteger(_)
+            | DataXsd::PositiveInteger(_) => return Err(self.out_of_value_space()),
+            DataXsd::Decimal(_) => DataXsd::Decimal(Some(decimal)),
+            DataXsd::Float(_) => DataXsd::Float(Some(
+                decimal_to_float::<f32>(&decimal).ok_or(self.out_of_value_space())?,
+            )),
+            DataXsd::Double(_) => DataXsd::Double(Some(
+                decimal_to_float::<f64>(&decimal).ok_or(self.out_of_value_space())?,
+            )),
+            _ => return Err(self.incompatible()),
+        })
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn float_lexical(value: f64) -> String {
+    match value {
+        f64::INFINITY => "INF".to_string(),
+        f64::NEG_INFINITY => "-INF".to_string(),
+        value if value.is_nan() => "NaN".to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Shortest decimal representation which round-trips to the same float.
+fn float_to_decimal<F: ToString>(value: F) -> Option<BigDecimal> {
+    BigDecimal::from_str(&value.to_string()).ok()
+}
+
+/// Only succeeds if the float is finite and converts back into the same decimal.
+fn decimal_to_float<F: FromStr + ToString + Copy>(decimal: &BigDecimal) -> Option<F> {
+    let float = F::from_str(&decimal.to_string()).ok()?;
+
+    (float_to_decimal(float)? == *decimal).then_some(float)
+}
+
+macro_rules! impl_xsd_integer {
+    ($($ty:ty => $to:ident),* $(,)?) => {
+        $(
+            impl FromDataXsd for $ty {
+                fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+                    let Some(decimal) = value.as_decimal()? else {
+                        return Ok(None);
+                    };
+
+                    decimal
+                        .is_integer()
+                        .then(|| decimal.$to())
+                        .flatten()
+                        .map(Some)
+                        .ok_or(value.incompatible())
+                }
+            }
+
+            impl IntoDataXsd for $ty {
+                fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+                    value_type.with_decimal(BigDecimal::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_xsd_integer!(
+    i8 => to_i8,
+    i16 => to_i16,
+    i32 => to_i32,
+    i64 => to_i64,
+    i128 => to_i128,
+    u8 => to_u8,
+    u16 => to_u16,
+    u32 => to_u32,
+    u64 => to_u64,
+    u128 => to_u128,
+);
+
+macro_rules! impl_xsd_float {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl FromDataXsd for $ty {
+                fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+                    match value {
+                        // keeps NaN and infinity
+                        DataXsd::$variant(float) => Ok(float.map(<$ty>::from)),
+                        _ => match value.as_decimal()? {
+                            Some(decimal) => decimal_to_float(&decimal)
+                                .map(Some)
+                                .ok_or(value.incompatible()),
+                            None => Ok(None),
+                        },
+                    }
+                }
+            }
+
+            impl IntoDataXsd for $ty {
+                fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+                    let float = f64::from(self);
+
+                    match value_type {
+                        DataXsd::Float(_) if !float.is_finite() => Ok(DataXsd::Float(Some(float as f32))),
+                        DataXsd::Double(_) if !float.is_finite() => Ok(DataXsd::Double(Some(float))),
+                        _ => value_type.with_decimal(
+                            float_to_decimal(self).ok_or(value_type.out_of_value_space())?,
+                        ),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_xsd_float!(f32 => Float, f64 => Double);
+
+impl FromDataXsd for BigDecimal {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        value.as_decimal()
+    }
+}
+
+impl IntoDataXsd for BigDecimal {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        value_type.with_decimal(self)
+    }
+}
+
+impl FromDataXsd for bool {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::Boolean(value) => Ok(*value),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl IntoDataXsd for bool {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        match value_type {
+            DataXsd::Boolean(_) => Ok(DataXsd::Boolean(Some(self))),
+            _ => Err(value_type.incompatible()),
+        }
+    }
+}
+
+/// Text based types: `xs:string`, `xs:anyURI`, `xs:duration` and the gregorian types.
+impl FromDataXsd for String {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::String(value)
+            | DataXsd::Duration(value)
+            | DataXsd::GDay(value)
+            | DataXsd::GMonth(value)
+            | DataXsd::GMonthDay(value)
+            | DataXsd::GYear(value)
+            | DataXsd::GYearMonth(value) => Ok(value.clone()),
+            DataXsd::AnyURI(value) => Ok(value.as_ref().map(|iri| iri.to_string())),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl IntoDataXsd for String {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        Ok(match value_type {
+            DataXsd::String(_) => DataXsd::String(Some(self)),
+            DataXsd::Duration(_) => DataXsd::Duration(Some(self)),
+            DataXsd::GDay(_) => DataXsd::GDay(Some(self)),
+            DataXsd::GMonth(_) => DataXsd::GMonth(Some(self)),
+            DataXsd::GMonthDay(_) => DataXsd::GMonthDay(Some(self)),
+            DataXsd::GYear(_) => DataXsd::GYear(Some(self)),
+            DataXsd::GYearMonth(_) => DataXsd::GYearMonth(Some(self)),
+            DataXsd::AnyURI(_) => DataXsd::AnyURI(Some(
+                Iri::new(self).map_err(|_| value_type.out_of_value_space())?,
+            )),
+            _ => return Err(value_type.incompatible()),
+        })
+    }
+}
+
+impl IntoDataXsd for &str {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        self.to_string().into_data_xsd(value_type)
+    }
+}
+
+impl FromDataXsd for Iri {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::AnyURI(value) => Ok(value.clone()),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl IntoDataXsd for Iri {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        match value_type {
+            DataXsd::AnyURI(_) => Ok(DataXsd::AnyURI(Some(self))),
+            _ => Err(value_type.incompatible()),
+        }
+    }
+}
+
+impl FromDataXsd for Vec<u8> {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::Base64Binary(value) | DataXsd::HexBinary(value) => Ok(value.clone()),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl IntoDataXsd for Vec<u8> {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        match value_type {
+            DataXsd::Base64Binary(_) => Ok(DataXsd::Base64Binary(Some(self))),
+            DataXsd::HexBinary(_) => Ok(DataXsd::HexBinary(Some(self))),
+            _ => Err(value_type.incompatible()),
+        }
+    }
+}
+
+impl FromDataXsd for NaiveDate {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::Date(value) => Ok(*value),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl IntoDataXsd for NaiveDate {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        match value_type {
+            DataXsd::Date(_) => Ok(DataXsd::Date(Some(self))),
+            _ => Err(value_type.incompatible()),
+        }
+    }
+}
+
+impl FromDataXsd for NaiveTime {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::Time(value) => Ok(*value),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl IntoDataXsd for NaiveTime {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        match value_type {
+            DataXsd::Time(_) => Ok(DataXsd::Time(Some(self))),
+            _ => Err(value_type.incompatible()),
+        }
+    }
+}
+
+impl FromDataXsd for DateTime<Utc> {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        match value {
+            DataXsd::DateTime(value) => Ok(*value),
+            _ => Err(value.incompatible()),
+        }
+    }
+}
+
+impl FromDataXsd for DateTime<FixedOffset> {
+    fn from_data_xsd(value: &DataXsd) -> Result<Option<Self>, DataXsdError> {
+        Ok(DateTime::<Utc>::from_data_xsd(value)?.map(|date_time| date_time.fixed_offset()))
+    }
+}
+
+/// The point in time is kept, the offset is normalized to UTC.
+impl<Tz: chrono::TimeZone> IntoDataXsd for DateTime<Tz> {
+    fn into_data_xsd(self, value_type: &DataXsd) -> Result<DataXsd, DataXsdError> {
+        match value_type {
+            DataXsd::DateTime(_) => Ok(DataXsd::DateTime(Some(self.with_timezone(&Utc)))),
+            _ => Err(value_type.incompatible()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lexical() {
+        let mut value = DataXsd::UnsignedShort(None);
+        value.set_lexical(" +42 ").unwrap();
+        assert_eq!(value, DataXsd::UnsignedShort(Some(42)));
+        assert_eq!(value.lexical(), Some("42".to_string()));
+        assert_eq!(
+            value.set_lexical("-1"),
+            Err(DataXsdError::OutOfValueSpace("xs:unsignedShort".into()))
+        );
+
+        for lexical in ["1.0", "1.", "1e0"] {
+            assert!(DataXsd::Int(None).set_lexical(lexical).is_err());
+            assert!(DataXsd::Integer(None).set_lexical(lexical).is_err());
+        }
+        let mut value = DataXsd::Decimal(None);
+        value.set_lexical("1.0").unwrap();
+        assert!(value.set_lexical("1e0").is_err());
+
+        let mut value = DataXsd::Float(None);
+        value.set_lexical("-INF").unwrap();
+        assert_eq!(value.lexical(), Some("-INF".to_string()));
+
+        let mut value = DataXsd::Boolean(None);
+        assert!(value.set_lexical("yes").is_err());
+        value.set_lexical("1").unwrap();
+        assert_eq!(value.lexical(), Some("true".to_string()));
+
+        let mut value = DataXsd::Date(None);
+        value.set_lexical("2025-01-31").unwrap();
+        assert_eq!(value.lexical(), Some("2025-01-31".to_string()));
+
+        let mut value = DataXsd::HexBinary(None);
+        value.set_lexical("CAFE").unwrap();
+        assert_eq!(value, DataXsd::HexBinary(Some(vec![0xca, 0xfe])));
+        assert!(value.set_lexical("+AFE").is_err());
+
+        // the lexical representation matches the JSON serialization
+        for (value_type, lexical) in [
+            ("xs:decimal", "-12.50"),
+            ("xs:time", "12:30:00.500"),
+            ("xs:dateTime", "2025-01-31T12:30:00Z"),
+            ("xs:base64Binary", "yv4="),
+            ("xs:hexBinary", "CAFE"),
+            ("xs:anyURI", "https://example.com/a"),
+        ] {
+            let mut value = DataXsd::from_value_type(value_type).unwrap();
+            value.set_lexical(lexical).unwrap();
+            assert_eq!(value.lexical().as_deref(), Some(lexical));
+            assert_eq!(serde_json::to_value(&value).unwrap()["value"], lexical);
+        }
+        assert_eq!(DataXsd::from_value_type("int"), None);
+        assert_eq!(
+            DataXsd::from_value_type("xs:gYearMonth"),
+            Some(DataXsd::GYearMonth(None))
+        );
+        assert_eq!(DataXsd::String(None).lexical(), None);
+    }
+
+    #[test]
+    fn get_lossless_numbers() {
+        let value = DataXsd::Double(Some(21.5));
+
+        assert_eq!(value.get::<f64>(), Ok(Some(21.5)));
+        assert_eq!(value.get::<f32>(), Ok(Some(21.5)));
+        assert_eq!(
+            value.get::<BigDecimal>(),
+            Ok(Some(BigDecimal::from_str("21.5").unwrap()))
+        );
+        assert!(value.get::<i32>().is_err());
+
+        let value = DataXsd::Decimal(Some(BigDecimal::from_str("0.1").unwrap()));
+        assert_eq!(value.get::<f64>(), Ok(Some(0.1)));
+
+        let value = DataXsd::Long(Some(i64::MAX));
+        assert!(value.get::<i32>().is_err());
+        assert!(value.get::<f64>().is_err());
+        assert_eq!(value.get::<i128>(), Ok(Some(i64::MAX as i128)));
+
+        let value = DataXsd::Double(Some(5.0));
+        assert_eq!(value.get::<u8>(), Ok(Some(5)));
+    }
+
+    #[test]
+    fn get_missing_value() {
+        assert_eq!(DataXsd::Int(None).get::<i32>(), Ok(None));
+        assert!(DataXsd::String(None).get::<i32>().is_err());
+    }
+
+    #[test]
+    fn get_date_time() {
+        let now = Utc::now();
+        let value = DataXsd::DateTime(Some(now));
+
+        assert_eq!(value.get::<DateTime<Utc>>(), Ok(Some(now)));
+        assert_eq!(
+            value.get::<DateTime<FixedOffset>>(),
+            Ok(Some(now.fixed_offset()))
+        );
+        assert!(value.get::<String>().is_err());
+    }
+
+    #[test]
+    fn set_respects_value_space() {
+        let mut value = DataXsd::NonNegativeInteger(None);
+
+        assert_eq!(
+            value.set(-1),
+            Err(DataXsdError::OutOfValueSpace(
+                "xs:nonNegativeInteger".into()
+            ))
+        );
+        assert_eq!(value, DataXsd::NonNegativeInteger(None));
+
+        value.set(0).unwrap();
+        assert_eq!(
+            value,
+            DataXsd::NonNegativeInteger(Some(BigDecimal::from(0)))
+        );
+
+        let mut value = DataXsd::PositiveInteger(None);
+        assert!(value.set(0).is_err());
+        assert!(value.set(1.5).is_err());
+        assert!(value.set(1).is_ok());
+
+        let mut value = DataXsd::Byte(None);
+        assert!(value.set(128).is_err());
+        assert!(value.set(-128).is_ok());
+
+        let mut value = DataXsd::Short(None);
+        assert!(value.set(-1).is_ok());
+        assert!(value.set(40_000).is_err());
+    }
+
+    #[test]
+    fn set_floats() {
+        let mut value = DataXsd::Float(None);
+
+        value.set(0.5f64).unwrap();
+        assert_eq!(value, DataXsd::Float(Some(0.5)));
+        // not representable as f32
+        assert!(value.set(1.0000000000000002f64).is_err());
+
+        value.set(f64::NAN).unwrap();
+        assert!(matches!(value, DataXsd::Float(Some(float)) if float.is_nan()));
+
+        let mut value = DataXsd::Int(None);
+        assert!(value.set(f64::INFINITY).is_err());
+        value.set(3.0).unwrap();
+        assert_eq!(value, DataXsd::Int(Some(3)));
+    }
+
+    #[test]
+    fn set_incompatible() {
+        let mut value = DataXsd::Boolean(None);
+
+        assert_eq!(
+            value.set("true"),
+            Err(DataXsdError::IncompatibleType("xs:boolean".into()))
+        );
+        value.set(true).unwrap();
+        assert_eq!(value.get::<bool>(), Ok(Some(true)));
+    }
+
+    #[test]
+    fn validate() {
+        assert!(
+            DataXsd::NegativeInteger(Some(BigDecimal::from(1)))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataXsd::Integer(Some(BigDecimal::from_str("1.5").unwrap()))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataXsd::NegativeInteger(Some(BigDecimal::from(-1)))
+                .validate()
+                .is_ok()
+        );
+        assert!(DataXsd::String(Some("test".into())).validate().is_ok());
+    }
+}
@@ -0,0 +1,665 @@
+//! Redacted copies of environments and submodels, i.e. to share a package with customers
+//! without the confidential cost data.
+//!
+//! A [`RedactionPolicy`] removes submodels and elements by semanticId, idShort path, qualifier
+//! type or the rights of a subject. Whatever depends on removed data goes as well: elements
+//! referring to a removed element and the references of shells to removed submodels. Concept
+//! descriptions no longer used can be pruned.
+//!
+//! ```
+//! use aas::part_1::v3_1::core::Submodel;
+//! use aas::part_1::v3_1::redaction::RedactionPolicy;
+//!
+//! let submodel: Submodel = serde_json::from_str(r#"{
+//!     "id": "https://example.com/submodels/technical-data",
+//!     "submodelElements": [
+//!         {"modelType": "Property", "idShort": "MaxPower", "valueType": "xs:int", "value": 7500},
+//!         {"modelType": "SubmodelElementCollection", "idShort": "Costs", "value": [
+//!             {"modelType": "Property", "idShort": "PurchasePrice", "valueType": "xs:decimal", "value": 1200}
+//!         ]}
+//!     ]
+//! }"#).unwrap();
+//!
+//! let redacted = RedactionPolicy::new()
+//!     .remove_path("**.PurchasePrice")
+//!     .redact_submodel(&submodel)
+//!     .unwrap();
+//!
+//! assert!(redacted.element("Costs").is_some());
+//! assert!(redacted.element("Costs.PurchasePrice").is_none());
+//! ```
+
+use crate::part_1::v3_1::core::{AssetAdministrationShell, Submodel};
+use crate::part_1::v3_1::references::reference_keys;
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::{Environment, Key, Reference};
+use crate::part_4::v3_0::{AccessRules, Right, Subject, Target};
+use std::collections::HashSet;
+
+/// What to remove, everything else is kept.
+#[derive(Clone, Debug, Default)]
+pub struct RedactionPolicy<'a> {
+    semantic_ids: Vec<Reference>,
+    paths: Vec<String>,
+    qualifiers: Vec<String>,
+    access: Option<(&'a AccessRules, &'a Subject)>,
+    prune_concept_descriptions: bool,
+}
+
+impl<'a> RedactionPolicy<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes submodels and elements with this semantic id, supplemental semantic ids included.
+    pub fn remove_semantic_id(mut self, semantic_id: Reference) -> Self {
+        self.semantic_ids.push(semantic_id);
+        self
+    }
+
+    /// Removes elements whose idShort path matches `pattern`, with everything they contain.
+    /// `*` stands for any text within a segment and `**` for any number of segments, i.e.
+    /// `Costs`, `**.PurchasePrice` or `Prices[*].Amount`.
+    pub fn remove_path(mut self, pattern: impl Into<String>) -> Self {
+        self.paths.push(pattern.into());
+        self
+    }
+
+    /// Removes submodels and elements with a qualifier of this type, i.e. `Confidential`.
+    pub fn remove_qualified(mut self, qualifier_type: impl Into<String>) -> Self {
+        self.qualifiers.push(qualifier_type.into());
+        self
+    }
+
+    /// Removes everything `subject` may not read according to `rules`.
+    pub fn readable_by(mut self, rules: &'a AccessRules, subject: &'a Subject) -> Self {
+        self.access = Some((rules, subject));
+        self
+    }
+
+    /// Removes concept descriptions no longer referred to by the redacted environment.
+    pub fn prune_concept_descriptions(mut self, prune: bool) -> Self {
+        self.prune_concept_descriptions = prune;
+        self
+    }
+
+    pub fn redact(&self, environment: &Environment) -> Environment {
+        // The key values of everything removed, as in the original, i.e. `[submodel id, idShort]`.
+        let mut removed = HashSet::new();
+        for shell in environment.asset_administration_shells.iter().flatten() {
+            let id = shell.identifiable.id.as_ref();
+            if !self.allows(environment, Target::Shell(id)) {
+                removed.insert(vec![id.to_string()]);
+            }
+        }
+        for description in environment.concept_descriptions.iter().flatten() {
+            let id = description.identifiable.id.as_ref();
+            if !self.allows(environment, Target::ConceptDescription(id)) {
+                removed.insert(vec![id.to_string()]);
+            }
+        }
+
+        // Removing an element can leave others dangling, so this goes on until nothing changes.
+        let submodels = loop {
+            let mut newly_removed = Vec::new();
+            let mut submodels = Vec::new();
+
+            for submodel in environment.submodels.iter().flatten() {
+                let keys = [submodel.id().to_string()];
+                if !self.keeps_submodel(environment, submodel) {
+                    newly_removed.push(keys.to_vec());
+                    continue;
+                }
+
+                submodels.push(with_elements(submodel, |elements| {
+                    filtered(
+                        elements,
+                        None,
+                        false,
+                        &keys,
+                        &mut newly_removed,
+                        &|path, element| {
+                            self.keeps_element(environment, submodel, path, element)
+                                && !model_references(element)
+                                    .iter()
+                                    .any(|keys| is_dangling(keys, environment, &removed))
+                        },
+                    )
+                }));
+            }
+
+            let count = removed.len();
+            removed.extend(newly_removed);
+            if removed.len() == count {
+                break submodels;
+            }
+        };
+
+        let kept = |id: &str| !removed.contains([id.to_string()].as_slice());
+        let mut redacted = Environment {
+            asset_administration_shells: environment.asset_administration_shells.as_ref().map(
+                |shells| {
+                    shells
+                        .iter()
+                        .filter(|shell| kept(shell.identifiable.id.as_ref()))
+                        .map(|shell| without_dangling_references(shell, environment, &removed))
+                        .collect()
+                },
+            ),
+            submodels: environment.submodels.as_ref().map(|_| submodels),
+            concept_descriptions: environment
+                .concept_descriptions
+                .as_ref()
+                .map(|descriptions| {
+                    descriptions
+                        .iter()
+                        .filter(|description| kept(description.identifiable.id.as_ref()))
+                        .cloned()
+                        .collect()
+                }),
+        };
+
+        if self.prune_concept_descriptions {
+            prune_concept_descriptions(&mut redacted);
+        }
+
+        redacted
+    }
+
+    /// The redacted copy of `submodel`, `None` if the submodel is removed as a whole.
+    pub fn redact_submodel(&self, submodel: &Submodel) -> Option<Submodel> {
+        let environment = Environment {
+            asset_administration_shells: None,
+            submodels: Some(vec![submodel.clone()]),
+            concept_descriptions: None,
+        };
+
+        self.redact(&environment).submodels?.pop()
+    }
+
+    fn keeps_submodel(&self, environment: &Environment, submodel: &Submodel) -> bool {
+        let removed = self
+            .semantic_ids
+            .iter()
+            .any(|semantic_id| submodel.semantics.has_any_semantic_id(semantic_id))
+            || self
+                .qualifiers
+                .iter()
+                .any(|qualifier| submodel.qualifier.has_qualifier(qualifier));
+
+        !removed && self.allows(environment, Target::Submodel(submodel.id().as_ref()))
+    }
+
+    fn keeps_element(
+        &self,
+        environment: &Environment,
+        submodel: &Submodel,
+        path: &str,
+        element: &SubmodelElement,
+    ) -> bool {
+        let removed = self
+            .semantic_ids
+            .iter()
+            .any(|semantic_id| element.semantics().has_any_semantic_id(semantic_id))
+            || self
+                .qualifiers
+                .iter()
+                .any(|qualifier| element.qualifiable().has_qualifier(qualifier))
+            || self.paths.iter().any(|pattern| matches_path(pattern, path));
+
+        !removed
+            && self.allows(
+                environment,
+                Target::Element {
+                    submodel: submodel.id().as_ref(),
+                    id_short_path: path,
+                },
+            )
+    }
+
+    fn allows(&self, environment: &Environment, target: Target) -> bool {
+        self.access.is_none_or(|(rules, subject)| {
+            rules.is_allowed(environment, subject, Right::Read, target)
+        })
+    }
+}
+
+fn with_elements(
+    submodel: &Submodel,
+    elements: impl FnOnce(&[SubmodelElement]) -> Vec<SubmodelElement>,
+) -> Submodel {
+    Submodel {
+        submodel_elements: submodel.submodel_elements.as_deref().map(elements),
+        ..submodel.clone()
+    }
+}
+
+/// Copies the elements `keep` accepts, `keep` gets the idShort path of each element.
+/// Elements outside of lists without idShort can't be addressed and are kept. The key values of
+/// removed elements, starting with the `keys` of the parent, are added to `removed`.
+fn filtered(
+    elements: &[SubmodelElement],
+    prefix: Option<&str>,
+    in_list: bool,
+    keys: &[String],
+    removed: &mut Vec<Vec<String>>,
+    keep: &dyn Fn(&str, &SubmodelElement) -> bool,
+) -> Vec<SubmodelElement> {
+    let mut kept = Vec::new();
+
+    for (index, element) in elements.iter().enumerate() {
+        let (path, key) = match (prefix, in_list, element.id_short()) {
+            (Some(prefix), true, _) => (format!("{prefix}[{index}]"), index.to_string()),
+            (Some(prefix), false, Some(id_short)) => {
+                (format!("{prefix}.{id_short}"), id_short.to_string())
+            }
+            (None, _, Some(id_short)) => (id_short.to_string(), id_short.to_string()),
+            _ => {
+                kept.push(element.clone());
+                continue;
+            }
+        };
+        let keys = [keys, &[key]].concat();
+
+        if !keep(&path, element) {
+            removed.push(keys);
+            continue;
+        }
+
+        let mut copy = element.clone();
+        if let (Some(children), Some(copied)) = (element.children(), copy.children_mut()) {
+            let is_list = matches!(element, SubmodelElement::SubmodelElementList(_));
+            *copied = filtered(children, Some(&path), is_list, &keys, removed, keep);
+        }
+        kept.push(copy);
+    }
+
+    kept
+}
+
+/// The model references an element depends on.
+fn model_references(element: &SubmodelElement) -> Vec<&[Key]> {
+    fn model(reference: &Option<Reference>) -> Option<&[Key]> {
+        match reference {
+            Some(reference @ Reference::ModelReference(_)) => Some(reference.keys()),
+            _ => None,
+        }
+    }
+
+    match element {
+        SubmodelElement::ReferenceElement(element) => model(&element.value).into_iter().collect(),
+        SubmodelElement::RelationshipElement(element) => {
+            [model(&element.first), model(&element.second)]
+                .into_iter()
+                .flatten()
+                .collect()
+        }
+        SubmodelElement::AnnotatedRelationshipElement(element) => {
+            [model(&element.first), model(&element.second)]
+                .into_iter()
+                .flatten()
+                .collect()
+        }
+        SubmodelElement::BasicEventElement(element) => vec![element.observed.keys.as_slice()],
+        _ => Vec::new(),
+    }
+}
+
+/// The target existed before redacting but was removed, by itself or along with a parent.
+/// References are compared with the original, as removing a list element shifts the indices of
+/// the following ones.
+fn is_dangling(keys: &[Key], original: &Environment, removed: &HashSet<Vec<String>>) -> bool {
+    let values = keys
+        .iter()
+        .map(|key| key.value().clone())
+        .collect::<Vec<_>>();
+
+    original.resolve(keys).is_some()
+        && (1..=values.len()).any(|length| removed.contains(&values[..length]))
+}
+
+fn without_dangling_references(
+    shell: &AssetAdministrationShell,
+    original: &Environment,
+    removed: &HashSet<Vec<String>>,
+) -> AssetAdministrationShell {
+    let mut shell = shell.clone();
+
+    if let Some(submodels) = shell.submodels.as_mut() {
+        submodels.retain(|reference| !is_dangling(&reference.keys, original, removed));
+    }
+    if shell
+        .derived_from
+        .as_ref()
+        .is_some_and(|reference| is_dangling(&reference.keys, original, removed))
+    {
+        shell.derived_from = None;
+    }
+
+    shell
+}
+
+/// Keeps the concept descriptions referred to by shells, submodels or kept concept
+/// descriptions, i.e. by a semanticId or `isCaseOf`.
+fn prune_concept_descriptions(environment: &mut Environment) {
+    let Some(descriptions) = environment.concept_descriptions.take() else {
+        return;
+    };
+
+    let key_values = |references: Vec<Vec<Key>>| {
+        references
+            .into_iter()
+            .flatten()
+            .map(|key| key.value().clone())
+            .collect::<Vec<_>>()
+    };
+
+    let shells = environment.asset_administration_shells.iter().flatten();
+    let submodels = environment.submodels.iter().flatten();
+    let mut referred = shells
+        .map(reference_keys)
+        .chain(submodels.map(reference_keys))
+        .flat_map(key_values)
+        .collect::<HashSet<_>>();
+
+    let mut kept = vec![false; descriptions.len()];
+    loop {
+        let newly_kept = descriptions
+            .iter()
+            .enumerate()
+            .filter(|(index, description)| {
+                !kept[*index] && referred.contains(description.identifiable.id.as_ref() as &str)
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if newly_kept.is_empty() {
+            break;
+        }
+        for index in newly_kept {
+            kept[index] = true;
+            referred.extend(key_values(reference_keys(&descriptions[index])));
+        }
+    }
+
+    environment.concept_descriptions = Some(
+        descriptions
+            .into_iter()
+            .zip(kept)
+            .filter_map(|(description, kept)| kept.then_some(description))
+            .collect(),
+    );
+}
+
+/// Segments are separated by `.`, `**` matches any number of segments.
+fn matches_path(pattern: &str, path: &str) -> bool {
+    fn matches(pattern: &[&str], path: &[&str]) -> bool {
+        match (pattern.split_first(), path.split_first()) {
+            (None, None) => true,
+            (Some((&"**", rest)), _) => {
+                matches(rest, path) || (!path.is_empty() && matches(pattern, &path[1..]))
+            }
+            (Some((segment, rest)), Some((first, path))) => {
+                matches_segment(segment, first) && matches(rest, path)
+            }
+            _ => false,
+        }
+    }
+
+    let pattern = pattern.split('.').collect::<Vec<_>>();
+    let path = path.split('.').collect::<Vec<_>>();
+    matches(&pattern, &path)
+}
+
+/// `*` matches any text, everything else literally.
+fn matches_segment(pattern: &str, segment: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == segment,
+        Some((prefix, rest)) => segment.strip_prefix(prefix).is_some_and(|segment| {
+            (0..=segment.len())
+                .filter(|start| segment.is_char_boundary(*start))
+                .any(|start| matches_segment(rest, &segment[start..]))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn semantic_id(id: &str) -> Value {
+        json!({ "type": "ExternalReference", "keys": [{ "type": "GlobalReference", "value": id }] })
+    }
+
+    fn environment() -> Environment {
+        serde_json::from_value(json!({
+            "assetAdministrationShells": [{
+                "id": "https://example.com/aas/motor",
+                "assetInformation": { "assetKind": "Instance", "globalAssetId": "https://example.com/motor" },
+                "submodels": [
+                    { "type": "ModelReference", "keys": [{ "type": "Submodel", "value": "https://example.com/sm/technical-data" }] },
+                    { "type": "ModelReference", "keys": [{ "type": "Submodel", "value": "https://example.com/sm/calculation" }] }
+                ]
+            }],
+            "submodels": [
+                {
+                    "modelType": "Submodel",
+                    "id": "https://example.com/sm/technical-data",
+                    "submodelElements": [
+                        {
+                            "modelType": "Property", "idShort": "MaxPower", "valueType": "xs:int", "value": 7500,
+                            "semanticId": semantic_id("urn:cd:max-power")
+                        },
+                        {
+                            "modelType": "SubmodelElementCollection",
+                            "idShort": "Costs",
+                            "qualifiers": [{ "type": "Confidential", "valueType": "xs:boolean", "value": true }],
+                            "value": [{
+                                "modelType": "Property", "idShort": "PurchasePrice", "valueType": "xs:decimal", "value": 1200,
+                                "semanticId": semantic_id("urn:cd:purchase-price")
+                            }]
+                        },
+                        {
+                            "modelType": "ReferenceElement",
+                            "idShort": "PriceSource",
+                            "value": {
+                                "type": "ModelReference",
+                                "keys": [
+                                    { "type": "Submodel", "value": "https://example.com/sm/calculation" },
+                                    { "type": "Property", "value": "Margin" }
+                                ]
+                            }
+                        },
+                        {
+                            "modelType": "RelationshipElement",
+                            "idShort": "DerivedFrom",
+                            "first": {
+                                "type": "ModelReference",
+                                "keys": [
+                                    { "type": "Submodel", "value": "https://example.com/sm/technical-data" },
+                                    { "type": "ReferenceElement", "value": "PriceSource" }
+                                ]
+                            },
+                            "second": {
+                                "type": "ModelReference",
+                                "keys": [
+                                    { "type": "Submodel", "value": "https://example.com/sm/technical-data" },
+                                    { "type": "Property", "value": "MaxPower" }
+                                ]
+                            }
+                        },
+                        {
+                            "modelType": "ReferenceElement",
+                            "idShort": "Datasheet",
+                            "value": { "type": "ExternalReference", "keys": [{ "type": "GlobalReference", "value": "https://example.com/datasheet.pdf" }] }
+                        }
+                    ]
+                },
+                {
+                    "modelType": "Submodel",
+                    "id": "https://example.com/sm/calculation",
+                    "semanticId": semantic_id("urn:sm:calculation"),
+                    "submodelElements": [
+                        { "modelType": "Property", "idShort": "Margin", "valueType": "xs:decimal", "value": 0.3 }
+                    ]
+                }
+            ],
+            "conceptDescriptions": [
+                { "id": "urn:cd:max-power", "isCaseOf": semantic_id("urn:cd:power") },
+                { "id": "urn:cd:power" },
+                { "id": "urn:cd:purchase-price" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn remove_dependent_data() {
+        let environment = environment();
+        let calculation: Reference =
+            serde_json::from_value(semantic_id("urn:sm:calculation")).unwrap();
+
+        let redacted = RedactionPolicy::new()
+            .remove_qualified("Confidential")
+            .remove_semantic_id(calculation)
+            .prune_concept_descriptions(true)
+            .redact(&environment);
+
+        assert!(
+            redacted
+                .submodel("https://example.com/sm/calculation")
+                .is_none()
+        );
+        let technical_data = redacted
+            .submodel("https://example.com/sm/technical-data")
+            .unwrap();
+        assert_eq!(technical_data.id_short_paths(), ["MaxPower", "Datasheet"]);
+
+        let shell = redacted
+            .asset_administration_shell("https://example.com/aas/motor")
+            .unwrap();
+        assert_eq!(shell.submodels.as_ref().unwrap().len(), 1);
+
+        let descriptions = redacted
+            .concept_descriptions
+            .unwrap()
+            .into_iter()
+            .map(|description| description.identifiable.id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(descriptions, ["urn:cd:max-power", "urn:cd:power"]);
+
+        // The original is untouched and nothing is pruned unless asked for.
+        let kept = RedactionPolicy::new().redact(&environment);
+        assert_eq!(kept, environment);
+    }
+
+    #[test]
+    fn readable_by_subject() {
+        let environment = environment();
+        let rules: AccessRules = json!({ "rules": [
+            {
+                "ACL": { "ATTRIBUTES": [{ "CLAIM": "customer" }], "RIGHTS": ["READ"], "ACCESS": "ALLOW" },
+                "OBJECTS": [{ "IDENTIFIABLE": "(Submodel)https://example.com/sm/technical-data" }],
+                "FORMULA": { "$ne": [{ "$field": "$sme#idShort" }, { "$strVal": "Costs" }] }
+            },
+            {
+                "ACL": { "RIGHTS": ["READ"], "ACCESS": "ALLOW" },
+                "OBJECTS": [{ "IDENTIFIABLE": "(AssetAdministrationShell)*" }]
+            }
+        ] })
+        .to_string()
+        .parse()
+        .unwrap();
+        let customer = Subject::default().with_claim("customer", "ACME");
+
+        let redacted = RedactionPolicy::new()
+            .readable_by(&rules, &customer)
+            .redact(&environment);
+
+        // The calculation is not readable, so the elements referring to it go as well.
+        assert!(
+            redacted
+                .submodel("https://example.com/sm/calculation")
+                .is_none()
+        );
+        let technical_data = redacted
+            .submodel("https://example.com/sm/technical-data")
+            .unwrap();
+        assert_eq!(technical_data.id_short_paths(), ["MaxPower", "Datasheet"]);
+        assert_eq!(
+            redacted.asset_administration_shells.unwrap()[0]
+                .submodels
+                .as_ref()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let anonymous = Subject::anonymous();
+        let redacted = RedactionPolicy::new()
+            .readable_by(&rules, &anonymous)
+            .redact(&environment);
+        assert_eq!(redacted.submodels, Some(Vec::new()));
+    }
+
+    #[test]
+    fn list_references_keep_their_target() {
+        let reference = |index: &str| {
+            json!({
+                "type": "ModelReference",
+                "keys": [
+                    { "type": "Submodel", "value": "urn:sm:tests" },
+                    { "type": "SubmodelElementList", "value": "Runs" },
+                    { "type": "Property", "value": index }
+                ]
+            })
+        };
+        let run = |confidential: bool| {
+            json!({
+                "modelType": "Property", "valueType": "xs:int", "value": 1,
+                "qualifiers": [{ "type": if confidential { "Confidential" } else { "Public" }, "valueType": "xs:boolean", "value": true }]
+            })
+        };
+        let environment: Environment = serde_json::from_value(json!({
+            "submodels": [{
+                "modelType": "Submodel",
+                "id": "urn:sm:tests",
+                "submodelElements": [
+                    {
+                        "modelType": "SubmodelElementList",
+                        "idShort": "Runs",
+                        "typeValueListElement": "Property",
+                        "value": [run(false), run(true), run(false)]
+                    },
+                    { "modelType": "ReferenceElement", "idShort": "Removed", "value": reference("1") },
+                    { "modelType": "ReferenceElement", "idShort": "Last", "value": reference("2") }
+                ]
+            }]
+        }))
+        .unwrap();
+
+        let redacted = RedactionPolicy::new()
+            .remove_qualified("Confidential")
+            .redact(&environment);
+
+        // The reference to the removed run goes, the one to the last run stays although that
+        // is now at index 1.
+        let tests = redacted.submodel("urn:sm:tests").unwrap();
+        assert_eq!(
+            tests.id_short_paths(),
+            ["Runs", "Runs[0]", "Runs[1]", "Last"]
+        );
+    }
+
+    #[test]
+    fn path_patterns() {
+        assert!(matches_path("Costs", "Costs"));
+        assert!(!matches_path("Costs", "Costs.PurchasePrice"));
+        assert!(matches_path("**.PurchasePrice", "Costs.PurchasePrice"));
+        assert!(matches_path("**.PurchasePrice", "PurchasePrice"));
+        assert!(matches_path("Prices[*].Amount", "Prices[3].Amount"));
+        assert!(matches_path("Cost*", "CostBreakdown"));
+        assert!(!matches_path("Prices[*].Amount", "Prices[3].Currency"));
+    }
+}
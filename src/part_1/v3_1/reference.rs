@@ -1,8 +1,11 @@
-use crate::part_1::v3_1::key::Key;
-use serde::de::{Error, Visitor};
-use serde::{Deserialize, Deserializer, Serialize};
+use crate::part_1::v3_1::key::{Key, KeyParseError, KeyTypes};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
-use strum::EnumString;
+use std::fmt::{Display, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+use thiserror::Error;
 
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize, Default)]
 pub struct ReferenceInner {
@@ -20,16 +23,19 @@ pub struct ReferenceInner {
 ///
 /// The `ReferenceType` attribute of a `Reference` determines the scope and semantics of the reference:
 /// - `GlobalReference` means the reference resolves to an element identifiable globally,
-/// often outside the local AAS context.
+///   often outside the local AAS context.
 /// - `LocalReference` restricts the reference scope to internal elements or fragments
-/// within the current parent element or AAS.
+///   within the current parent element or AAS.
 ///
 /// This distinction affects how references are interpreted, resolved, and validated in distributed environments,
 /// ensuring interoperability and correct addressing in digital twin ecosystems.
 ///
 /// A `Reference` supports multi-level navigation through composite structures by chaining multiple keys,
 /// enabling precise targeting of nested submodels, submodel elements, or fragments.
-#[derive(EnumString, Clone, PartialEq, Debug, Deserialize, Serialize)]
+///
+/// The text serialization of Part 2 is used by `Display` and `FromStr`,
+/// i.e. `[ModelRef](Submodel)urn:x:sm, (Property)temp`.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum Reference {
     ExternalReference(ReferenceInner),
@@ -50,144 +56,554 @@ impl ReferenceInner {
             keys,
         }
     }
+
+    /// The key chain of `self` is the start of the key chain of `other`, or the same.
+    /// i.e. `(Submodel)sm` is a prefix of `(Submodel)sm, (Property)temp`.
+    pub fn is_prefix_of(&self, other: &ReferenceInner) -> bool {
+        other.keys.starts_with(&self.keys)
+    }
+
+    /// `other` points to a direct child of the element `self` points to.
+    pub fn is_parent_of(&self, other: &ReferenceInner) -> bool {
+        other.keys.len() == self.keys.len() + 1 && self.is_prefix_of(other)
+    }
 }
 
-pub fn deserialize_model_reference<'de, D>(deserializer: D) -> Result<Reference, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    // Define a visitor to handle deserialization from sequence of ModelReference
-    struct SubmodelsVisitor;
+impl Reference {
+    /// `ModelReference` or `ExternalReference`
+    pub fn reference_type(&self) -> &'static str {
+        match self {
+            Reference::ExternalReference(_) => "ExternalReference",
+            Reference::ModelReference(_) => "ModelReference",
+        }
+    }
 
-    impl<'de> Visitor<'de> for SubmodelsVisitor {
-        type Value = Reference;
+    pub fn inner(&self) -> &ReferenceInner {
+        match self {
+            Reference::ExternalReference(inner) | Reference::ModelReference(inner) => inner,
+        }
+    }
 
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("an optional sequence of ModelReference")
+    pub fn inner_mut(&mut self) -> &mut ReferenceInner {
+        match self {
+            Reference::ExternalReference(inner) | Reference::ModelReference(inner) => inner,
         }
+    }
 
-        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let reference: Reference = Reference::deserialize(deserializer)?;
+    pub fn into_inner(self) -> ReferenceInner {
+        match self {
+            Reference::ExternalReference(inner) | Reference::ModelReference(inner) => inner,
+        }
+    }
 
-            if let Reference::ModelReference(model_ref_inner) = reference {
-                // Convert model_ref_inner into Submodel
-                // Assuming From<ReferenceInner> for Submodel is implemented
-                Ok(Reference::ModelReference(model_ref_inner))
-            } else {
-                Err(D::Error::custom("unexpected reference type"))
-            }
+    pub fn keys(&self) -> &[Key] {
+        &self.inner().keys
+    }
+
+    pub fn referred_semantic_id(&self) -> Option<&Reference> {
+        self.inner().referred_semantic_id.as_deref()
+    }
+
+    /// Same keys and referred semantic id, but the reference type is ignored.
+    /// i.e. a `[ModelRef](Submodel)x` written as `[ExternalRef](Submodel)x` by another tool.
+    pub fn eq_ignore_type(&self, other: &Reference) -> bool {
+        self.inner() == other.inner()
+    }
+
+    /// See [`ReferenceInner::is_prefix_of`]. References of different types never match.
+    pub fn is_prefix_of(&self, other: &Reference) -> bool {
+        self.reference_type() == other.reference_type() && self.inner().is_prefix_of(other.inner())
+    }
+
+    /// See [`ReferenceInner::is_parent_of`]. References of different types never match.
+    pub fn is_parent_of(&self, other: &Reference) -> bool {
+        self.reference_type() == other.reference_type() && self.inner().is_parent_of(other.inner())
+    }
+
+    /// The identifier a semantic id stands for:
+    /// the value of an `ExternalReference` with a single `GlobalReference` key,
+    /// or a `ModelReference` with a single `ConceptDescription` key.
+    pub fn global_identifier(&self) -> Option<&str> {
+        match self {
+            Reference::ExternalReference(ReferenceInner { keys, .. }) => match keys.as_slice() {
+                [Key::GlobalReference(value)] => Some(value),
+                _ => None,
+            },
+            Reference::ModelReference(ReferenceInner { keys, .. }) => match keys.as_slice() {
+                [Key::ConceptDescription(value)] => Some(value),
+                _ => None,
+            },
         }
     }
 
-    deserializer.deserialize_option(SubmodelsVisitor)
+    /// Semantic equivalence, used to match semantic ids.
+    ///
+    /// References standing for a global identifier (see [`Reference::global_identifier`])
+    /// match by that identifier, all other references have to be equal in type and keys.
+    /// If both references have a referred semantic id, these have to match as well.
+    pub fn matches_semantic_id(&self, other: &Reference) -> bool {
+        let same_target = match (self.global_identifier(), other.global_identifier()) {
+            (Some(id), Some(other_id)) => id == other_id,
+            (None, None) => {
+                self.reference_type() == other.reference_type() && self.keys() == other.keys()
+            }
+            _ => false,
+        };
+
+        same_target
+            && match (self.referred_semantic_id(), other.referred_semantic_id()) {
+                (Some(referred), Some(other_referred)) => {
+                    referred.matches_semantic_id(other_referred)
+                }
+                _ => true,
+            }
+    }
+
+    /// Matches a plain identifier like `0173-1#02-AAO677#002` semantically.
+    pub fn matches_identifier(&self, identifier: &str) -> bool {
+        self.global_identifier() == Some(identifier)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ReferenceTypeError {
+    #[error("Expected a {expected}, found a {found}")]
+    UnexpectedType { expected: String, found: String },
 }
 
-pub fn deserialize_external_reference<'de, D>(deserializer: D) -> Result<Reference, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    // Define a visitor to handle deserialization from sequence of ModelReference
-    struct SubmodelsVisitor;
+#[derive(Error, Debug, PartialEq)]
+pub enum ReferenceParseError {
+    #[error("A reference has to start with [ModelRef] or [ExternalRef]")]
+    MissingReferenceType,
+
+    #[error("Unknown reference type {0}")]
+    UnknownReferenceType(String),
+
+    #[error("The referred semantic id has to be closed by ' -]'")]
+    UnclosedReferredSemanticId,
+
+    #[error("A reference needs at least one key")]
+    NoKeys,
 
-    impl<'de> Visitor<'de> for SubmodelsVisitor {
-        type Value = Reference;
+    #[error(transparent)]
+    Key(#[from] KeyParseError),
 
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("an optional sequence of ModelReference")
+    #[error("Unexpected trailing characters: {0}")]
+    TrailingCharacters(String),
+}
+
+impl Display for Reference {
+    /// Formats as `[ModelRef](Submodel)urn:x:sm, (Property)temp`.
+    /// A referred semantic id is nested as `[ModelRef- [ExternalRef](GlobalReference)urn:x -](Submodel)urn:x:sm`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Reference::ExternalReference(inner) => write_reference(f, "ExternalRef", inner),
+            Reference::ModelReference(inner) => write_reference(f, "ModelRef", inner),
         }
+    }
+}
 
-        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let reference: Reference = Reference::deserialize(deserializer)?;
+fn write_reference(
+    f: &mut Formatter<'_>,
+    reference_type: &str,
+    inner: &ReferenceInner,
+) -> fmt::Result {
+    match &inner.referred_semantic_id {
+        Some(semantic_id) => write!(f, "[{}- {} -]", reference_type, semantic_id)?,
+        None => write!(f, "[{}]", reference_type)?,
+    }
 
-            if let Reference::ExternalReference(model_ref_inner) = reference {
-                // Convert model_ref_inner into Submodel
-                // Assuming From<ReferenceInner> for Submodel is implemented
-                Ok(Reference::ExternalReference(model_ref_inner))
-            } else {
-                Err(D::Error::custom("unexpected reference type"))
-            }
+    for (i, key) in inner.keys.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
         }
+        write!(f, "{}", key)?;
     }
 
-    deserializer.deserialize_option(SubmodelsVisitor)
+    Ok(())
 }
 
-pub fn deserialize_optional_model_reference<'de, D>(
-    deserializer: D,
-) -> Result<Option<Reference>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    // Define a visitor to handle deserialization from sequence of ModelReference
-    struct SubmodelsVisitor;
+impl FromStr for Reference {
+    type Err = ReferenceParseError;
 
-    impl<'de> Visitor<'de> for SubmodelsVisitor {
-        type Value = Option<Reference>;
+    /// Parse from the text serialization of Part 2.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use aas::part_1::v3_1::{Key, Reference, ReferenceInner};
+    ///
+    /// let reference = Reference::from_str("[ModelRef](Submodel)urn:x:sm, (Property)temp").unwrap();
+    ///
+    /// assert_eq!(
+    ///     reference,
+    ///     Reference::ModelReference(ReferenceInner::from_vec(vec![
+    ///         Key::Submodel("urn:x:sm".to_string()),
+    ///         Key::Property("temp".to_string()),
+    ///     ]))
+    /// );
+    /// assert_eq!(reference.to_string(), "[ModelRef](Submodel)urn:x:sm, (Property)temp");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (reference, rest) = parse_reference(s.trim(), false)?;
 
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("an optional sequence of ModelReference")
+        if !rest.is_empty() {
+            return Err(ReferenceParseError::TrailingCharacters(rest.to_string()));
         }
 
-        fn visit_none<E>(self) -> Result<Self::Value, E> {
-            Ok(None)
+        Ok(reference)
+    }
+}
+
+/// Parses a reference from the start of `input` and returns the unparsed rest.
+/// Nested references (referred semantic ids) end in front of ` -]`.
+fn parse_reference(input: &str, nested: bool) -> Result<(Reference, &str), ReferenceParseError> {
+    let input = input
+        .strip_prefix('[')
+        .ok_or(ReferenceParseError::MissingReferenceType)?;
+
+    let type_end = input
+        .find(['-', ']'])
+        .ok_or(ReferenceParseError::MissingReferenceType)?;
+
+    let make_reference: fn(ReferenceInner) -> Reference = match &input[..type_end] {
+        "ModelRef" => Reference::ModelReference,
+        "ExternalRef" => Reference::ExternalReference,
+        other => return Err(ReferenceParseError::UnknownReferenceType(other.to_string())),
+    };
+
+    let mut rest = &input[type_end..];
+    let mut referred_semantic_id = None;
+
+    if let Some(nested_input) = rest.strip_prefix('-') {
+        let (semantic_id, nested_rest) = parse_reference(nested_input.trim_start(), true)?;
+
+        rest = nested_rest
+            .trim_start()
+            .strip_prefix("-]")
+            .ok_or(ReferenceParseError::UnclosedReferredSemanticId)?;
+        referred_semantic_id = Some(Box::new(semantic_id));
+    } else {
+        rest = &rest[1..];
+    }
+
+    let mut keys = Vec::new();
+
+    while rest.starts_with('(') {
+        let end = key_end(rest, nested);
+        keys.push(Key::from_str(&rest[..end])?);
+        rest = &rest[end..];
+
+        match rest.strip_prefix(", ") {
+            Some(next) if next.starts_with('(') => rest = next,
+            _ => break,
         }
+    }
 
-        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let reference: Reference = Reference::deserialize(deserializer)?;
+    if keys.is_empty() {
+        return Err(ReferenceParseError::NoKeys);
+    }
 
-            if let Reference::ModelReference(model_ref_inner) = reference {
-                // Convert model_ref_inner into Submodel
-                // Assuming From<ReferenceInner> for Submodel is implemented
-                Ok(Some(Reference::ModelReference(model_ref_inner)))
-            } else {
-                Err(D::Error::custom("unexpected reference type"))
-            }
+    Ok((
+        make_reference(ReferenceInner {
+            referred_semantic_id,
+            keys,
+        }),
+        rest,
+    ))
+}
+
+/// A key value ends in front of the next key `, (KeyType)`,
+/// in front of ` -]` for nested references or at the end of the input.
+fn key_end(input: &str, nested: bool) -> usize {
+    let mut end = input.len();
+
+    for (i, _) in input.match_indices(", (") {
+        let next_key = &input[i + 3..];
+        let is_key = next_key
+            .split_once(')')
+            .is_some_and(|(key_type, _)| KeyTypes::from_str(key_type).is_ok());
+
+        if is_key {
+            end = i;
+            break;
         }
     }
 
-    deserializer.deserialize_option(SubmodelsVisitor)
+    if nested && let Some(i) = input.find(" -]") {
+        end = end.min(i);
+    }
+
+    end
+}
+
+/// Serializes a borrowed `ReferenceInner` with the `type` tag of a `Reference`.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum TaggedReference<'a> {
+    ExternalReference(&'a ReferenceInner),
+    ModelReference(&'a ReferenceInner),
 }
 
-pub fn deserialize_optional_external_reference<'de, D>(
-    deserializer: D,
-) -> Result<Option<Reference>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    // Define a visitor to handle deserialization from sequence of ModelReference
-    struct SubmodelsVisitor;
-
-    impl<'de> Visitor<'de> for SubmodelsVisitor {
-        type Value = Option<Reference>;
-
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("an optional sequence of ModelReference")
-        }
-
-        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let reference: Reference = Reference::deserialize(deserializer)?;
-
-            if let Reference::ExternalReference(model_ref_inner) = reference {
-                // Convert model_ref_inner into Submodel
-                // Assuming From<ReferenceInner> for Submodel is implemented
-                Ok(Some(Reference::ExternalReference(model_ref_inner)))
-            } else {
-                Err(D::Error::custom("unexpected reference type"))
+macro_rules! typed_reference {
+    ($(#[$meta:meta])* $name:ident, $notation:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, PartialEq, Debug, Default)]
+        pub struct $name(ReferenceInner);
+
+        impl $name {
+            pub fn new(inner: ReferenceInner) -> Self {
+                Self(inner)
+            }
+
+            pub fn into_inner(self) -> ReferenceInner {
+                self.0
+            }
+        }
+
+        impl From<ReferenceInner> for $name {
+            fn from(inner: ReferenceInner) -> Self {
+                Self(inner)
+            }
+        }
+
+        impl From<$name> for Reference {
+            fn from(reference: $name) -> Self {
+                Reference::$name(reference.0)
+            }
+        }
+
+        impl TryFrom<Reference> for $name {
+            type Error = ReferenceTypeError;
+
+            fn try_from(reference: Reference) -> Result<Self, Self::Error> {
+                match reference {
+                    Reference::$name(inner) => Ok(Self(inner)),
+                    other => Err(ReferenceTypeError::UnexpectedType {
+                        expected: stringify!($name).to_string(),
+                        found: other.reference_type().to_string(),
+                    }),
+                }
+            }
+        }
+
+        impl PartialEq<Reference> for $name {
+            fn eq(&self, other: &Reference) -> bool {
+                matches!(other, Reference::$name(inner) if *inner == self.0)
+            }
+        }
+
+        impl PartialEq<$name> for Reference {
+            fn eq(&self, other: &$name) -> bool {
+                other == self
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write_reference(f, $notation, &self.0)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = ReferenceInner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                TaggedReference::$name(&self.0).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $name::try_from(Reference::deserialize(deserializer)?).map_err(D::Error::custom)
             }
         }
+    };
+}
+
+typed_reference!(
+    /// A `Reference` which is statically known to be a `ModelReference`,
+    /// i.e. for fields like `AssetAdministrationShell::submodels`.
+    ModelReference,
+    "ModelRef"
+);
+
+typed_reference!(
+    /// A `Reference` which is statically known to be an `ExternalReference`,
+    /// i.e. for fields like `SpecificAssetId::external_subject_id`.
+    ExternalReference,
+    "ExternalRef"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_model_reference() {
+        let json = r#"{"type":"ModelReference","keys":[{"type":"Submodel","value":"urn:x:sm"}]}"#;
+
+        let reference: ModelReference = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            reference,
+            ModelReference::new(ReferenceInner::new(Key::Submodel("urn:x:sm".into())))
+        );
+        assert_eq!(serde_json::to_string(&reference).unwrap(), json);
     }
 
-    deserializer.deserialize_option(SubmodelsVisitor)
+    #[test]
+    fn reject_wrong_reference_type() {
+        let json =
+            r#"{"type":"ExternalReference","keys":[{"type":"GlobalReference","value":"urn:x"}]}"#;
+
+        assert!(serde_json::from_str::<ModelReference>(json).is_err());
+        assert!(serde_json::from_str::<ExternalReference>(json).is_ok());
+    }
+
+    #[test]
+    fn display_reference() {
+        let reference = Reference::ModelReference(ReferenceInner {
+            referred_semantic_id: Some(Box::new(Reference::ExternalReference(
+                ReferenceInner::new(Key::GlobalReference("urn:x:semantic".into())),
+            ))),
+            keys: vec![
+                Key::Submodel("urn:x:sm".into()),
+                Key::Property("temp".into()),
+            ],
+        });
+
+        assert_eq!(
+            reference.to_string(),
+            "[ModelRef- [ExternalRef](GlobalReference)urn:x:semantic -](Submodel)urn:x:sm, (Property)temp"
+        );
+    }
+
+    #[test]
+    fn parse_reference_round_trip() {
+        for text in [
+            "[ModelRef](Submodel)urn:x:sm, (Property)temp",
+            "[ExternalRef](GlobalReference)0173-1#02-AAO677#002",
+            "[ModelRef- [ExternalRef](GlobalReference)urn:x:semantic -](Submodel)urn:x:sm",
+            "[ModelRef- [ModelRef- [ExternalRef](GlobalReference)a, b -](Submodel)c -](Submodel)d",
+            // ", " without a following key type belongs to the value
+            "[ExternalRef](GlobalReference)Motor, 5kW, (Property)power",
+        ] {
+            let reference = Reference::from_str(text).unwrap();
+
+            assert_eq!(reference.to_string(), text);
+        }
+
+        let reference =
+            Reference::from_str("[ExternalRef](GlobalReference)Motor, 5kW, (Property)power")
+                .unwrap();
+        assert_eq!(reference.inner().keys.len(), 2);
+        assert_eq!(reference.inner().keys[0].value(), "Motor, 5kW");
+    }
+
+    #[test]
+    fn parse_invalid_reference() {
+        assert_eq!(
+            Reference::from_str("(Submodel)urn:x:sm"),
+            Err(ReferenceParseError::MissingReferenceType)
+        );
+        assert_eq!(
+            Reference::from_str("[LocalRef](Submodel)urn:x:sm"),
+            Err(ReferenceParseError::UnknownReferenceType("LocalRef".into()))
+        );
+        assert_eq!(
+            Reference::from_str("[ModelRef]"),
+            Err(ReferenceParseError::NoKeys)
+        );
+        assert_eq!(
+            Reference::from_str("[ModelRef- [ExternalRef](GlobalReference)x](Submodel)y"),
+            Err(ReferenceParseError::UnclosedReferredSemanticId)
+        );
+    }
+
+    #[test]
+    fn prefix_and_parent() {
+        let submodel = Reference::from_str("[ModelRef](Submodel)sm").unwrap();
+        let collection =
+            Reference::from_str("[ModelRef](Submodel)sm, (SubmodelElementCollection)c").unwrap();
+        let property = Reference::from_str(
+            "[ModelRef](Submodel)sm, (SubmodelElementCollection)c, (Property)p",
+        )
+        .unwrap();
+
+        assert!(submodel.is_prefix_of(&submodel));
+        assert!(submodel.is_prefix_of(&property));
+        assert!(!property.is_prefix_of(&submodel));
+        assert!(submodel.is_parent_of(&collection));
+        assert!(!submodel.is_parent_of(&property));
+        assert!(!submodel.is_prefix_of(&Reference::from_str("[ExternalRef](Submodel)sm").unwrap()));
+    }
+
+    #[test]
+    fn eq_ignore_type() {
+        let model = Reference::from_str("[ModelRef](Submodel)sm").unwrap();
+        let external = Reference::from_str("[ExternalRef](Submodel)sm").unwrap();
+
+        assert_ne!(model, external);
+        assert!(model.eq_ignore_type(&external));
+    }
+
+    #[test]
+    fn semantic_id_matching() {
+        let global =
+            Reference::from_str("[ExternalRef](GlobalReference)0173-1#02-AAO677#002").unwrap();
+        let concept =
+            Reference::from_str("[ModelRef](ConceptDescription)0173-1#02-AAO677#002").unwrap();
+        let other =
+            Reference::from_str("[ExternalRef](GlobalReference)0173-1#02-AAO677#003").unwrap();
+
+        assert!(global.matches_semantic_id(&concept));
+        assert!(!global.matches_semantic_id(&other));
+        assert!(global.matches_identifier("0173-1#02-AAO677#002"));
+
+        let with_referred = Reference::from_str(
+            "[ExternalRef- [ExternalRef](GlobalReference)a -](GlobalReference)0173-1#02-AAO677#002",
+        )
+        .unwrap();
+        let with_other_referred = Reference::from_str(
+            "[ExternalRef- [ExternalRef](GlobalReference)b -](GlobalReference)0173-1#02-AAO677#002",
+        )
+        .unwrap();
+
+        assert!(with_referred.matches_semantic_id(&global));
+        assert!(!with_referred.matches_semantic_id(&with_other_referred));
+    }
+
+    #[test]
+    fn convert_into_reference() {
+        let inner = ReferenceInner::new(Key::GlobalReference("urn:x".into()));
+        let reference: Reference = ExternalReference::new(inner.clone()).into();
+
+        assert_eq!(reference, Reference::ExternalReference(inner.clone()));
+        assert_eq!(
+            ModelReference::try_from(reference),
+            Err(ReferenceTypeError::UnexpectedType {
+                expected: "ModelReference".into(),
+                found: "ExternalReference".into(),
+            })
+        );
+    }
 }
@@ -0,0 +1,428 @@
+use crate::part_1::v3_1::attributes::administrative_information::AdministrativeInformation;
+use crate::part_1::v3_1::attributes::data_specification::{
+    DataSpecificationIec61360, EmbeddedDataSpecification, HasDataSpecification,
+};
+use crate::part_1::v3_1::attributes::extension::{Extension, HasExtensions};
+use crate::part_1::v3_1::attributes::identifiable::Identifiable;
+use crate::part_1::v3_1::attributes::qualifiable::{Qualifiable, Qualifier, QualifierInner};
+use crate::part_1::v3_1::attributes::referable::Referable;
+use crate::part_1::v3_1::attributes::semantics::HasSemantics;
+use crate::part_1::v3_1::concept_description::ConceptDescription;
+use crate::part_1::v3_1::core::{
+    AssetAdministrationShell, AssetInformation, AssetInformationInner, SpecificAssetId, Submodel,
+};
+use crate::part_1::v3_1::key::Key;
+use crate::part_1::v3_1::reference::{
+    ExternalReference, ModelReference, Reference, ReferenceInner,
+};
+use crate::part_1::v3_1::submodel_elements::{
+    AnnotatedRelationshipElement, BasicEventElement, Blob, Capability, DataElement, Entity,
+    EntityInner, File, MultiLanguageProperty, Operation, OperationVariable, Property, Range,
+    ReferenceElement, RelationshipElement, SubmodelElement, SubmodelElementCollection,
+    SubmodelElementFields, SubmodelElementList,
+};
+use crate::part_1::v3_1::value_list::{ValueList, ValueReferencePair};
+
+/// Visits the references of the fields, with the JSON name of each field appended to the
+/// pointer. Flattened fields have an empty name.
+macro_rules! visit_fields {
+    ($this:expr, $method:ident, $pointer:expr, $visit:expr, { $($field:ident: $name:literal),* $(,)? }) => {
+        $($this.$field.$method(&format!("{}{}", $pointer, $name), $visit);)*
+    };
+}
+
+/// Declares a reference walker over the model, for shared or for mutable access.
+macro_rules! references {
+    ($trait:ident, $method:ident, $iter:ident, $entity:ident, $range:ident $(, $mut:tt)?) => {
+        /// Visits every reference of a model element together with its JSON pointer, relative to
+        /// the element, i.e. `/submodelElements/0/semanticId`. Referred semantic ids are visited
+        /// before the reference they belong to.
+        pub(crate) trait $trait {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            );
+        }
+
+        impl<T: $trait> $trait for Option<T> {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                if let Some(value) = self {
+                    value.$method(pointer, visit);
+                }
+            }
+        }
+
+        impl<T: $trait> $trait for Vec<T> {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                for (index, item) in self.$iter().enumerate() {
+                    item.$method(&format!("{pointer}/{index}"), visit);
+                }
+            }
+        }
+
+        impl $trait for ReferenceInner {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                if let Some(referred) = &$($mut)? self.referred_semantic_id {
+                    referred.$method(&format!("{pointer}/referredSemanticId"), visit);
+                }
+                visit(pointer, self);
+            }
+        }
+
+        impl $trait for Reference {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                match self {
+                    Reference::ExternalReference(inner) | Reference::ModelReference(inner) => {
+                        inner.$method(pointer, visit)
+                    }
+                }
+            }
+        }
+
+        impl $trait for ModelReference {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                ReferenceInner::$method(self, pointer, visit);
+            }
+        }
+
+        impl $trait for ExternalReference {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                ReferenceInner::$method(self, pointer, visit);
+            }
+        }
+
+        impl $trait for Qualifier {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                match self {
+                    Qualifier::ConceptQualifier(inner)
+                    | Qualifier::TemplateQualifier(inner)
+                    | Qualifier::ValueQualifier(inner)
+                    | Qualifier::Unknown(inner) => inner.$method(pointer, visit),
+                }
+            }
+        }
+
+        impl $trait for AssetInformation {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                match self {
+                    AssetInformation::Instance(inner)
+                    | AssetInformation::NotApplicable(inner)
+                    | AssetInformation::Role(inner)
+                    | AssetInformation::Type(inner) => inner.$method(pointer, visit),
+                }
+            }
+        }
+
+        impl $trait for Entity {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                self.$entity().$method(pointer, visit);
+            }
+        }
+
+        impl $trait for Range {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                self.$range().$method(pointer, visit);
+            }
+        }
+
+        impl $trait for SubmodelElement {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                match self {
+                    SubmodelElement::RelationshipElement(element) => element.$method(pointer, visit),
+                    SubmodelElement::AnnotatedRelationshipElement(element) => {
+                        element.$method(pointer, visit)
+                    }
+                    SubmodelElement::BasicEventElement(element) => element.$method(pointer, visit),
+                    SubmodelElement::Blob(element) => element.$method(pointer, visit),
+                    SubmodelElement::Capability(element) => element.$method(pointer, visit),
+                    SubmodelElement::DataElement(element) => element.$method(pointer, visit),
+                    SubmodelElement::Entity(element) => element.$method(pointer, visit),
+                    SubmodelElement::File(element) => element.$method(pointer, visit),
+                    SubmodelElement::MultiLanguageProperty(element) => {
+                        element.$method(pointer, visit)
+                    }
+                    SubmodelElement::Operation(element) => element.$method(pointer, visit),
+                    SubmodelElement::Property(element) => element.$method(pointer, visit),
+                    SubmodelElement::Range(element) => element.$method(pointer, visit),
+                    SubmodelElement::ReferenceElement(element) => element.$method(pointer, visit),
+                    SubmodelElement::SubmodelElementCollection(element) => {
+                        element.$method(pointer, visit)
+                    }
+                    SubmodelElement::SubmodelElementList(element) => {
+                        element.$method(pointer, visit)
+                    }
+                }
+            }
+        }
+
+        // Data elements are serialized externally tagged, i.e. `{"Property": {…}}`.
+        impl $trait for DataElement {
+            fn $method(
+                &$($mut)? self,
+                pointer: &str,
+                visit: &mut dyn FnMut(&str, &$($mut)? ReferenceInner),
+            ) {
+                let pointer = format!("{pointer}/{self}");
+                match self {
+                    DataElement::Blob(element) => element.$method(&pointer, visit),
+                    DataElement::File(element) => element.$method(&pointer, visit),
+                    DataElement::MultiLanguageProperty(element) => element.$method(&pointer, visit),
+                    DataElement::Property(element) => element.$method(&pointer, visit),
+                    DataElement::Range(element) => element.$method(&pointer, visit),
+                    DataElement::ReferenceElement(element) => element.$method(&pointer, visit),
+                }
+            }
+        }
+
+        references!(@fields $trait, $method, [$($mut)?] {
+            HasSemantics {
+                semantic_id: "/semanticId",
+                supplemental_semantic_ids: "/supplementalSemanticIds",
+            }
+            HasExtensions { extension: "/extensions" }
+            Extension {
+                semantic_id: "/semanticId",
+                supplemental_semantic_ids: "/supplementalSemanticIds",
+                refers_to: "/refersTo",
+            }
+            Referable { extensions: "" }
+            AdministrativeInformation { creator: "/creator", data_specification: "" }
+            Identifiable { administrative_information: "/administrative_information", referable: "" }
+            HasDataSpecification { embedded_data_specifications: "/embeddedDataSpecifications" }
+            EmbeddedDataSpecification {
+                data_specification: "/dataSpecification",
+                data_specification_content: "/dataSpecificationContent",
+            }
+            DataSpecificationIec61360 { unit_id: "/unitId", value_list: "/valueList" }
+            ValueList { value_reference_pairs: "/valueReferencePairs" }
+            ValueReferencePair { value_id: "/valueId" }
+            Qualifiable { qualifiers: "/qualifiers" }
+            QualifierInner { semantics: "", value_id: "/valueId" }
+            AssetAdministrationShell {
+                asset_information: "/assetInformation",
+                identifiable: "",
+                data_specification: "",
+                derived_from: "/derivedFrom",
+                submodels: "/submodels",
+            }
+            AssetInformationInner { specific_asset_ids: "/specificAssetIds" }
+            SpecificAssetId { has_semantics: "", external_subject_id: "/externalSubjectId" }
+            Submodel {
+                identifiable: "",
+                semantics: "",
+                qualifier: "",
+                data_specification: "",
+                submodel_elements: "/submodelElements",
+            }
+            ConceptDescription { identifiable: "", data_specification: "", is_case_of: "/isCaseOf" }
+            SubmodelElementFields {
+                referable: "",
+                semantics: "",
+                qualifiable: "",
+                embedded_data_specifications: "",
+            }
+            RelationshipElement { submodel_element_fields: "", first: "/first", second: "/second" }
+            AnnotatedRelationshipElement {
+                submodel_element_fields: "",
+                first: "/first",
+                second: "/second",
+                annotations: "/annotations",
+            }
+            BasicEventElement {
+                submodel_element_fields: "",
+                observed: "/observed",
+                message_broker: "/messageBroker",
+            }
+            Blob { referable: "", semantics: "", qualifiable: "", embedded_data_specifications: "" }
+            Capability {
+                referable: "",
+                semantics: "",
+                qualifiable: "",
+                embedded_data_specifications: "",
+            }
+            EntityInner {
+                submodel_element_fields: "",
+                statement: "/statements",
+                specific_asset_id: "/specificAssetIds",
+            }
+            File { referable: "", semantics: "", qualifiable: "", embedded_data_specifications: "" }
+            MultiLanguageProperty {
+                referable: "",
+                semantics: "",
+                qualifiable: "",
+                embedded_data_specifications: "",
+                value_id: "/valueId",
+            }
+            Operation {
+                referable: "",
+                semantics: "",
+                qualifiable: "",
+                embedded_data_specifications: "",
+                input_variables: "/inputVariables",
+                output_variables: "/outputVariables",
+                inoutput_variables: "/inoutputVariables",
+            }
+            OperationVariable { value: "/value" }
+            Property {
+                referable: "",
+                semantics: "",
+                qualifiable: "",
+                embedded_data_specifications: "",
+            }
+            ReferenceElement { submodel_element_fields: "", value: "/value" }
+            SubmodelElementCollection { submodel_element_fields: "", value: "/value" }
+            SubmodelElementList {
+                submodel_element_fields: "",
+                semantic_id_list_element: "/semanticIdListElement",
+                value: "/value",
+            }
+        });
+    };
+    (@fields $trait:ident, $method:ident, [] {
+        $($ty:ident { $($field:ident: $name:literal),* $(,)? })*
+    }) => {
+        $(
+            impl $trait for $ty {
+                fn $method(
+                    &self,
+                    pointer: &str,
+                    visit: &mut dyn FnMut(&str, &ReferenceInner),
+                ) {
+                    visit_fields!(self, $method, pointer, visit, { $($field: $name),* });
+                }
+            }
+        )*
+    };
+    (@fields $trait:ident, $method:ident, [mut] {
+        $($ty:ident { $($field:ident: $name:literal),* $(,)? })*
+    }) => {
+        $(
+            impl $trait for $ty {
+                fn $method(
+                    &mut self,
+                    pointer: &str,
+                    visit: &mut dyn FnMut(&str, &mut ReferenceInner),
+                ) {
+                    visit_fields!(self, $method, pointer, visit, { $($field: $name),* });
+                }
+            }
+        )*
+    };
+}
+
+references!(
+    VisitReferences,
+    visit_references,
+    iter,
+    inner,
+    submodel_element_fields
+);
+references!(
+    VisitReferencesMut,
+    visit_references_mut,
+    iter_mut,
+    inner_mut,
+    submodel_element_fields_mut,
+    mut
+);
+
+/// The keys of every reference of `item`, i.e. to find the identifiables it depends on.
+pub(crate) fn reference_keys(item: &impl VisitReferences) -> Vec<Vec<Key>> {
+    let mut references = Vec::new();
+    item.visit_references("", &mut |_, reference| {
+        references.push(reference.keys.clone())
+    });
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pointers_follow_the_serialization() {
+        let global = |id: &str| json!({ "type": "ExternalReference", "keys": [{ "type": "GlobalReference", "value": id }] });
+        let mut submodel: Submodel = serde_json::from_value(json!({
+            "modelType": "Submodel",
+            "id": "urn:sm:1",
+            "submodelElements": [{
+                "modelType": "SubmodelElementList",
+                "idShort": "List",
+                "typeValueListElement": "Property",
+                "value": [{
+                    "modelType": "Property",
+                    "valueType": "xs:int",
+                    "qualifiers": [{ "type": "Unit", "valueType": "xs:string", "valueId": global("urn:unit") }],
+                    "semanticId": {
+                        "type": "ExternalReference",
+                        "referredSemanticId": global("urn:referred"),
+                        "keys": [{ "type": "GlobalReference", "value": "urn:semantic" }]
+                    }
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let mut visited = Vec::new();
+        submodel.visit_references("", &mut |pointer, reference| {
+            visited.push((pointer.to_string(), reference.keys[0].value().clone()))
+        });
+        let json = serde_json::to_value(&submodel).unwrap();
+        for (pointer, value) in &visited {
+            assert_eq!(
+                json.pointer(&format!("{pointer}/keys/0/value")).unwrap(),
+                value
+            );
+        }
+        assert_eq!(visited.len(), 3);
+
+        submodel.visit_references_mut("", &mut |_, reference| reference.keys.clear());
+        assert_eq!(reference_keys(&submodel), vec![Vec::<Key>::new(); 3]);
+    }
+}
@@ -1,16 +1,18 @@
-use crate::part_1::v3_1::primitives::{DateTimeUTC, MessageTopic};
-use crate::part_1::v3_1::reference::Reference;
+use crate::part_1::v3_1::primitives::{BlobType, DateTimeUTC, MessageTopic};
+use crate::part_1::v3_1::reference::{ExternalReference, ModelReference, Reference};
 use crate::part_1::v3_1::submodel_elements::SubmodelElementFields;
 use crate::part_1::{MetamodelError, ToJsonMetamodel};
 use serde::{Deserialize, Serialize};
+use serde_with::base64::Base64;
+use serde_with::serde_as;
 use strum::{Display, EnumString};
 
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub struct BasicEventElement {
     #[serde(flatten)]
-    submodel_element_fields: SubmodelElementFields,
+    pub submodel_element_fields: SubmodelElementFields,
 
-    pub observed: Reference,
+    pub observed: ModelReference,
 
     pub direction: Direction,
 
@@ -22,7 +24,7 @@ pub struct BasicEventElement {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "messageBroker")]
-    pub message_broker: Option<Reference>,
+    pub message_broker: Option<ModelReference>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "lastUpdate")]
@@ -42,7 +44,7 @@ pub struct BasicEventElement {
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub struct BasicEventElementMeta {
     #[serde(flatten)]
-    submodel_element_fields: SubmodelElementFields,
+    pub submodel_element_fields: SubmodelElementFields,
 
     pub direction: Direction,
 
@@ -54,7 +56,7 @@ pub struct BasicEventElementMeta {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "messageBroker")]
-    pub message_broker: Option<Reference>,
+    pub message_broker: Option<ModelReference>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "lastUpdate")]
@@ -97,7 +99,7 @@ impl ToJsonMetamodel for BasicEventElement {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         serde_json::to_string::<BasicEventElementMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+            .map_err(MetamodelError::FailedSerialisation)
     }
 }
 
@@ -116,3 +118,39 @@ pub enum Direction {
     #[serde(rename = "output")]
     Output,
 }
+
+/// Message sent by an event element, see [`crate::part_1::v3_1::events`].
+#[serde_as]
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct EventPayload {
+    /// Reference to the `BasicEventElement` emitting the event.
+    pub source: ModelReference,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sourceSemanticId")]
+    pub source_semantic_id: Option<Reference>,
+
+    /// Reference to the observed element.
+    #[serde(rename = "observableReference")]
+    pub observable_reference: ModelReference,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "observableSemanticId")]
+    pub observable_semantic_id: Option<Reference>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<MessageTopic>,
+
+    /// The subject, i.e. the user, which caused the event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "subjectId")]
+    pub subject_id: Option<ExternalReference>,
+
+    #[serde(rename = "timeStamp")]
+    pub time_stamp: DateTimeUTC,
+
+    /// Event specific data, base64 encoded in JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<Base64>")]
+    pub payload: Option<BlobType>,
+}
@@ -4,9 +4,12 @@ use crate::part_1::v3_1::attributes::data_specification::HasDataSpecification;
 use crate::part_1::v3_1::attributes::qualifiable::Qualifiable;
 use crate::part_1::v3_1::attributes::referable::Referable;
 use crate::part_1::v3_1::attributes::semantics::HasSemantics;
-use crate::part_1::v3_1::primitives::ContentType;
+use crate::part_1::v3_1::primitives::{BlobType, ContentType};
 use serde::{Deserialize, Serialize};
+use serde_with::base64::Base64;
+use serde_with::serde_as;
 
+#[serde_as]
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize, Default)]
 pub struct Blob {
     // Inherited from DataElement
@@ -22,17 +25,17 @@ pub struct Blob {
     #[serde(flatten)]
     pub embedded_data_specifications: HasDataSpecification,
     // ----- end inheritance
+    /// Decoded content, base64 encoded in JSON.
     #[serde(skip_serializing_if = "Option::is_none")]
-    // TODO "contentEncoding": "base64"
-    pub value: Option<String>,
+    #[serde_as(as = "Option<Base64>")]
+    pub value: Option<BlobType>,
 
-    // TODO typing. Add constraints. New type..
     #[serde(rename = "contentType")]
     pub content_type: ContentType,
 }
 
 impl Blob {
-    pub fn new(content_type: String) -> Self {
+    pub fn new(content_type: ContentType) -> Self {
         Self {
             referable: Referable::default(),
             semantics: HasSemantics::default(),
@@ -82,8 +85,7 @@ impl ToJsonMetamodel for Blob {
     type Error = MetamodelError;
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
-        serde_json::to_string::<BlobMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+        serde_json::to_string::<BlobMeta>(&self.into()).map_err(MetamodelError::FailedSerialisation)
     }
 }
 
@@ -100,7 +102,7 @@ mod tests {
         {
             "modelType":"Blob",
             "value": null,
-            "contentType": ""
+            "contentType": "application/octet-stream"
         }
         "#;
 
@@ -109,9 +111,26 @@ mod tests {
         assert_eq!(expect, blob);
     }
 
+    #[test]
+    fn it_deserializes_base64() {
+        let json = r#"{"value":"SGVsbG8=","contentType":"text/plain"}"#;
+
+        let blob: Blob = serde_json::from_str(json).unwrap();
+
+        assert_eq!(blob.value, Some(b"Hello".to_vec()));
+        assert_eq!(serde_json::to_string(&blob).unwrap(), json);
+    }
+
+    #[test]
+    fn it_rejects_invalid_base64() {
+        let json = r#"{"value":"not base64!","contentType":"text/plain"}"#;
+
+        assert!(serde_json::from_str::<Blob>(json).is_err());
+    }
+
     #[test]
     fn it_serializes() {
-        let blob = Blob::new(String::from(""));
+        let blob = Blob::new(ContentType::default());
 
         let json = serde_json::to_string(&blob).unwrap();
 
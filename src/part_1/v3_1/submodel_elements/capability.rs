@@ -39,7 +39,7 @@ impl ToJsonMetamodel for Capability {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         // TODO: Add modelType!
-        serde_json::to_string(&self).map_err(|e| MetamodelError::FailedSerialisation(e))
+        serde_json::to_string(&self).map_err(MetamodelError::FailedSerialisation)
     }
 }
 
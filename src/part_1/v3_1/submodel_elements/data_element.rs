@@ -1,3 +1,6 @@
+use crate::part_1::v3_1::attributes::qualifiable::Qualifiable;
+use crate::part_1::v3_1::attributes::referable::Referable;
+use crate::part_1::v3_1::attributes::semantics::HasSemantics;
 use crate::part_1::v3_1::submodel_elements::Blob;
 use crate::part_1::v3_1::submodel_elements::file::File;
 use crate::part_1::v3_1::submodel_elements::multi_language_property::MultiLanguageProperty;
@@ -18,6 +21,50 @@ pub enum DataElement {
     ReferenceElement(ReferenceElement),
 }
 
+/// Accesses a field every data element has.
+macro_rules! data_element_field {
+    ($element:expr, $field:ident) => {
+        data_element_field!(@ $element, $field, submodel_element_fields)
+    };
+    ($element:expr, $field:ident, mut) => {
+        data_element_field!(@ $element, $field, submodel_element_fields_mut, mut)
+    };
+    (@ $element:expr, $field:ident, $range_fields:ident $(, $mut:tt)?) => {
+        match $element {
+            DataElement::Blob(element) => &$($mut)? element.$field,
+            DataElement::File(element) => &$($mut)? element.$field,
+            DataElement::MultiLanguageProperty(element) => &$($mut)? element.$field,
+            DataElement::Property(element) => &$($mut)? element.$field,
+            DataElement::Range(element) => &$($mut)? element.$range_fields().$field,
+            DataElement::ReferenceElement(element) => {
+                &$($mut)? element.submodel_element_fields.$field
+            }
+        }
+    };
+}
+
+impl DataElement {
+    pub fn referable(&self) -> &Referable {
+        data_element_field!(self, referable)
+    }
+
+    pub fn referable_mut(&mut self) -> &mut Referable {
+        data_element_field!(self, referable, mut)
+    }
+
+    pub fn semantics(&self) -> &HasSemantics {
+        data_element_field!(self, semantics)
+    }
+
+    pub fn semantics_mut(&mut self) -> &mut HasSemantics {
+        data_element_field!(self, semantics, mut)
+    }
+
+    pub fn qualifiable(&self) -> &Qualifiable {
+        data_element_field!(self, qualifiable)
+    }
+}
+
 impl ToJsonMetamodel for DataElement {
     type Error = MetamodelError;
 
@@ -1,7 +1,7 @@
 use crate::part_1::ToJsonMetamodel;
 use crate::part_1::v3_1::core::SpecificAssetId;
 use crate::part_1::v3_1::primitives::Identifier;
-use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::submodel_elements::{SubmodelElement, SubmodelElementFields};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
@@ -9,6 +9,7 @@ use strum::{Display, EnumString};
 /// it is composed of (e.g. bill of material).
 /// These parts are called entities. Not all entities have a global asset ID.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize, Display, EnumString)]
+#[serde(tag = "entityType")]
 pub enum Entity {
     /// There is no separate Asset Administration Shell for co-managed entities.
     /// Co-managed entities need to be part of a self-managed entity.
@@ -21,8 +22,13 @@ pub enum Entity {
 
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize, Default)]
 pub struct EntityInner {
+    #[serde(flatten)]
+    pub submodel_element_fields: SubmodelElementFields,
+
     /// Statement applicable to the entity,
     /// each statement described by submodel element - typically with a qualified value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "statements")]
     pub statement: Option<Vec<SubmodelElement>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -30,10 +36,24 @@ pub struct EntityInner {
     pub global_asset_id: Option<Identifier>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "specificAssetId")]
+    #[serde(rename = "specificAssetIds")]
     pub specific_asset_id: Option<Vec<SpecificAssetId>>,
 }
 
+impl Entity {
+    pub fn inner(&self) -> &EntityInner {
+        match self {
+            Entity::CoManagedEntity(inner) | Entity::SelfManagedEntity(inner) => inner,
+        }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut EntityInner {
+        match self {
+            Entity::CoManagedEntity(inner) | Entity::SelfManagedEntity(inner) => inner,
+        }
+    }
+}
+
 impl ToJsonMetamodel for Entity {
     type Error = ();
 
@@ -70,7 +70,6 @@ impl ToJsonMetamodel for File {
     type Error = MetamodelError;
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
-        serde_json::to_string::<FileMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+        serde_json::to_string::<FileMeta>(&self.into()).map_err(MetamodelError::FailedSerialisation)
     }
 }
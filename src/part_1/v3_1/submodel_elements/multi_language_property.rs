@@ -76,7 +76,7 @@ impl ToJsonMetamodel for MultiLanguageProperty {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         let meta = MultiLanguagePropertyMeta::from(self);
-        serde_json::to_string(&meta).map_err(|e| MetamodelError::FailedSerialisation(e))
+        serde_json::to_string(&meta).map_err(MetamodelError::FailedSerialisation)
     }
 }
 
@@ -89,11 +89,13 @@ mod tests {
     fn it_serializes() {
         let expected = r#"{"value":[{"language":"de","text":"Das ist ein deutscher Bezeichner"},{"language":"en","text":"That's an English label"}]}"#;
 
-        let mut ml_property = MultiLanguageProperty::default();
-        ml_property.value = Some(vec![
-            LangString::from_str(r#""Das ist ein deutscher Bezeichner"@de"#).unwrap(),
-            LangString::from_str(r#""That's an English label"@en"#).unwrap(),
-        ]);
+        let ml_property = MultiLanguageProperty {
+            value: Some(vec![
+                LangString::from_str(r#""Das ist ein deutscher Bezeichner"@de"#).unwrap(),
+                LangString::from_str(r#""That's an English label"@en"#).unwrap(),
+            ]),
+            ..Default::default()
+        };
 
         let actual =
             serde_json::to_string(&ml_property).expect("Can't serialize MultiLanguageProperty.");
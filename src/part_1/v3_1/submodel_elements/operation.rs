@@ -2,7 +2,7 @@ use crate::part_1::v3_1::attributes::data_specification::HasDataSpecification;
 use crate::part_1::v3_1::attributes::qualifiable::Qualifiable;
 use crate::part_1::v3_1::attributes::referable::Referable;
 use crate::part_1::v3_1::attributes::semantics::HasSemantics;
-use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::submodel_elements::{Property, SubmodelElement};
 use crate::part_1::{MetamodelError, ToJsonMetamodel};
 use serde::{Deserialize, Serialize};
 
@@ -21,14 +21,45 @@ pub struct Operation {
     #[serde(flatten)]
     pub embedded_data_specifications: HasDataSpecification,
     // ----- end inheritance
-    #[serde(rename = "inputVariable")]
-    pub input_variable: Option<Box<SubmodelElement>>,
+    /// Input parameters of the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "inputVariables")]
+    pub input_variables: Option<Vec<OperationVariable>>,
 
-    #[serde(rename = "outputVariable")]
-    pub output_variable: Option<Box<SubmodelElement>>,
+    /// Output parameters of the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "outputVariables")]
+    pub output_variables: Option<Vec<OperationVariable>>,
 
-    #[serde(rename = "inoutputVariable")]
-    pub inoutput_variable: Option<Box<SubmodelElement>>,
+    /// Parameters that are input and output of the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "inoutputVariables")]
+    pub inoutput_variables: Option<Vec<OperationVariable>>,
+}
+
+/// The value of an operation variable is a submodel element that is used as input and/or output
+/// variable of an operation.
+/// Constraint AASd-134: For an Operation, the idShort of all inputVariable/value,
+/// outputVariable/value, and inoutputVariable/value shall be unique.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct OperationVariable {
+    pub value: SubmodelElement,
+}
+
+impl OperationVariable {
+    pub fn new(value: SubmodelElement) -> Self {
+        Self { value }
+    }
+
+    pub fn id_short(&self) -> Option<&str> {
+        self.value.id_short()
+    }
+}
+
+impl From<Property> for OperationVariable {
+    fn from(property: Property) -> Self {
+        Self::new(SubmodelElement::Property(property))
+    }
 }
 
 impl ToJsonMetamodel for Operation {
@@ -36,6 +67,35 @@ impl ToJsonMetamodel for Operation {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         // TODO: add modelType tag
-        serde_json::to_string(&self).map_err(|e| MetamodelError::FailedSerialisation(e))
+        serde_json::to_string(&self).map_err(MetamodelError::FailedSerialisation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_operation_variables() {
+        let json = r#"
+        {
+            "idShort": "Calibrate",
+            "inputVariables": [
+                {
+                    "value": {
+                        "modelType": "Property",
+                        "idShort": "Offset",
+                        "valueType": "xs:string"
+                    }
+                }
+            ]
+        }"#;
+
+        let operation: Operation = serde_json::from_str(json).unwrap();
+
+        let inputs = operation.input_variables.unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].id_short(), Some("Offset"));
+        assert!(operation.output_variables.is_none());
     }
 }
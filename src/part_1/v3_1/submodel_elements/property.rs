@@ -5,6 +5,7 @@ use crate::part_1::v3_1::attributes::qualifiable::Qualifiable;
 use crate::part_1::v3_1::attributes::referable::Referable;
 use crate::part_1::v3_1::attributes::semantics::HasSemantics;
 use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+use crate::part_1::v3_1::primitives::{DataXsdError, FromDataXsd, Identifier, IntoDataXsd};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -26,6 +27,33 @@ pub struct Property {
     pub value: DataXsd,
 }
 
+impl Property {
+    /// A property with an idShort and nothing else but the value, i.e. an operation variable.
+    pub fn new(id_short: Identifier, value: DataXsd) -> Self {
+        Self {
+            referable: Referable {
+                id_short: Some(id_short),
+                ..Default::default()
+            },
+            semantics: Default::default(),
+            qualifiable: Default::default(),
+            embedded_data_specifications: Default::default(),
+            value,
+        }
+    }
+
+    /// Typed access to the value, i.e. `property.get::<f64>()`.
+    /// Only lossless conversions succeed, see [`FromDataXsd`].
+    pub fn get<T: FromDataXsd>(&self) -> Result<Option<T>, DataXsdError> {
+        self.value.get()
+    }
+
+    /// Set the value, refusing values which don't fit the declared `valueType`.
+    pub fn set<T: IntoDataXsd>(&mut self, value: T) -> Result<(), DataXsdError> {
+        self.value.set(value)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PropertyMeta {
     // Inherited from DataElement
@@ -70,6 +98,29 @@ impl ToJsonMetamodel for Property {
     type Error = MetamodelError;
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         serde_json::to_string::<PropertyMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+            .map_err(MetamodelError::FailedSerialisation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_and_set_typed() {
+        let mut property = Property {
+            referable: Default::default(),
+            semantics: Default::default(),
+            qualifiable: Default::default(),
+            embedded_data_specifications: Default::default(),
+            value: DataXsd::Double(Some(21.5)),
+        };
+
+        assert_eq!(property.get::<f64>(), Ok(Some(21.5)));
+
+        property.set(22).unwrap();
+        assert_eq!(property.get::<f64>(), Ok(Some(22.0)));
+        assert_eq!(property.value, DataXsd::Double(Some(22.0)));
+        assert!(property.set(true).is_err());
     }
 }
@@ -1,5 +1,9 @@
 use crate::part_1::ToJsonMetamodel;
-use chrono::{DateTime, NaiveTime, Utc};
+use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+use crate::part_1::v3_1::primitives::{DataXsdError, FromDataXsd, IntoDataXsd};
+use crate::part_1::v3_1::submodel_elements::SubmodelElementFields;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
@@ -7,6 +11,9 @@ use strum::{Display, EnumString};
 // TODO: If the max value is missing, the value is assumed to be positive infinite.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize, Default)]
 pub struct RangeInner<T> {
+    #[serde(flatten)]
+    pub submodel_element_fields: SubmodelElementFields,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -42,7 +49,7 @@ pub enum Range {
     PositiveInteger(RangeInner<u32>),
 
     #[serde(rename = "xs:short")]
-    Short(RangeInner<u16>),
+    Short(RangeInner<i16>),
 
     #[serde(rename = "xs:string")]
     String(RangeInner<String>),
@@ -65,9 +72,8 @@ pub enum Range {
     #[serde(rename = "xs:unsignedShort")]
     UnsignedShort(RangeInner<u16>),
 
-    /// TODO: using proper type
     #[serde(rename = "xs:decimal")]
-    Decimal(RangeInner<String>),
+    Decimal(RangeInner<BigDecimal>),
 
     #[serde(rename = "xs:float")]
     Float(RangeInner<f32>),
@@ -82,7 +88,7 @@ pub enum Range {
 
     // TODO: TIMEZONES?
     #[serde(rename = "xs:date")]
-    Date(RangeInner<NaiveTime>),
+    Date(RangeInner<NaiveDate>),
 
     #[serde(rename = "xs:dateTime")]
     DateTime(RangeInner<DateTime<Utc>>),
@@ -120,14 +126,122 @@ pub enum Range {
 
     // string related
     #[serde(rename = "xs:anyURI")]
+    #[strum(serialize = "anyURI")]
     AnyURI(RangeInner<String>),
 }
 
+/// Runs `$body` with `$inner` bound to the `RangeInner` of any variant.
+macro_rules! with_range_inner {
+    ($range:expr, $inner:ident => $body:expr) => {
+        match $range {
+            Range::Int($inner) => $body,
+            Range::Integer($inner) => $body,
+            Range::Long($inner) => $body,
+            Range::NegativeInteger($inner) => $body,
+            Range::NonNegativeInteger($inner) => $body,
+            Range::NonPositiveInteger($inner) => $body,
+            Range::PositiveInteger($inner) => $body,
+            Range::Short($inner) => $body,
+            Range::String($inner) => $body,
+            Range::Boolean($inner) => $body,
+            Range::Byte($inner) => $body,
+            Range::UnsignedByte($inner) => $body,
+            Range::UnsignedInt($inner) => $body,
+            Range::UnsignedLong($inner) => $body,
+            Range::UnsignedShort($inner) => $body,
+            Range::Decimal($inner) => $body,
+            Range::Float($inner) => $body,
+            Range::Double($inner) => $body,
+            Range::Time($inner) => $body,
+            Range::Date($inner) => $body,
+            Range::DateTime($inner) => $body,
+            Range::Duration($inner) => $body,
+            Range::GDay($inner) => $body,
+            Range::GMonth($inner) => $body,
+            Range::GMonthDay($inner) => $body,
+            Range::GYear($inner) => $body,
+            Range::GYearMonth($inner) => $body,
+            Range::Base64Binary($inner) => $body,
+            Range::HexBinary($inner) => $body,
+            Range::AnyURI($inner) => $body,
+        }
+    };
+}
+
+impl Range {
+    pub fn submodel_element_fields(&self) -> &SubmodelElementFields {
+        with_range_inner!(self, inner => &inner.submodel_element_fields)
+    }
+
+    pub fn submodel_element_fields_mut(&mut self) -> &mut SubmodelElementFields {
+        with_range_inner!(self, inner => &mut inner.submodel_element_fields)
+    }
+
+    /// The value type as empty value, i.e. `DataXsd::Int(None)` for `xs:int`.
+    pub fn value_type(&self) -> DataXsd {
+        DataXsd::from_value_type(&self.to_string())
+            .expect("every value type of a Range is a value type of DataXsd")
+    }
+
+    /// The bounds as values of the value type, empty if a bound is not set.
+    /// Fails if a bound is outside the value space, i.e. a min of 5 for `xs:negativeInteger`.
+    pub fn bounds(&self) -> Result<(DataXsd, DataXsd), DataXsdError> {
+        let value_type = self.value_type();
+
+        with_range_inner!(self, inner => Ok((
+            bound_value(&inner.min, &value_type)?,
+            bound_value(&inner.max, &value_type)?,
+        )))
+    }
+
+    /// Replace the bounds by parsing their lexical representations, keeping the value type.
+    /// See [`DataXsd::set_lexical`].
+    pub fn set_lexical(
+        &mut self,
+        min: Option<&str>,
+        max: Option<&str>,
+    ) -> Result<(), DataXsdError> {
+        let value_type = self.value_type();
+
+        with_range_inner!(self, inner => {
+            let (min, max) = (parse_bound(&value_type, min)?, parse_bound(&value_type, max)?);
+            inner.min = min;
+            inner.max = max;
+        });
+
+        Ok(())
+    }
+}
+
+fn bound_value<T: IntoDataXsd + Clone>(
+    bound: &Option<T>,
+    value_type: &DataXsd,
+) -> Result<DataXsd, DataXsdError> {
+    match bound {
+        Some(bound) => bound.clone().into_data_xsd(value_type),
+        None => Ok(value_type.clone()),
+    }
+}
+
+fn parse_bound<T: FromDataXsd>(
+    value_type: &DataXsd,
+    lexical: Option<&str>,
+) -> Result<Option<T>, DataXsdError> {
+    match lexical {
+        Some(lexical) => {
+            let mut value = value_type.clone();
+            value.set_lexical(lexical)?;
+            value.get()
+        }
+        None => Ok(None),
+    }
+}
+
 impl ToJsonMetamodel for Range {
     type Error = ();
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
-        Ok(format!(r#"{{"valueType":"{}"}}"#, self.to_string()))
+        Ok(format!(r#"{{"valueType":"{}"}}"#, self))
     }
 }
 
@@ -135,12 +249,76 @@ impl ToJsonMetamodel for Range {
 mod tests {
     use super::*;
 
+    #[test]
+    fn set_lexical() {
+        let mut range = Range::Double(RangeInner::default());
+        range.set_lexical(Some("-1.5"), None).unwrap();
+        assert_eq!(
+            range,
+            Range::Double(RangeInner {
+                min: Some(-1.5),
+                ..Default::default()
+            })
+        );
+
+        let mut range = Range::Date(RangeInner::default());
+        range
+            .set_lexical(Some("2025-01-01"), Some("2025-12-31"))
+            .unwrap();
+        assert!(
+            Range::UnsignedByte(RangeInner::default())
+                .set_lexical(Some("256"), None)
+                .is_err()
+        );
+
+        let mut range = Range::AnyURI(RangeInner::default());
+        range
+            .set_lexical(None, Some("https://example.com"))
+            .unwrap();
+    }
+
+    #[test]
+    fn value_type_and_bounds() {
+        let range = Range::AnyURI(RangeInner {
+            max: Some("https://example.com".into()),
+            ..Default::default()
+        });
+        assert_eq!(range.to_string(), "xs:anyURI");
+        assert_eq!(range.value_type(), DataXsd::AnyURI(None));
+        assert_eq!(
+            range.bounds(),
+            Ok((
+                DataXsd::AnyURI(None),
+                DataXsd::AnyURI(Some("https://example.com".parse().unwrap()))
+            ))
+        );
+
+        let range = Range::Short(RangeInner {
+            min: Some(-3),
+            ..Default::default()
+        });
+        assert_eq!(
+            range.bounds(),
+            Ok((DataXsd::Short(Some(-3)), DataXsd::Short(None)))
+        );
+
+        let range = Range::NegativeInteger(RangeInner {
+            min: Some(5),
+            ..Default::default()
+        });
+        assert_eq!(
+            range.bounds(),
+            Err(DataXsdError::OutOfValueSpace("xs:negativeInteger".into()))
+        );
+    }
+
     #[test]
     fn test_range_to_json() {
         let expected = r#"{"valueType":"xs:int","min":1,"max":10}"#;
         let actual = Range::Int(RangeInner {
             min: Some(1),
             max: Some(10),
+            ..Default::default()
         });
         let actual = serde_json::to_string(&actual).unwrap();
         assert_eq!(expected, actual);
@@ -151,9 +329,10 @@ mod tests {
         let expected = Range::Int(RangeInner {
             min: Some(1),
             max: Some(10),
+            ..Default::default()
         });
         let actual = r#"{"valueType":"xs:int","min":1,"max":10}"#;
-        let actual = serde_json::from_str(&actual).unwrap();
+        let actual = serde_json::from_str(actual).unwrap();
         assert_eq!(expected, actual);
     }
 
@@ -163,6 +342,7 @@ mod tests {
         let actual = Range::Int(RangeInner {
             min: Some(1),
             max: Some(10),
+            ..Default::default()
         });
         let actual = actual.to_json_metamodel().unwrap();
 
@@ -171,10 +351,11 @@ mod tests {
 
     #[test]
     fn test_range_to_metamodel_camel_case() {
-        let expected = r#"{"valueType":"xs:anyUri"}"#;
+        let expected = r#"{"valueType":"xs:anyURI"}"#;
         let actual = Range::AnyURI(RangeInner {
             min: Some("https://example.com".into()),
             max: Some("https://example.com".into()),
+            ..Default::default()
         });
         let actual = actual.to_json_metamodel().unwrap();
 
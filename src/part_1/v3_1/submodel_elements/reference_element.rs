@@ -1,10 +1,14 @@
 use crate::part_1::ToJsonMetamodel;
 use crate::part_1::v3_1::reference::Reference;
+use crate::part_1::v3_1::submodel_elements::SubmodelElementFields;
 use serde::{Deserialize, Serialize};
 
 // ToJsonMetadata implemented from upper enum.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize, Default)]
 pub struct ReferenceElement {
+    #[serde(flatten)]
+    pub submodel_element_fields: SubmodelElementFields,
+
     /// External reference to an external object or entity or a logical reference
     /// to another element within the same or another Asset Administration Shell
     /// (i.e. a model reference to a Referable)
@@ -16,6 +20,6 @@ impl ToJsonMetamodel for ReferenceElement {
     type Error = ();
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
-        Ok(format!(r#"{{"modelType":"ReferenceElement"}}"#))
+        Ok(r#"{"modelType":"ReferenceElement"}"#.to_string())
     }
 }
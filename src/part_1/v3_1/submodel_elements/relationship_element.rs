@@ -10,10 +10,10 @@ pub struct RelationshipElement {
     pub submodel_element_fields: SubmodelElementFields,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    first: Option<Reference>,
+    pub first: Option<Reference>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    second: Option<Reference>,
+    pub second: Option<Reference>,
 }
 
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
@@ -80,7 +80,7 @@ impl ToJsonMetamodel for RelationshipElement {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         serde_json::to_string::<RelationshipElementMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+            .map_err(MetamodelError::FailedSerialisation)
     }
 }
 
@@ -89,7 +89,7 @@ impl ToJsonMetamodel for AnnotatedRelationshipElement {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         serde_json::to_string::<AnnotatedRelationshipElementMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+            .map_err(MetamodelError::FailedSerialisation)
     }
 }
 
@@ -103,6 +103,7 @@ mod tests {
     use crate::part_1::v3_1::reference::ReferenceInner;
     use super::*;
     #[test]
+    #[allow(deprecated)]
     fn test_relationship_element_to_metamodel() {
         // expect to remove "first" & "second" fields.
         let expected = r#"{"idShort":"relationship_test"}"#;
@@ -1,16 +1,20 @@
 use crate::part_1::ToJsonMetamodel;
-use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::submodel_elements::{SubmodelElement, SubmodelElementFields};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SubmodelElementCollection {
-    value: Option<Vec<SubmodelElement>>,
+    #[serde(flatten)]
+    pub submodel_element_fields: SubmodelElementFields,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Vec<SubmodelElement>>,
 }
 
 impl ToJsonMetamodel for SubmodelElementCollection {
     type Error = ();
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
-        Ok(format!(r#"{{"modelType":"SubmodelElementCollection"}}"#))
+        Ok(r#"{"modelType":"SubmodelElementCollection"}"#.to_string())
     }
 }
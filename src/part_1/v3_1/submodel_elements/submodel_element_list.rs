@@ -1,6 +1,8 @@
 use crate::part_1::v3_1::primitives::data_type_def_xs::DataTypeXSDef;
 use crate::part_1::v3_1::reference::Reference;
-use crate::part_1::v3_1::submodel_elements::{AasSubmodelElements, SubmodelElement};
+use crate::part_1::v3_1::submodel_elements::{
+    AasSubmodelElements, SubmodelElement, SubmodelElementFields,
+};
 use crate::part_1::{MetamodelError, ToJsonMetamodel};
 use serde::{Deserialize, Serialize};
 
@@ -11,14 +13,18 @@ use serde::{Deserialize, Serialize};
 /// A submodel element list is an ordered list of submodel elements.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SubmodelElementList {
+    #[serde(flatten)]
+    pub submodel_element_fields: SubmodelElementFields,
+
     /// Defines whether order in list is relevant. If orderRelevant = false, the list represents a set or a bag.
     #[serde(rename = "orderRelevant")]
     #[serde(default = "ordering_default")]
-    is_order_relevant: bool,
+    pub is_order_relevant: bool,
 
     /// Semantic ID which the submodel elements contained in the list match
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "semanticIdListElement")]
-    semantic_id_list_element: Option<Reference>,
+    pub semantic_id_list_element: Option<Reference>,
 
 
     // Question: can value, type_value_list_element be merged into an enum?
@@ -26,25 +32,31 @@ pub struct SubmodelElementList {
     // newtype or something for type safety.
 
     /// Submodel elements contained in the list
-    value: Option<Vec<SubmodelElement>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Vec<SubmodelElement>>,
 
     /// The submodel element type of the submodel elements contained in the list
     #[serde(rename = "typeValueListElement")]
-    type_value_list_element: AasSubmodelElements,
+    pub type_value_list_element: AasSubmodelElements,
 
     /// The value type of the submodel element contained in the list
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "valueTypeListElement")]
-    value_type_list_element: Option<DataTypeXSDef>,
+    pub value_type_list_element: Option<DataTypeXSDef>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SubmodelElementListMeta {
+    #[serde(flatten)]
+    pub submodel_element_fields: SubmodelElementFields,
+
     /// Defines whether order in list is relevant. If orderRelevant = false, the list represents a set or a bag.
     #[serde(rename = "orderRelevant")]
     #[serde(default = "ordering_default")]
     is_order_relevant: bool,
 
     /// Semantic ID which the submodel elements contained in the list match
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "semanticIdListElement")]
     semantic_id_list_element: Option<Reference>,
 
@@ -53,6 +65,7 @@ pub struct SubmodelElementListMeta {
     type_value_list_element: AasSubmodelElements,
 
     /// The value type of the submodel element contained in the list
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "valueTypeListElement")]
     value_type_list_element: Option<DataTypeXSDef>,
 }
@@ -64,6 +77,7 @@ fn ordering_default() -> bool {
 impl From<SubmodelElementList> for SubmodelElementListMeta {
     fn from(element: SubmodelElementList) -> Self {
         Self {
+            submodel_element_fields: element.submodel_element_fields,
             is_order_relevant: element.is_order_relevant,
             semantic_id_list_element: element.semantic_id_list_element,
             type_value_list_element: element.type_value_list_element,
@@ -83,6 +97,6 @@ impl ToJsonMetamodel for SubmodelElementList {
 
     fn to_json_metamodel(&self) -> Result<String, Self::Error> {
         serde_json::to_string::<SubmodelElementListMeta>(&self.into())
-            .map_err(|e| MetamodelError::FailedSerialisation(e))
+            .map_err(MetamodelError::FailedSerialisation)
     }
 }
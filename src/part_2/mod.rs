@@ -0,0 +1,3 @@
+/// type definitions for version 3.1 of the AAS Specification part 2.
+/// <https://industrialdigitaltwin.io/aas-specifications/IDTA-01002/v3.1.1/index.html>
+pub mod v3_1;
@@ -0,0 +1,599 @@
+//! HTTP client for Part 2 repositories, enabled by the `client` feature.
+//!
+//! The [`Client`] talks to the AAS Repository (`/shells`) and Submodel Repository (`/submodels`)
+//! API of a server and returns the typed values of this crate. Identifiers are base64url encoded,
+//! collections are read page by page through [`Pages`].
+
+use crate::part_1::v3_1::core::{AssetAdministrationShell, AssetInformation, Submodel};
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::{ModelReference, Reference};
+use crate::part_2::v3_1::{Message, PagedResult, ServiceResult, encode_identifier};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    /// The repository refused the request, with the messages of its `Result`.
+    #[error("The repository answered {status}: {}", texts(.messages))]
+    Service { status: u16, messages: Vec<Message> },
+
+    #[error(transparent)]
+    Transport(#[from] Box<ureq::Transport>),
+
+    #[error("Can't serialize the request body: {0}")]
+    Serialization(serde_json::Error),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl ClientError {
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Service { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+}
+
+fn texts(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(|message| message.text.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Client of a Part 2 repository, i.e. `Client::new("http://localhost:8081")`.
+#[derive(Clone, Debug)]
+pub struct Client {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// `base_url` is the URL the `/shells` and `/submodels` routes are relative to.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_agent(base_url, ureq::Agent::new())
+    }
+
+    /// Uses a configured agent, i.e. with timeouts, a proxy or custom TLS settings.
+    pub fn with_agent(base_url: impl Into<String>, agent: ureq::Agent) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            agent,
+        }
+    }
+
+    pub fn shells(&self) -> Pages<'_, AssetAdministrationShell> {
+        Pages::new(self, "/shells".to_string())
+    }
+
+    pub fn shell(&self, id: &str) -> Result<AssetAdministrationShell, ClientError> {
+        self.get(&shell_path(id))
+    }
+
+    pub fn create_shell(
+        &self,
+        shell: &AssetAdministrationShell,
+    ) -> Result<AssetAdministrationShell, ClientError> {
+        self.post("/shells", shell)
+    }
+
+    /// Replaces the shell with the same id.
+    pub fn put_shell(&self, shell: &AssetAdministrationShell) -> Result<(), ClientError> {
+        self.put(&shell_path(shell.identifiable.id.as_ref()), shell)
+    }
+
+    pub fn delete_shell(&self, id: &str) -> Result<(), ClientError> {
+        self.delete(&shell_path(id))
+    }
+
+    pub fn asset_information(&self, shell_id: &str) -> Result<AssetInformation, ClientError> {
+        self.get(&format!("{}/asset-information", shell_path(shell_id)))
+    }
+
+    pub fn submodel_references(&self, shell_id: &str) -> Pages<'_, ModelReference> {
+        Pages::new(self, format!("{}/submodel-refs", shell_path(shell_id)))
+    }
+
+    pub fn submodels(&self) -> Pages<'_, Submodel> {
+        Pages::new(self, "/submodels".to_string())
+    }
+
+    pub fn submodel(&self, id: &str) -> Result<Submodel, ClientError> {
+        self.get(&submodel_path(id))
+    }
+
+    /// The submodel without its elements.
+    pub fn submodel_metadata(&self, id: &str) -> Result<Submodel, ClientError> {
+        self.get(&format!("{}/$metadata", submodel_path(id)))
+    }
+
+    /// The ValueOnly serialization of all elements, keyed by idShort.
+    pub fn submodel_value(&self, id: &str) -> Result<Value, ClientError> {
+        self.get(&format!("{}/$value", submodel_path(id)))
+    }
+
+    pub fn submodel_reference(&self, id: &str) -> Result<Reference, ClientError> {
+        self.get(&format!("{}/$reference", submodel_path(id)))
+    }
+
+    pub fn create_submodel(&self, submodel: &Submodel) -> Result<Submodel, ClientError> {
+        self.post("/submodels", submodel)
+    }
+
+    /// Replaces the submodel with the same id.
+    pub fn put_submodel(&self, submodel: &Submodel) -> Result<(), ClientError> {
+        self.put(&submodel_path(submodel.id().as_ref()), submodel)
+    }
+
+    /// Sets the values of the given elements, see [`submodel_value`](Self::submodel_value).
+    pub fn patch_submodel_value(&self, id: &str, value: &Value) -> Result<(), ClientError> {
+        self.patch(&format!("{}/$value", submodel_path(id)), value)
+    }
+
+    pub fn delete_submodel(&self, id: &str) -> Result<(), ClientError> {
+        self.delete(&submodel_path(id))
+    }
+
+    /// The top level elements of a submodel.
+    pub fn submodel_elements(&self, submodel_id: &str) -> Pages<'_, SubmodelElement> {
+        Pages::new(self, elements_path(submodel_id))
+    }
+
+    /// The element at an idShort path like `Maintenance.Counters[0]`.
+    pub fn submodel_element(
+        &self,
+        submodel_id: &str,
+        path: &str,
+    ) -> Result<SubmodelElement, ClientError> {
+        self.get(&element_path(submodel_id, path))
+    }
+
+    /// The element without its value and children.
+    pub fn submodel_element_metadata(
+        &self,
+        submodel_id: &str,
+        path: &str,
+    ) -> Result<SubmodelElement, ClientError> {
+        self.get(&format!("{}/$metadata", element_path(submodel_id, path)))
+    }
+
+    pub fn submodel_element_value(
+        &self,
+        submodel_id: &str,
+        path: &str,
+    ) -> Result<Value, ClientError> {
+        self.get(&format!("{}/$value", element_path(submodel_id, path)))
+    }
+
+    /// Adds `element` to the collection, list or entity at `parent`, or to the submodel itself
+    /// for an empty `parent`.
+    pub fn create_submodel_element(
+        &self,
+        submodel_id: &str,
+        parent: &str,
+        element: &SubmodelElement,
+    ) -> Result<SubmodelElement, ClientError> {
+        match parent {
+            "" => self.post(&elements_path(submodel_id), element),
+            parent => self.post(&element_path(submodel_id, parent), element),
+        }
+    }
+
+    pub fn put_submodel_element(
+        &self,
+        submodel_id: &str,
+        path: &str,
+        element: &SubmodelElement,
+    ) -> Result<(), ClientError> {
+        self.put(&element_path(submodel_id, path), element)
+    }
+
+    pub fn patch_submodel_element_value(
+        &self,
+        submodel_id: &str,
+        path: &str,
+        value: &Value,
+    ) -> Result<(), ClientError> {
+        self.patch(
+            &format!("{}/$value", element_path(submodel_id, path)),
+            value,
+        )
+    }
+
+    pub fn delete_submodel_element(
+        &self,
+        submodel_id: &str,
+        path: &str,
+    ) -> Result<(), ClientError> {
+        self.delete(&element_path(submodel_id, path))
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        typed(self.send("GET", path, &[], None)?)
+    }
+
+    fn post<T: Serialize, R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<R, ClientError> {
+        typed(self.send("POST", path, &[], Some(&to_json(body)?))?)
+    }
+
+    fn put<T: Serialize>(&self, path: &str, body: &T) -> Result<(), ClientError> {
+        self.send("PUT", path, &[], Some(&to_json(body)?))
+            .map(|_| ())
+    }
+
+    fn patch(&self, path: &str, body: &Value) -> Result<(), ClientError> {
+        self.send("PATCH", path, &[], Some(body)).map(|_| ())
+    }
+
+    fn delete(&self, path: &str) -> Result<(), ClientError> {
+        self.send("DELETE", path, &[], None).map(|_| ())
+    }
+
+    /// The JSON body of a successful response, `None` if it is empty.
+    fn send(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> Result<Option<Value>, ClientError> {
+        let request = self
+            .agent
+            .request(method, &format!("{}{path}", self.base_url))
+            .query_pairs(query.iter().copied());
+        let response = match body {
+            Some(body) => request
+                .set("Content-Type", "application/json")
+                .send_string(&body.to_string()),
+            None => request.call(),
+        };
+
+        match response {
+            Ok(response) => {
+                let text = response
+                    .into_string()
+                    .map_err(|err| ClientError::InvalidResponse(err.to_string()))?;
+                match text.trim() {
+                    "" => Ok(None),
+                    text => serde_json::from_str(text)
+                        .map(Some)
+                        .map_err(|err| ClientError::InvalidResponse(err.to_string())),
+                }
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                let result = response
+                    .into_string()
+                    .ok()
+                    .and_then(|text| serde_json::from_str::<ServiceResult>(&text).ok())
+                    .unwrap_or_default();
+                Err(ClientError::Service {
+                    status,
+                    messages: result.messages,
+                })
+            }
+            Err(ureq::Error::Transport(transport)) => Err(Box::new(transport).into()),
+        }
+    }
+}
+
+/// Iterator over all items of a collection, requesting the next page when the current one is
+/// used up. Stops after the first error.
+pub struct Pages<'a, T> {
+    client: &'a Client,
+    path: String,
+    limit: Option<usize>,
+    cursor: Option<String>,
+    page: VecDeque<Value>,
+    done: bool,
+    item: PhantomData<T>,
+}
+
+impl<'a, T> Pages<'a, T> {
+    fn new(client: &'a Client, path: String) -> Self {
+        Self {
+            client,
+            path,
+            limit: None,
+            cursor: None,
+            page: VecDeque::new(),
+            done: false,
+            item: PhantomData,
+        }
+    }
+
+    /// Requests pages of at most `limit` items instead of the size the server chooses.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn next_page(&mut self) -> Result<(), ClientError> {
+        let limit = self.limit.map(|limit| limit.to_string());
+        let query = [
+            ("limit", limit.as_deref()),
+            ("cursor", self.cursor.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect::<Vec<_>>();
+
+        let page: PagedResult<Value> = typed(self.client.send("GET", &self.path, &query, None)?)?;
+
+        // a server repeating itself would be requested forever
+        if let Some(cursor) = &page.paging_metadata.cursor {
+            if page.result.is_empty() {
+                return Err(ClientError::InvalidResponse(format!(
+                    "An empty page continues at {cursor}"
+                )));
+            }
+            if self.cursor.as_ref() == Some(cursor) {
+                return Err(ClientError::InvalidResponse(format!(
+                    "The cursor {cursor} did not advance"
+                )));
+            }
+        }
+
+        self.cursor = page.paging_metadata.cursor;
+        self.done = self.cursor.is_none();
+        self.page.extend(page.result);
+        Ok(())
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for Pages<'_, T> {
+    type Item = Result<T, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.page.is_empty() && !self.done {
+            if let Err(err) = self.next_page() {
+                self.done = true;
+                return Some(Err(err));
+            }
+        }
+
+        self.page.pop_front().map(|item| typed(Some(item)))
+    }
+}
+
+fn typed<T: DeserializeOwned>(body: Option<Value>) -> Result<T, ClientError> {
+    let body = body.ok_or_else(|| ClientError::InvalidResponse("The body is empty".to_string()))?;
+    serde_json::from_value(body).map_err(|err| ClientError::InvalidResponse(err.to_string()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, ClientError> {
+    serde_json::to_value(value).map_err(ClientError::Serialization)
+}
+
+fn shell_path(id: &str) -> String {
+    format!("/shells/{}", encode_identifier(id))
+}
+
+fn submodel_path(id: &str) -> String {
+    format!("/submodels/{}", encode_identifier(id))
+}
+
+fn elements_path(submodel_id: &str) -> String {
+    format!("{}/submodel-elements", submodel_path(submodel_id))
+}
+
+/// idShort paths are percent encoded, keeping the separating dots.
+fn element_path(submodel_id: &str, path: &str) -> String {
+    let encoded = path
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect::<String>();
+
+    format!("{}/{encoded}", elements_path(submodel_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+    /// Answers one connection per response in order, recording request line and body.
+    fn mock(responses: Vec<(u16, Value)>) -> (Client, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = Client::new(format!("http://{}/api/", listener.local_addr().unwrap()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((_, value)) = header
+                        .split_once(':')
+                        .filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+                recorded.lock().unwrap().push((
+                    request_line.trim().to_string(),
+                    String::from_utf8(request_body).unwrap(),
+                ));
+
+                let body = match body {
+                    Value::Null => String::new(),
+                    body => body.to_string(),
+                };
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (client, requests)
+    }
+
+    fn submodel(id: &str) -> Value {
+        json!({ "modelType": "Submodel", "id": id })
+    }
+
+    #[test]
+    fn paging() {
+        let (client, requests) = mock(vec![
+            (
+                200,
+                json!({ "paging_metadata": { "cursor": "Mg" }, "result": [submodel("urn:sm:1"), submodel("urn:sm:2")] }),
+            ),
+            (
+                200,
+                json!({ "paging_metadata": {}, "result": [submodel("urn:sm:3")] }),
+            ),
+        ]);
+
+        let ids = client
+            .submodels()
+            .limit(2)
+            .map(|submodel| submodel.unwrap().id().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["urn:sm:1", "urn:sm:2", "urn:sm:3"]);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0, "GET /api/submodels?limit=2 HTTP/1.1");
+        assert_eq!(
+            requests[1].0,
+            "GET /api/submodels?limit=2&cursor=Mg HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn stalled_paging() {
+        let page = |cursor: &str, result: Vec<Value>| {
+            (
+                200,
+                json!({ "paging_metadata": { "cursor": cursor }, "result": result }),
+            )
+        };
+        let (client, _) = mock(vec![
+            page("Mg", vec![submodel("urn:sm:1")]),
+            page("Mg", vec![submodel("urn:sm:2")]),
+        ]);
+
+        let mut submodels = client.submodels();
+        assert!(submodels.next().unwrap().is_ok());
+        assert!(matches!(
+            submodels.next(),
+            Some(Err(ClientError::InvalidResponse(_)))
+        ));
+        assert!(submodels.next().is_none());
+
+        let (client, _) = mock(vec![page("Mg", vec![])]);
+        let mut submodels = client.submodels();
+        assert!(matches!(
+            submodels.next(),
+            Some(Err(ClientError::InvalidResponse(_)))
+        ));
+        assert!(submodels.next().is_none());
+    }
+
+    #[test]
+    fn elements_and_values() {
+        let (client, requests) = mock(vec![
+            (
+                200,
+                json!({ "modelType": "Property", "idShort": "Speed", "valueType": "xs:int", "value": 5 }),
+            ),
+            (200, json!(5)),
+            (204, Value::Null),
+        ]);
+
+        let element = client
+            .submodel_element("urn:sm:1", "Motor.Counters[0]")
+            .unwrap();
+        let SubmodelElement::Property(property) = element else {
+            panic!("Expected a property, got {element:?}");
+        };
+        assert_eq!(property.get::<i32>(), Ok(Some(5)));
+
+        assert_eq!(
+            client.submodel_element_value("urn:sm:1", "Speed").unwrap(),
+            json!(5)
+        );
+        client
+            .patch_submodel_element_value("urn:sm:1", "Speed", &json!(6))
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let submodel = encode_identifier("urn:sm:1");
+        assert_eq!(
+            requests[0].0,
+            format!(
+                "GET /api/submodels/{submodel}/submodel-elements/Motor.Counters%5B0%5D HTTP/1.1"
+            )
+        );
+        assert_eq!(
+            requests[2],
+            (
+                format!("PATCH /api/submodels/{submodel}/submodel-elements/Speed/$value HTTP/1.1"),
+                "6".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn service_errors() {
+        let (client, _) = mock(vec![
+            (
+                404,
+                json!({ "messages": [{ "messageType": "Error", "text": "No submodel urn:sm:9", "code": "404" }] }),
+            ),
+            (500, json!("not a result")),
+        ]);
+
+        let err = client.submodel("urn:sm:9").unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(
+            err.to_string(),
+            "The repository answered 404: No submodel urn:sm:9"
+        );
+
+        let err = client.delete_submodel("urn:sm:9").unwrap_err();
+        assert!(
+            matches!(err, ClientError::Service { status: 500, ref messages } if messages.is_empty())
+        );
+    }
+}
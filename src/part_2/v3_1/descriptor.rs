@@ -0,0 +1,422 @@
+use crate::part_1::v3_1::Environment;
+use crate::part_1::v3_1::Key;
+use crate::part_1::v3_1::attributes::administrative_information::AdministrativeInformation;
+use crate::part_1::v3_1::attributes::extension::HasExtensions;
+use crate::part_1::v3_1::attributes::semantics::HasSemantics;
+use crate::part_1::v3_1::core::{AssetAdministrationShell, AssetKind, SpecificAssetId, Submodel};
+use crate::part_1::v3_1::primitives::{Identifier, MultiLanguageNameType};
+use crate::part_2::v3_1::encode_identifier;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// Registry entry of a shell, telling clients where its API is served.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AssetAdministrationShellDescriptor {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<MultiLanguageNameType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "displayName")]
+    pub display_name: Option<MultiLanguageNameType>,
+
+    #[serde(flatten)]
+    pub extensions: HasExtensions,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub administration: Option<AdministrativeInformation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "assetKind")]
+    pub asset_kind: Option<AssetKind>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "assetType")]
+    pub asset_type: Option<Identifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<Vec<Endpoint>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "globalAssetId")]
+    pub global_asset_id: Option<Identifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "idShort")]
+    pub id_short: Option<Identifier>,
+
+    pub id: Identifier,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "specificAssetIds")]
+    pub specific_asset_ids: Option<Vec<SpecificAssetId>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "submodelDescriptors")]
+    pub submodel_descriptors: Option<Vec<SubmodelDescriptor>>,
+}
+
+/// Registry entry of a submodel, telling clients where its API is served.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SubmodelDescriptor {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<MultiLanguageNameType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "displayName")]
+    pub display_name: Option<MultiLanguageNameType>,
+
+    #[serde(flatten)]
+    pub extensions: HasExtensions,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub administration: Option<AdministrativeInformation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "idShort")]
+    pub id_short: Option<Identifier>,
+
+    pub id: Identifier,
+
+    #[serde(flatten)]
+    pub semantics: HasSemantics,
+
+    pub endpoints: Vec<Endpoint>,
+}
+
+/// An interface of a descriptor, i.e. `SUBMODEL-3.0` served at `https://example.com/submodels/…`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Endpoint {
+    /// Name and version of the interface, i.e. `AAS-3.0` or `SUBMODEL-3.0`.
+    pub interface: String,
+
+    #[serde(rename = "protocolInformation")]
+    pub protocol_information: ProtocolInformation,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
+pub struct ProtocolInformation {
+    pub href: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "endpointProtocol")]
+    pub endpoint_protocol: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "endpointProtocolVersion")]
+    pub endpoint_protocol_version: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subprotocol: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "subprotocolBody")]
+    pub subprotocol_body: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "subprotocolBodyEncoding")]
+    pub subprotocol_body_encoding: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "securityAttributes")]
+    pub security_attributes: Option<Vec<SecurityAttributeObject>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, EnumString, Display)]
+pub enum SecurityType {
+    #[serde(rename = "NONE")]
+    #[strum(serialize = "NONE")]
+    None,
+
+    #[serde(rename = "RFC_TLSA")]
+    #[strum(serialize = "RFC_TLSA")]
+    RfcTlsa,
+
+    #[serde(rename = "W3C_DID")]
+    #[strum(serialize = "W3C_DID")]
+    W3cDid,
+}
+
+/// Security requirement of an endpoint, i.e. the TLSA record of its certificate.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SecurityAttributeObject {
+    #[serde(rename = "type")]
+    pub security_type: SecurityType,
+
+    pub key: String,
+
+    pub value: String,
+}
+
+/// Where the generated descriptors point to. `{id}` in a template is replaced by the base64url
+/// encoded identifier.
+#[derive(Clone, PartialEq, Debug)]
+pub struct EndpointTemplate {
+    shell: String,
+    submodel: String,
+}
+
+impl EndpointTemplate {
+    /// The repository layout below `base_url`, i.e. `{base_url}/shells/{id}`.
+    pub fn new(base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            shell: format!("{base_url}/shells/{{id}}"),
+            submodel: format!("{base_url}/submodels/{{id}}"),
+        }
+    }
+
+    /// i.e. `https://example.com/aas/{id}`
+    pub fn with_shell(mut self, template: impl Into<String>) -> Self {
+        self.shell = template.into();
+        self
+    }
+
+    /// i.e. `https://example.com/sm/{id}`
+    pub fn with_submodel(mut self, template: impl Into<String>) -> Self {
+        self.submodel = template.into();
+        self
+    }
+
+    pub fn shell_endpoint(&self, id: &str) -> Endpoint {
+        endpoint("AAS-3.0", &self.shell, id)
+    }
+
+    pub fn submodel_endpoint(&self, id: &str) -> Endpoint {
+        endpoint("SUBMODEL-3.0", &self.submodel, id)
+    }
+}
+
+fn endpoint(interface: &str, template: &str, id: &str) -> Endpoint {
+    Endpoint {
+        interface: interface.to_string(),
+        protocol_information: ProtocolInformation {
+            href: template.replace("{id}", &encode_identifier(id)),
+            endpoint_protocol: Some("HTTP".to_string()),
+            endpoint_protocol_version: Some(vec!["1.1".to_string()]),
+            ..Default::default()
+        },
+    }
+}
+
+impl AssetAdministrationShellDescriptor {
+    /// The descriptor of `shell` with the descriptors of its submodels. Submodels missing in
+    /// `environment` are described by their id only.
+    pub fn new(
+        shell: &AssetAdministrationShell,
+        environment: &Environment,
+        endpoints: &EndpointTemplate,
+    ) -> Self {
+        let referable = &shell.identifiable.referable;
+        let asset = shell.asset_information.inner();
+        let submodel_descriptors = shell
+            .submodels
+            .iter()
+            .flatten()
+            .filter_map(|reference| match reference.keys.as_slice() {
+                [Key::Submodel(id)] => Some(match environment.submodel(id) {
+                    Some(submodel) => SubmodelDescriptor::new(submodel, endpoints),
+                    None => SubmodelDescriptor {
+                        description: None,
+                        display_name: None,
+                        extensions: Default::default(),
+                        administration: None,
+                        id_short: None,
+                        id: id.clone().try_into().ok()?,
+                        semantics: Default::default(),
+                        endpoints: vec![endpoints.submodel_endpoint(id)],
+                    },
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            description: referable.description.clone(),
+            display_name: referable.display_name.clone(),
+            extensions: referable.extensions.clone(),
+            administration: shell.identifiable.administrative_information.clone(),
+            asset_kind: Some(shell.asset_information.asset_kind()),
+            asset_type: asset.asset_type.clone(),
+            endpoints: Some(vec![
+                endpoints.shell_endpoint(shell.identifiable.id.as_ref()),
+            ]),
+            global_asset_id: asset.global_asset_id.clone(),
+            id_short: referable.id_short.clone(),
+            id: shell.identifiable.id.clone(),
+            specific_asset_ids: asset.specific_asset_ids.clone(),
+            submodel_descriptors: Some(submodel_descriptors).filter(|found| !found.is_empty()),
+        }
+    }
+}
+
+impl SubmodelDescriptor {
+    pub fn new(submodel: &Submodel, endpoints: &EndpointTemplate) -> Self {
+        let referable = &submodel.identifiable.referable;
+
+        Self {
+            description: referable.description.clone(),
+            display_name: referable.display_name.clone(),
+            extensions: referable.extensions.clone(),
+            administration: submodel.identifiable.administrative_information.clone(),
+            id_short: referable.id_short.clone(),
+            id: submodel.id().clone(),
+            semantics: submodel.semantics.clone(),
+            endpoints: vec![endpoints.submodel_endpoint(submodel.id().as_ref())],
+        }
+    }
+}
+
+/// Descriptors of all shells of `environment`, to register them in an AAS registry.
+pub fn shell_descriptors(
+    environment: &Environment,
+    endpoints: &EndpointTemplate,
+) -> Vec<AssetAdministrationShellDescriptor> {
+    environment
+        .asset_administration_shells
+        .iter()
+        .flatten()
+        .map(|shell| AssetAdministrationShellDescriptor::new(shell, environment, endpoints))
+        .collect()
+}
+
+/// Descriptors of all submodels of `environment`, to register them in a submodel registry.
+pub fn submodel_descriptors(
+    environment: &Environment,
+    endpoints: &EndpointTemplate,
+) -> Vec<SubmodelDescriptor> {
+    environment
+        .submodels
+        .iter()
+        .flatten()
+        .map(|submodel| SubmodelDescriptor::new(submodel, endpoints))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn shell_descriptor_round_trip() {
+        let json = json!({
+            "idShort": "Robot",
+            "id": "https://example.com/aas/robot",
+            "assetKind": "Instance",
+            "globalAssetId": "https://example.com/asset/robot",
+            "endpoints": [{
+                "interface": "AAS-3.0",
+                "protocolInformation": {
+                    "href": "https://example.com/shells/aHR0cHM6Ly9leGFtcGxlLmNvbS9hYXMvcm9ib3Q",
+                    "endpointProtocol": "HTTP",
+                    "endpointProtocolVersion": ["1.1"],
+                    "securityAttributes": [{ "type": "NONE", "key": "NONE", "value": "NONE" }]
+                }
+            }],
+            "submodelDescriptors": [{
+                "id": "https://example.com/sm/nameplate",
+                "semanticId": {
+                    "type": "ExternalReference",
+                    "keys": [{ "type": "GlobalReference", "value": "https://admin-shell.io/idta/nameplate/3/0/Nameplate" }]
+                },
+                "endpoints": [{
+                    "interface": "SUBMODEL-3.0",
+                    "protocolInformation": { "href": "https://example.com/submodels/nameplate" }
+                }]
+            }]
+        });
+
+        let descriptor: AssetAdministrationShellDescriptor =
+            serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(descriptor.asset_kind, Some(AssetKind::Instance));
+
+        let endpoint = &descriptor.endpoints.as_ref().unwrap()[0];
+        assert_eq!(
+            endpoint
+                .protocol_information
+                .security_attributes
+                .as_ref()
+                .unwrap()[0]
+                .security_type,
+            SecurityType::None
+        );
+
+        let submodel = &descriptor.submodel_descriptors.as_ref().unwrap()[0];
+        assert!(submodel.semantics.semantic_id.is_some());
+
+        assert_eq!(serde_json::to_value(&descriptor).unwrap(), json);
+    }
+
+    #[test]
+    fn generate_from_environment() {
+        let environment: Environment = serde_json::from_value(json!({
+            "assetAdministrationShells": [{
+                "id": "https://example.com/aas/robot",
+                "idShort": "Robot",
+                "assetInformation": {
+                    "assetKind": "Type",
+                    "globalAssetId": "https://example.com/asset/robot",
+                    "assetType": "Robot",
+                    "specificAssetIds": [{ "name": "serialNumber", "value": "4711" }]
+                },
+                "submodels": [
+                    { "type": "ModelReference", "keys": [{ "type": "Submodel", "value": "https://example.com/sm/nameplate" }] },
+                    { "type": "ModelReference", "keys": [{ "type": "Submodel", "value": "https://example.com/sm/remote" }] }
+                ]
+            }],
+            "submodels": [{
+                "id": "https://example.com/sm/nameplate",
+                "idShort": "Nameplate",
+                "semanticId": {
+                    "type": "ExternalReference",
+                    "keys": [{ "type": "GlobalReference", "value": "https://admin-shell.io/idta/nameplate/3/0/Nameplate" }]
+                }
+            }]
+        }))
+        .unwrap();
+        let endpoints = EndpointTemplate::new("https://example.com/api/")
+            .with_submodel("https://sm.example.com/{id}");
+
+        let descriptors = shell_descriptors(&environment, &endpoints);
+        let descriptor = serde_json::to_value(&descriptors[0]).unwrap();
+        assert_eq!(descriptor["idShort"], "Robot");
+        assert_eq!(descriptor["assetKind"], "Type");
+        assert_eq!(descriptor["assetType"], "Robot");
+        assert_eq!(
+            descriptor["globalAssetId"],
+            "https://example.com/asset/robot"
+        );
+        assert_eq!(descriptor["specificAssetIds"][0]["value"], "4711");
+        assert_eq!(
+            descriptor["endpoints"][0]["protocolInformation"]["href"],
+            format!(
+                "https://example.com/api/shells/{}",
+                encode_identifier("https://example.com/aas/robot")
+            )
+        );
+
+        let submodels = &descriptor["submodelDescriptors"];
+        assert_eq!(submodels[0]["idShort"], "Nameplate");
+        assert_eq!(
+            submodels[0]["semanticId"]["keys"][0]["value"],
+            "https://admin-shell.io/idta/nameplate/3/0/Nameplate"
+        );
+        assert_eq!(submodels[0]["endpoints"][0]["interface"], "SUBMODEL-3.0");
+        assert_eq!(
+            submodels[1],
+            json!({
+                "id": "https://example.com/sm/remote",
+                "endpoints": [{
+                    "interface": "SUBMODEL-3.0",
+                    "protocolInformation": {
+                        "href": format!("https://sm.example.com/{}", encode_identifier("https://example.com/sm/remote")),
+                        "endpointProtocol": "HTTP",
+                        "endpointProtocolVersion": ["1.1"]
+                    }
+                }]
+            })
+        );
+
+        assert_eq!(submodel_descriptors(&environment, &endpoints).len(), 1);
+    }
+}
@@ -0,0 +1,235 @@
+//! Lookup of shells by the identifiers of their asset, as done by the Part 2 discovery service.
+//!
+//! The asset of a shell is linked by its `globalAssetId` and its `specificAssetIds`, i.e. the serial
+//! number printed on the device. A specific asset id with an `externalSubjectId` is only visible
+//! to that subject.
+
+use crate::part_1::v3_1::core::AssetAdministrationShell;
+use crate::part_1::v3_1::{Environment, ExternalReference};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name of the link standing for the `globalAssetId` of a shell.
+pub const GLOBAL_ASSET_ID: &str = "globalAssetId";
+
+/// A name/value pair identifying an asset, i.e. `serialNumber` = `4711`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct AssetLink {
+    pub name: String,
+    pub value: String,
+}
+
+impl AssetLink {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn global_asset_id(value: impl Into<String>) -> Self {
+        Self::new(GLOBAL_ASSET_ID, value)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Entry {
+    shell: usize,
+    subject: Option<ExternalReference>,
+}
+
+impl Entry {
+    fn is_visible_to(&self, subject: Option<&ExternalReference>) -> bool {
+        match &self.subject {
+            None => true,
+            Some(own) => subject == Some(own),
+        }
+    }
+}
+
+/// Index of the asset links of all shells of an environment.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct AssetDiscovery {
+    shell_ids: Vec<String>,
+    entries: HashMap<AssetLink, Vec<Entry>>,
+}
+
+impl AssetDiscovery {
+    pub fn new(environment: &Environment) -> Self {
+        let mut discovery = Self::default();
+        environment
+            .asset_administration_shells
+            .iter()
+            .flatten()
+            .for_each(|shell| discovery.insert(shell));
+        discovery
+    }
+
+    /// Adds the links of `shell`, shells are found in the order they are inserted.
+    pub fn insert(&mut self, shell: &AssetAdministrationShell) {
+        let index = self.shell_ids.len();
+        self.shell_ids.push(shell.identifiable.id.to_string());
+
+        for (link, subject) in links(shell) {
+            self.entries.entry(link).or_default().push(Entry {
+                shell: index,
+                subject,
+            });
+        }
+    }
+
+    /// Ids of the shells matching all `links`, as seen by `subject`. Without links all shells
+    /// match.
+    pub fn shell_ids(&self, links: &[AssetLink], subject: Option<&ExternalReference>) -> Vec<&str> {
+        let matches = |index: usize, link: &AssetLink| {
+            self.entries.get(link).is_some_and(|entries| {
+                entries
+                    .iter()
+                    .any(|entry| entry.shell == index && entry.is_visible_to(subject))
+            })
+        };
+
+        self.shell_ids
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| links.iter().all(|link| matches(*index, link)))
+            .map(|(_, id)| id.as_str())
+            .collect()
+    }
+
+    /// The links of the shell with the given id, as seen by `subject`.
+    pub fn asset_links(
+        &self,
+        shell_id: &str,
+        subject: Option<&ExternalReference>,
+    ) -> Vec<AssetLink> {
+        let Some(index) = self.shell_ids.iter().position(|id| id == shell_id) else {
+            return Vec::new();
+        };
+
+        let mut found = self
+            .entries
+            .iter()
+            .filter(|(_, entries)| {
+                entries
+                    .iter()
+                    .any(|entry| entry.shell == index && entry.is_visible_to(subject))
+            })
+            .map(|(link, _)| link.clone())
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| (&a.name, &a.value).cmp(&(&b.name, &b.value)));
+        found
+    }
+}
+
+fn links(shell: &AssetAdministrationShell) -> Vec<(AssetLink, Option<ExternalReference>)> {
+    let asset = shell.asset_information.inner();
+    let global = asset
+        .global_asset_id
+        .iter()
+        .map(|id| (AssetLink::global_asset_id(id.to_string()), None));
+    let specific = asset.specific_asset_ids.iter().flatten().map(|id| {
+        (
+            AssetLink::new(id.name.to_string(), id.value.to_string()),
+            id.external_subject_id.clone(),
+        )
+    });
+
+    global.chain(specific).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_1::v3_1::{Key, ReferenceInner};
+    use serde_json::json;
+
+    fn environment() -> Environment {
+        let shell = |id: &str, serial: &str| {
+            json!({
+                "id": id,
+                "assetInformation": {
+                    "assetKind": "Instance",
+                    "globalAssetId": format!("{id}/asset"),
+                    "specificAssetIds": [
+                        { "name": "manufacturer", "value": "ACME" },
+                        { "name": "serialNumber", "value": serial },
+                        {
+                            "name": "internalNumber",
+                            "value": format!("int-{serial}"),
+                            "externalSubjectId": {
+                                "type": "ExternalReference",
+                                "keys": [{ "type": "GlobalReference", "value": "https://acme.example.com" }]
+                            }
+                        }
+                    ]
+                }
+            })
+        };
+
+        serde_json::from_value(json!({
+            "assetAdministrationShells": [
+                shell("https://example.com/aas/1", "4711"),
+                shell("https://example.com/aas/2", "4712")
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn find_by_serial_number() {
+        let discovery = AssetDiscovery::new(&environment());
+
+        let links = [
+            AssetLink::new("manufacturer", "ACME"),
+            AssetLink::new("serialNumber", "4712"),
+        ];
+        assert_eq!(
+            discovery.shell_ids(&links, None),
+            ["https://example.com/aas/2"]
+        );
+
+        let links = [AssetLink::global_asset_id(
+            "https://example.com/aas/1/asset",
+        )];
+        assert_eq!(
+            discovery.shell_ids(&links, None),
+            ["https://example.com/aas/1"]
+        );
+
+        assert_eq!(discovery.shell_ids(&[], None).len(), 2);
+        assert!(
+            discovery
+                .shell_ids(&[AssetLink::new("serialNumber", "0000")], None)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn external_subject_visibility() {
+        let discovery = AssetDiscovery::new(&environment());
+        let acme = ExternalReference::new(ReferenceInner::new(Key::GlobalReference(
+            "https://acme.example.com".to_string(),
+        )));
+        let other = ExternalReference::new(ReferenceInner::new(Key::GlobalReference(
+            "https://other.example.com".to_string(),
+        )));
+        let links = [AssetLink::new("internalNumber", "int-4711")];
+
+        assert!(discovery.shell_ids(&links, None).is_empty());
+        assert!(discovery.shell_ids(&links, Some(&other)).is_empty());
+        assert_eq!(
+            discovery.shell_ids(&links, Some(&acme)),
+            ["https://example.com/aas/1"]
+        );
+
+        let public = discovery.asset_links("https://example.com/aas/1", None);
+        assert_eq!(public.len(), 3);
+        assert_eq!(
+            discovery
+                .asset_links("https://example.com/aas/1", Some(&acme))
+                .len(),
+            4
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, EnumString, Display, Default,
+)]
+pub enum MessageType {
+    #[default]
+    Undefined,
+    Info,
+    Warning,
+    Error,
+    Exception,
+}
+
+/// A message of a service result, i.e. the reason of a failed operation.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
+pub struct Message {
+    #[serde(rename = "messageType")]
+    pub message_type: MessageType,
+
+    pub text: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    #[serde(rename = "correlationId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl Message {
+    pub fn new(message_type: MessageType, text: impl Into<String>) -> Self {
+        Self {
+            message_type,
+            text: text.into(),
+            timestamp: Some(Utc::now()),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(MessageType::Error, text)
+    }
+}
@@ -0,0 +1,15 @@
+#[cfg(feature = "client")]
+pub mod client;
+mod descriptor;
+pub use descriptor::*;
+pub mod discovery;
+mod message;
+pub use message::*;
+mod operation;
+pub use operation::*;
+pub mod query;
+mod result;
+pub use result::*;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod value_only;
@@ -0,0 +1,74 @@
+use crate::part_1::v3_1::submodel_elements::OperationVariable;
+use crate::part_2::v3_1::Message;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// Lifecycle of an asynchronously executed operation.
+///
+/// `Initiated` -> `Running` -> one of `Completed`, `Canceled`, `Failed` or `Timeout`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, EnumString, Display)]
+pub enum ExecutionState {
+    Initiated,
+    Running,
+    Completed,
+    Canceled,
+    Failed,
+    Timeout,
+}
+
+impl ExecutionState {
+    /// No further state changes happen.
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, ExecutionState::Initiated | ExecutionState::Running)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
+pub struct OperationResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+
+    #[serde(rename = "executionState")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_state: Option<ExecutionState>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+
+    #[serde(rename = "outputArguments")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_arguments: Option<Vec<OperationVariable>>,
+
+    #[serde(rename = "inoutputArguments")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inoutput_arguments: Option<Vec<OperationVariable>>,
+}
+
+impl OperationResult {
+    /// A result without arguments, i.e. while the operation is still running.
+    pub fn with_state(execution_state: ExecutionState) -> Self {
+        Self {
+            execution_state: Some(execution_state),
+            success: execution_state
+                .is_finished()
+                .then_some(execution_state == ExecutionState::Completed),
+            ..Default::default()
+        }
+    }
+}
+
+/// Arguments of an operation invocation.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
+pub struct OperationRequest {
+    #[serde(rename = "inputArguments")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_arguments: Option<Vec<OperationVariable>>,
+
+    #[serde(rename = "inoutputArguments")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inoutput_arguments: Option<Vec<OperationVariable>>,
+
+    /// A `xs:duration`, i.e. `PT30S`.
+    #[serde(rename = "clientTimeoutDuration")]
+    pub client_timeout_duration: String,
+}
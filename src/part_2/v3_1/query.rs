@@ -0,0 +1,1068 @@
+//! The AAS Query Language: conditions on shells, submodels and submodel elements in its JSON
+//! grammar, evaluated against an [`Environment`].
+//!
+//! ```json
+//! { "Query": { "$condition": { "$and": [
+//!     { "$eq": [{ "$field": "$sm#idShort" }, { "$strVal": "TechnicalData" }] },
+//!     { "$gt": [{ "$field": "$sme.MaxPower#value" }, { "$numVal": 5000 }] }
+//! ] } } }
+//! ```
+//!
+//! A field stands for all values it reaches, a comparison holds if it holds for any of them.
+//! Values of properties are compared by their `valueType`, so `xs:int` `5` and `xs:double` `5.0`
+//! are equal while the string `"5"` is neither greater nor smaller than a number.
+//!
+//! `$aas` is the shell, `$sm` one of its submodels and `$sme` the elements of that submodel.
+//! `$sme.<idShortPath>` is the element at the path, `[]` in the path is any element of a list.
+
+use crate::part_1::v3_1::core::{AssetAdministrationShell, Submodel, parse_id_short_path};
+use crate::part_1::v3_1::primitives::DataXsdError;
+use crate::part_1::v3_1::primitives::data_type_def_xs::DataXsd;
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::{Environment, Key, Reference};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum QueryError {
+    #[error("Malformed query: {0}")]
+    Syntax(String),
+
+    #[error("Unknown operator {0}")]
+    UnknownOperator(String),
+
+    #[error("Unknown field {0}")]
+    UnknownField(String),
+
+    #[error("Invalid regular expression {0}")]
+    Regex(String),
+}
+
+/// What matching objects are returned as, set by `$select`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Select {
+    #[default]
+    All,
+    Id,
+}
+
+#[derive(Clone, Debug)]
+pub struct Query {
+    pub select: Select,
+    pub condition: Expression,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+pub enum Comparison {
+    #[strum(serialize = "$eq")]
+    Eq,
+    #[strum(serialize = "$ne")]
+    Ne,
+    #[strum(serialize = "$gt")]
+    Gt,
+    #[strum(serialize = "$ge")]
+    Ge,
+    #[strum(serialize = "$lt")]
+    Lt,
+    #[strum(serialize = "$le")]
+    Le,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+pub enum StringOperator {
+    #[strum(serialize = "$contains")]
+    Contains,
+    #[strum(serialize = "$starts-with")]
+    StartsWith,
+    #[strum(serialize = "$ends-with")]
+    EndsWith,
+}
+
+#[derive(Clone, Debug)]
+pub enum Expression {
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+    Not(Box<Expression>),
+    Compare(Comparison, Operand, Operand),
+    String(StringOperator, Operand, Operand),
+    /// The pattern may match anywhere, anchor it with `^…$` to match the whole value.
+    Regex(Operand, Regex),
+    Boolean(bool),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Operand {
+    Field(Field),
+    Value(QueryValue),
+    Cast(Cast, Box<Operand>),
+    /// `$attribute` of the subject asking, only known when checking access rules.
+    Attribute(AttributeItem),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum GlobalAttribute {
+    LocalNow,
+    UtcNow,
+    ClientNow,
+    /// `true` for subjects without claims.
+    Anonymous,
+}
+
+/// An attribute of the subject, i.e. `{ "CLAIM": "email" }` taken from its access token.
+#[derive(Clone, PartialEq, Debug)]
+pub enum AttributeItem {
+    Claim(String),
+    Global(GlobalAttribute),
+    /// The value of the element a model reference like `(Submodel)urn:x, (Property)Customers`
+    /// refers to.
+    Reference(Vec<Key>),
+}
+
+/// Resolves `$attribute` operands, implemented by the subject of access rules.
+pub trait AttributeResolver {
+    /// The values of `attribute`, empty if it is unknown.
+    fn attribute(
+        &self,
+        attribute: &AttributeItem,
+        environment: Option<&Environment>,
+    ) -> Vec<QueryValue>;
+}
+
+/// `$strCast`, `$numCast`, `$boolCast` and `$dateTimeCast`, values which can't be converted are
+/// left out.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cast {
+    String,
+    Number,
+    Boolean,
+    DateTime,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum QueryValue {
+    String(String),
+    Number(BigDecimal),
+    Boolean(bool),
+    DateTime(DateTime<Utc>),
+}
+
+/// A field like `$sm#semanticId` or `$sme.Nameplate.ProductName#value`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Field {
+    text: String,
+    scope: Scope,
+    attribute: Attribute,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Scope {
+    Shell,
+    Submodel,
+    /// Without path the element itself when querying elements, otherwise all elements.
+    /// The idShort path may contain `[]` for any element of a list, see [`Submodel::elements_at`].
+    Element(Option<String>),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Index {
+    Any,
+    At(usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Attribute {
+    Id,
+    IdShort,
+    /// `semanticId` is short for `semanticId.keys[0].value`.
+    SemanticId,
+    SemanticIdType,
+    SemanticIdKey(Index, KeyPart),
+    Value,
+    ValueType,
+    Language,
+    AssetKind,
+    AssetType,
+    GlobalAssetId,
+    SpecificAssetId(Index, SpecificAssetIdPart),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum KeyPart {
+    Type,
+    Value,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SpecificAssetIdPart {
+    Name,
+    Value,
+}
+
+/// An element matching a query, with the submodel it belongs to.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ElementMatch<'a> {
+    pub submodel: &'a Submodel,
+    pub id_short_path: String,
+    pub element: &'a SubmodelElement,
+}
+
+impl Query {
+    /// Accepts `{ "Query": { "$select": …, "$condition": … } }`, the inner object or a bare
+    /// condition.
+    pub fn from_json(json: &Value) -> Result<Self, QueryError> {
+        let query = json.get("Query").unwrap_or(json);
+        let Some(condition) = query.get("$condition") else {
+            return Ok(Self {
+                select: Select::All,
+                condition: Expression::from_json(query)?,
+            });
+        };
+
+        let select = match query.get("$select") {
+            None => Select::All,
+            Some(Value::String(select)) if select == "id" => Select::Id,
+            Some(select) => return Err(syntax(format!("$select {select}"))),
+        };
+
+        Ok(Self {
+            select,
+            condition: Expression::from_json(condition)?,
+        })
+    }
+
+    pub fn shells<'a>(&self, environment: &'a Environment) -> Vec<&'a AssetAdministrationShell> {
+        environment
+            .asset_administration_shells
+            .iter()
+            .flatten()
+            .filter(|shell| {
+                let submodels = shell_submodels(shell, environment);
+                let context = |submodel| Context {
+                    environment: Some(environment),
+                    shell: Some(shell),
+                    submodel,
+                    ..Context::default()
+                };
+
+                match submodels.is_empty() {
+                    true => self.condition.evaluate(&context(None)),
+                    false => submodels
+                        .into_iter()
+                        .any(|submodel| self.condition.evaluate(&context(Some(submodel)))),
+                }
+            })
+            .collect()
+    }
+
+    pub fn submodels<'a>(&self, environment: &'a Environment) -> Vec<&'a Submodel> {
+        environment
+            .submodels
+            .iter()
+            .flatten()
+            .filter(|submodel| {
+                self.condition.evaluate(&Context {
+                    environment: Some(environment),
+                    submodel: Some(submodel),
+                    ..Context::default()
+                })
+            })
+            .collect()
+    }
+
+    /// All elements, nested ones included, for which the condition holds with `$sme` being the
+    /// element.
+    pub fn elements<'a>(&self, environment: &'a Environment) -> Vec<ElementMatch<'a>> {
+        let mut found = Vec::new();
+
+        for submodel in environment.submodels.iter().flatten() {
+            for (id_short_path, element) in submodel.elements_by_path() {
+                let context = Context {
+                    environment: Some(environment),
+                    submodel: Some(submodel),
+                    element: Some(element),
+                    ..Context::default()
+                };
+
+                if self.condition.evaluate(&context) {
+                    found.push(ElementMatch {
+                        submodel,
+                        id_short_path,
+                        element,
+                    });
+                }
+            }
+        }
+
+        found
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let json = serde_json::from_str(s).map_err(|err| syntax(err.to_string()))?;
+        Self::from_json(&json)
+    }
+}
+
+impl Expression {
+    pub fn from_json(json: &Value) -> Result<Self, QueryError> {
+        let (operator, argument) = single_entry(json)?;
+
+        if let Ok(comparison) = Comparison::from_str(operator) {
+            let [left, right] = operands(operator, argument)?;
+            return Ok(Expression::Compare(comparison, left, right));
+        }
+        if let Ok(string) = StringOperator::from_str(operator) {
+            let [left, right] = operands(operator, argument)?;
+            return Ok(Expression::String(string, left, right));
+        }
+
+        Ok(match operator {
+            "$and" | "$or" => {
+                let expressions = argument
+                    .as_array()
+                    .filter(|expressions| !expressions.is_empty())
+                    .ok_or_else(|| syntax(format!("{operator} needs a list of conditions")))?
+                    .iter()
+                    .map(Expression::from_json)
+                    .collect::<Result<_, _>>()?;
+
+                match operator {
+                    "$and" => Expression::And(expressions),
+                    _ => Expression::Or(expressions),
+                }
+            }
+            "$not" => Expression::Not(Box::new(Expression::from_json(argument)?)),
+            "$regex" => {
+                let [value, pattern] = operands(operator, argument)?;
+                let Operand::Value(QueryValue::String(pattern)) = pattern else {
+                    return Err(syntax("$regex needs a $strVal pattern".to_string()));
+                };
+                let regex = Regex::new(&pattern).map_err(|_| QueryError::Regex(pattern))?;
+
+                Expression::Regex(value, regex)
+            }
+            "$boolean" => Expression::Boolean(
+                argument
+                    .as_bool()
+                    .ok_or_else(|| syntax("$boolean needs true or false".to_string()))?,
+            ),
+            _ => return Err(QueryError::UnknownOperator(operator.to_string())),
+        })
+    }
+
+    pub(crate) fn evaluate(&self, context: &Context) -> bool {
+        match self {
+            Expression::And(expressions) => expressions.iter().all(|e| e.evaluate(context)),
+            Expression::Or(expressions) => expressions.iter().any(|e| e.evaluate(context)),
+            Expression::Not(expression) => !expression.evaluate(context),
+            Expression::Compare(comparison, left, right) => {
+                any_pair(left, right, context, |left, right| {
+                    left.compare(right)
+                        .is_some_and(|ordering| comparison.holds(ordering))
+                })
+            }
+            Expression::String(operator, left, right) => {
+                any_pair(left, right, context, |left, right| match (left, right) {
+                    (QueryValue::String(left), QueryValue::String(right)) => match operator {
+                        StringOperator::Contains => left.contains(right.as_str()),
+                        StringOperator::StartsWith => left.starts_with(right.as_str()),
+                        StringOperator::EndsWith => left.ends_with(right.as_str()),
+                    },
+                    _ => false,
+                })
+            }
+            Expression::Regex(value, regex) => {
+                value.values(context).iter().any(|value| match value {
+                    QueryValue::String(value) => regex.is_match(value),
+                    _ => false,
+                })
+            }
+            Expression::Boolean(value) => *value,
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Eq => ordering.is_eq(),
+            Comparison::Ne => ordering.is_ne(),
+            Comparison::Gt => ordering.is_gt(),
+            Comparison::Ge => ordering.is_ge(),
+            Comparison::Lt => ordering.is_lt(),
+            Comparison::Le => ordering.is_le(),
+        }
+    }
+}
+
+impl Operand {
+    pub fn from_json(json: &Value) -> Result<Self, QueryError> {
+        let (operator, argument) = single_entry(json)?;
+        let invalid = || syntax(format!("{operator} can't be {argument}"));
+        let cast = |cast| Ok(Operand::Cast(cast, Box::new(Operand::from_json(argument)?)));
+
+        match operator {
+            "$field" => Ok(Operand::Field(
+                argument.as_str().ok_or_else(invalid)?.parse()?,
+            )),
+            "$strVal" => Ok(Operand::Value(QueryValue::String(
+                argument.as_str().ok_or_else(invalid)?.to_string(),
+            ))),
+            "$numVal" => match argument {
+                Value::Number(number) => Ok(Operand::Value(QueryValue::Number(
+                    BigDecimal::from_str(&number.to_string()).map_err(|_| invalid())?,
+                ))),
+                _ => Err(invalid()),
+            },
+            "$boolean" => Ok(Operand::Value(QueryValue::Boolean(
+                argument.as_bool().ok_or_else(invalid)?,
+            ))),
+            "$dateTimeVal" => argument
+                .as_str()
+                .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+                .map(|date_time| Operand::Value(QueryValue::DateTime(date_time.to_utc())))
+                .ok_or_else(invalid),
+            "$strCast" => cast(Cast::String),
+            "$numCast" => cast(Cast::Number),
+            "$boolCast" => cast(Cast::Boolean),
+            "$dateTimeCast" => cast(Cast::DateTime),
+            "$attribute" => Ok(Operand::Attribute(AttributeItem::from_json(argument)?)),
+            _ => Err(QueryError::UnknownOperator(operator.to_string())),
+        }
+    }
+
+    fn values(&self, context: &Context) -> Vec<QueryValue> {
+        match self {
+            Operand::Field(field) => field.values(context),
+            Operand::Value(value) => vec![value.clone()],
+            Operand::Attribute(attribute) => context
+                .subject
+                .map(|subject| subject.attribute(attribute, context.environment))
+                .unwrap_or_default(),
+            Operand::Cast(cast, operand) => operand
+                .values(context)
+                .into_iter()
+                .filter_map(|value| value.cast(*cast))
+                .collect(),
+        }
+    }
+}
+
+impl AttributeItem {
+    pub fn from_json(json: &Value) -> Result<Self, QueryError> {
+        let invalid = || QueryError::Syntax(format!("{json} is not an attribute"));
+        let (kind, value) = single_entry(json).map_err(|_| invalid())?;
+        let value = value.as_str().ok_or_else(invalid)?;
+
+        match kind {
+            "CLAIM" => Ok(AttributeItem::Claim(value.to_string())),
+            "GLOBAL" => GlobalAttribute::from_str(value)
+                .map(AttributeItem::Global)
+                .map_err(|_| invalid()),
+            "REFERENCE" => parse_keys(value)
+                .map(AttributeItem::Reference)
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl QueryValue {
+    /// Values of different types are not comparable.
+    pub fn compare(&self, other: &QueryValue) -> Option<Ordering> {
+        match (self, other) {
+            (QueryValue::String(a), QueryValue::String(b)) => Some(a.cmp(b)),
+            (QueryValue::Number(a), QueryValue::Number(b)) => Some(a.cmp(b)),
+            (QueryValue::Boolean(a), QueryValue::Boolean(b)) => Some(a.cmp(b)),
+            (QueryValue::DateTime(a), QueryValue::DateTime(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    fn cast(self, cast: Cast) -> Option<QueryValue> {
+        Some(match (cast, self) {
+            (Cast::String, QueryValue::String(value)) => QueryValue::String(value),
+            (Cast::String, QueryValue::Number(value)) => QueryValue::String(value.to_string()),
+            (Cast::String, QueryValue::Boolean(value)) => QueryValue::String(value.to_string()),
+            (Cast::String, QueryValue::DateTime(value)) => QueryValue::String(value.to_rfc3339()),
+            (Cast::Number, QueryValue::String(value)) => {
+                QueryValue::Number(BigDecimal::from_str(value.trim()).ok()?)
+            }
+            (Cast::Number, QueryValue::Boolean(value)) => {
+                QueryValue::Number(BigDecimal::from(value as u8))
+            }
+            (Cast::Number, value @ QueryValue::Number(_)) => value,
+            (Cast::Boolean, QueryValue::String(value)) => match value.trim() {
+                "true" | "1" => QueryValue::Boolean(true),
+                "false" | "0" => QueryValue::Boolean(false),
+                _ => return None,
+            },
+            (Cast::Boolean, QueryValue::Number(value)) => {
+                QueryValue::Boolean(value != BigDecimal::from(0))
+            }
+            (Cast::Boolean, value @ QueryValue::Boolean(_)) => value,
+            (Cast::DateTime, QueryValue::String(value)) => {
+                QueryValue::DateTime(DateTime::parse_from_rfc3339(value.trim()).ok()?.to_utc())
+            }
+            (Cast::DateTime, value @ QueryValue::DateTime(_)) => value,
+            _ => return None,
+        })
+    }
+
+    /// Numbers, booleans and date times keep their type, everything else is compared by its
+    /// lexical representation.
+    fn from_data_xsd(value: &DataXsd) -> Option<QueryValue> {
+        if let Ok(number) = value.get::<BigDecimal>() {
+            return number.map(QueryValue::Number);
+        }
+        if let Ok(boolean) = value.get::<bool>() {
+            return boolean.map(QueryValue::Boolean);
+        }
+        if let Ok(date_time) = value.get::<DateTime<Utc>>() {
+            return date_time.map(QueryValue::DateTime);
+        }
+
+        value.lexical().map(QueryValue::String)
+    }
+}
+
+impl FromStr for Field {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || QueryError::UnknownField(s.to_string());
+        let (scope, attribute) = s.split_once('#').ok_or_else(unknown)?;
+
+        let scope = match scope {
+            "$aas" => Scope::Shell,
+            "$sm" => Scope::Submodel,
+            "$sme" => Scope::Element(None),
+            _ => Scope::Element(Some(
+                scope
+                    .strip_prefix("$sme.")
+                    .filter(|path| parse_id_short_path(path).is_some())
+                    .ok_or_else(unknown)?
+                    .to_string(),
+            )),
+        };
+        let attribute = parse_attribute(attribute).ok_or_else(unknown)?;
+
+        let allowed = match (&scope, attribute) {
+            (_, Attribute::IdShort) => true,
+            (Scope::Shell, attribute) => matches!(
+                attribute,
+                Attribute::Id
+                    | Attribute::AssetKind
+                    | Attribute::AssetType
+                    | Attribute::GlobalAssetId
+                    | Attribute::SpecificAssetId(..)
+            ),
+            (Scope::Submodel, attribute) => matches!(
+                attribute,
+                Attribute::Id
+                    | Attribute::SemanticId
+                    | Attribute::SemanticIdType
+                    | Attribute::SemanticIdKey(..)
+            ),
+            (Scope::Element(_), attribute) => matches!(
+                attribute,
+                Attribute::SemanticId
+                    | Attribute::SemanticIdType
+                    | Attribute::SemanticIdKey(..)
+                    | Attribute::Value
+                    | Attribute::ValueType
+                    | Attribute::Language
+            ),
+        };
+
+        match allowed {
+            true => Ok(Self {
+                text: s.to_string(),
+                scope,
+                attribute,
+            }),
+            false => Err(unknown()),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Field {
+    fn values(&self, context: &Context) -> Vec<QueryValue> {
+        let strings = |values: Vec<String>| values.into_iter().map(QueryValue::String).collect();
+
+        match &self.scope {
+            Scope::Shell => context
+                .shell
+                .map(|shell| strings(shell_attribute(shell, self.attribute)))
+                .unwrap_or_default(),
+            Scope::Submodel => context
+                .submodel
+                .map(|submodel| {
+                    strings(match self.attribute {
+                        Attribute::Id => vec![submodel.id().to_string()],
+                        Attribute::IdShort => submodel
+                            .identifiable
+                            .referable
+                            .id_short
+                            .iter()
+                            .map(|id| id.to_string())
+                            .collect(),
+                        attribute => semantic_id_attribute(
+                            submodel.semantics.semantic_id.as_ref(),
+                            attribute,
+                        ),
+                    })
+                })
+                .unwrap_or_default(),
+            Scope::Element(path) => {
+                let elements = match (path, context.element, context.submodel) {
+                    (None, Some(element), _) => vec![element],
+                    (None, None, Some(submodel)) => submodel
+                        .elements_by_path()
+                        .into_iter()
+                        .map(|(_, element)| element)
+                        .collect(),
+                    (Some(path), _, Some(submodel)) => submodel.elements_at(path),
+                    (_, _, None) => Vec::new(),
+                };
+
+                elements
+                    .into_iter()
+                    .flat_map(|element| element_attribute(element, self.attribute))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// What `$aas`, `$sm`, `$sme` and `$attribute` refer to while evaluating.
+#[derive(Default)]
+pub(crate) struct Context<'a> {
+    pub(crate) environment: Option<&'a Environment>,
+    pub(crate) shell: Option<&'a AssetAdministrationShell>,
+    pub(crate) submodel: Option<&'a Submodel>,
+    pub(crate) element: Option<&'a SubmodelElement>,
+    pub(crate) subject: Option<&'a dyn AttributeResolver>,
+}
+
+/// The keys of a model reference like `(Submodel)urn:x, (Property)Power`.
+pub(crate) fn parse_keys(text: &str) -> Option<Vec<Key>> {
+    Reference::from_str(&format!("[ModelRef]{}", text.trim()))
+        .ok()
+        .map(|reference| reference.into_inner().keys)
+}
+
+fn syntax(message: String) -> QueryError {
+    QueryError::Syntax(message)
+}
+
+/// Operators and operands are objects with a single entry, i.e. `{ "$strVal": "a" }`.
+fn single_entry(json: &Value) -> Result<(&str, &Value), QueryError> {
+    match json.as_object().map(Map::iter).as_mut() {
+        Some(entries) => match (entries.next(), entries.next()) {
+            (Some((key, value)), None) => Ok((key.as_str(), value)),
+            _ => Err(syntax(format!("{json} has to have exactly one entry"))),
+        },
+        None => Err(syntax(format!("{json} is not an object"))),
+    }
+}
+
+fn operands(operator: &str, json: &Value) -> Result<[Operand; 2], QueryError> {
+    match json.as_array().map(Vec::as_slice) {
+        Some([left, right]) => Ok([Operand::from_json(left)?, Operand::from_json(right)?]),
+        _ => Err(syntax(format!("{operator} needs two operands"))),
+    }
+}
+
+fn any_pair(
+    left: &Operand,
+    right: &Operand,
+    context: &Context,
+    matches: impl Fn(&QueryValue, &QueryValue) -> bool,
+) -> bool {
+    let (left, right) = (left.values(context), right.values(context));
+
+    left.iter()
+        .any(|left| right.iter().any(|right| matches(left, right)))
+}
+
+fn parse_index(index: &str) -> Option<Index> {
+    match index {
+        "" => Some(Index::Any),
+        index => index.parse().ok().map(Index::At),
+    }
+}
+
+/// Splits `keys[0].value` into `keys`, the index and `value`.
+fn indexed<'a>(attribute: &'a str, name: &str) -> Option<(Index, &'a str)> {
+    let (index, part) = attribute
+        .strip_prefix(name)?
+        .strip_prefix('[')?
+        .split_once("].")?;
+    Some((parse_index(index)?, part))
+}
+
+fn parse_attribute(attribute: &str) -> Option<Attribute> {
+    Some(match attribute {
+        "id" => Attribute::Id,
+        "idShort" => Attribute::IdShort,
+        "semanticId" => Attribute::SemanticId,
+        "semanticId.type" => Attribute::SemanticIdType,
+        "value" => Attribute::Value,
+        "valueType" => Attribute::ValueType,
+        "language" => Attribute::Language,
+        "assetInformation.assetKind" => Attribute::AssetKind,
+        "assetInformation.assetType" => Attribute::AssetType,
+        "assetInformation.globalAssetId" => Attribute::GlobalAssetId,
+        attribute => {
+            if let Some((index, part)) = attribute
+                .strip_prefix("semanticId.")
+                .and_then(|keys| indexed(keys, "keys"))
+            {
+                let part = match part {
+                    "type" => KeyPart::Type,
+                    "value" => KeyPart::Value,
+                    _ => return None,
+                };
+                Attribute::SemanticIdKey(index, part)
+            } else {
+                let (index, part) = attribute
+                    .strip_prefix("assetInformation.")
+                    .and_then(|ids| indexed(ids, "specificAssetIds"))?;
+                let part = match part {
+                    "name" => SpecificAssetIdPart::Name,
+                    "value" => SpecificAssetIdPart::Value,
+                    _ => return None,
+                };
+                Attribute::SpecificAssetId(index, part)
+            }
+        }
+    })
+}
+
+fn select<T>(items: &[T], index: Index) -> &[T] {
+    match index {
+        Index::Any => items,
+        Index::At(at) => items.get(at..=at).unwrap_or_default(),
+    }
+}
+
+fn shell_attribute(shell: &AssetAdministrationShell, attribute: Attribute) -> Vec<String> {
+    let asset = shell.asset_information.inner();
+
+    match attribute {
+        Attribute::Id => vec![shell.identifiable.id.to_string()],
+        Attribute::IdShort => shell
+            .identifiable
+            .referable
+            .id_short
+            .iter()
+            .map(|id_short| id_short.to_string())
+            .collect(),
+        Attribute::AssetKind => vec![shell.asset_information.asset_kind().to_string()],
+        Attribute::AssetType => asset.asset_type.iter().map(|id| id.to_string()).collect(),
+        Attribute::GlobalAssetId => asset
+            .global_asset_id
+            .iter()
+            .map(|id| id.to_string())
+            .collect(),
+        Attribute::SpecificAssetId(index, part) => select(
+            asset.specific_asset_ids.as_deref().unwrap_or_default(),
+            index,
+        )
+        .iter()
+        .map(|id| match part {
+            SpecificAssetIdPart::Name => id.name.to_string(),
+            SpecificAssetIdPart::Value => id.value.to_string(),
+        })
+        .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn semantic_id_attribute(semantic_id: Option<&Reference>, attribute: Attribute) -> Vec<String> {
+    let Some(semantic_id) = semantic_id else {
+        return Vec::new();
+    };
+    let keys = |index, part| {
+        select(semantic_id.keys(), index)
+            .iter()
+            .map(|key: &Key| match part {
+                KeyPart::Type => key.key_type().to_string(),
+                KeyPart::Value => key.value().to_string(),
+            })
+            .collect()
+    };
+
+    match attribute {
+        Attribute::SemanticId => keys(Index::At(0), KeyPart::Value),
+        Attribute::SemanticIdType => vec![semantic_id.reference_type().to_string()],
+        Attribute::SemanticIdKey(index, part) => keys(index, part),
+        _ => Vec::new(),
+    }
+}
+
+fn element_attribute(element: &SubmodelElement, attribute: Attribute) -> Vec<QueryValue> {
+    let strings = |values: Vec<String>| values.into_iter().map(QueryValue::String).collect();
+
+    match attribute {
+        Attribute::IdShort => strings(element.id_short().map(str::to_string).into_iter().collect()),
+        // a range with bounds outside its value type has no value to compare
+        Attribute::Value => element_value(element).unwrap_or_default(),
+        Attribute::ValueType => strings(value_type(element).into_iter().collect()),
+        Attribute::Language => match element {
+            SubmodelElement::MultiLanguageProperty(property) => strings(
+                property
+                    .value
+                    .iter()
+                    .flatten()
+                    .map(|text| text.language.to_string())
+                    .collect(),
+            ),
+            _ => Vec::new(),
+        },
+        attribute => strings(semantic_id_attribute(
+            element.semantics().semantic_id.as_ref(),
+            attribute,
+        )),
+    }
+}
+
+/// The values of properties and the bounds of ranges are typed by their `valueType`.
+/// Fails for ranges with a bound outside the value space of their `valueType`.
+pub(crate) fn element_value(element: &SubmodelElement) -> Result<Vec<QueryValue>, DataXsdError> {
+    Ok(match element {
+        SubmodelElement::Property(property) => QueryValue::from_data_xsd(&property.value)
+            .into_iter()
+            .collect(),
+        SubmodelElement::Range(range) => {
+            let (min, max) = range.bounds()?;
+
+            [min, max]
+                .iter()
+                .filter_map(QueryValue::from_data_xsd)
+                .collect()
+        }
+        SubmodelElement::MultiLanguageProperty(property) => property
+            .value
+            .iter()
+            .flatten()
+            .map(|text| QueryValue::String(text.text.clone()))
+            .collect(),
+        SubmodelElement::File(file) => file
+            .value
+            .iter()
+            .map(|path| QueryValue::String(path.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// The `valueType` of properties and ranges, i.e. `xs:double`.
+fn value_type(element: &SubmodelElement) -> Option<String> {
+    match element {
+        SubmodelElement::Property(property) => Some(property.value.to_string()),
+        SubmodelElement::Range(range) => Some(range.to_string()),
+        _ => None,
+    }
+}
+
+fn shell_submodels<'a>(
+    shell: &AssetAdministrationShell,
+    environment: &'a Environment,
+) -> Vec<&'a Submodel> {
+    shell
+        .submodels
+        .iter()
+        .flatten()
+        .filter_map(|reference| match reference.keys.as_slice() {
+            [Key::Submodel(id)] => environment.submodel(id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn environment() -> Environment {
+        let motor = |id: &str, power: Value, value_type: &str| {
+            json!({
+                "modelType": "Submodel",
+                "id": format!("{id}/technical-data"),
+                "idShort": "TechnicalData",
+                "semanticId": {
+                    "type": "ExternalReference",
+                    "keys": [{ "type": "GlobalReference", "value": "https://admin-shell.io/ZVEI/TechnicalData/Submodel/1/2" }]
+                },
+                "submodelElements": [
+                    { "modelType": "Property", "idShort": "MaxPower", "valueType": value_type, "value": power },
+                    {
+                        "modelType": "MultiLanguageProperty",
+                        "idShort": "ProductName",
+                        "value": [
+                            { "language": "en", "text": format!("Motor {id}") },
+                            { "language": "de", "text": format!("Antrieb {id}") }
+                        ]
+                    },
+                    {
+                        "modelType": "SubmodelElementList",
+                        "idShort": "Ratings",
+                        "typeValueListElement": "Property",
+                        "value": [
+                            { "modelType": "Property", "valueType": "xs:string", "value": "IP55" },
+                            { "modelType": "Property", "valueType": "xs:string", "value": "IE3" }
+                        ]
+                    }
+                ]
+            })
+        };
+        let shell = |id: &str| {
+            json!({
+                "id": id,
+                "assetInformation": { "assetKind": "Instance", "globalAssetId": format!("{id}/asset") },
+                "submodels": [{
+                    "type": "ModelReference",
+                    "keys": [{ "type": "Submodel", "value": format!("{id}/technical-data") }]
+                }]
+            })
+        };
+
+        serde_json::from_value(json!({
+            "assetAdministrationShells": [shell("m1"), shell("m2"), shell("m3")],
+            "submodels": [
+                motor("m1", json!(7500), "xs:int"),
+                motor("m2", json!(2.2e3), "xs:double"),
+                motor("m3", json!("5500"), "xs:string")
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn ids(shells: Vec<&AssetAdministrationShell>) -> Vec<String> {
+        shells
+            .iter()
+            .map(|shell| shell.identifiable.id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn parse() {
+        let query: Query =
+            r#"{ "Query": { "$select": "id", "$condition": { "$boolean": true } } }"#
+                .parse()
+                .unwrap();
+        assert_eq!(query.select, Select::Id);
+
+        let bare = Query::from_json(&json!({ "$not": { "$boolean": false } })).unwrap();
+        assert_eq!(bare.select, Select::All);
+
+        let error = |query: Value| Query::from_json(&query).unwrap_err();
+        assert_eq!(
+            error(json!({ "$like": [] })),
+            QueryError::UnknownOperator("$like".to_string())
+        );
+        assert_eq!(
+            error(json!({ "$eq": [{ "$field": "$sm#valueType" }, { "$strVal": "a" }] })),
+            QueryError::UnknownField("$sm#valueType".to_string())
+        );
+        assert_eq!(
+            error(json!({ "$eq": [{ "$field": "$sme.A..B#value" }, { "$strVal": "a" }] })),
+            QueryError::UnknownField("$sme.A..B#value".to_string())
+        );
+        assert_eq!(
+            error(json!({ "$regex": [{ "$field": "$sme#value" }, { "$strVal": "(" }] })),
+            QueryError::Regex("(".to_string())
+        );
+        assert!(matches!(
+            error(json!({ "$eq": [{ "$strVal": "a" }] })),
+            QueryError::Syntax(_)
+        ));
+    }
+
+    #[test]
+    fn motors_above_5_kw() {
+        let environment = environment();
+        let query = Query::from_json(&json!({ "Query": { "$condition": { "$and": [
+            { "$eq": [
+                { "$field": "$sm#semanticId" },
+                { "$strVal": "https://admin-shell.io/ZVEI/TechnicalData/Submodel/1/2" }
+            ] },
+            { "$gt": [{ "$field": "$sme.MaxPower#value" }, { "$numVal": 5000 }] }
+        ] } } }))
+        .unwrap();
+
+        // m3 states its power as xs:string, which doesn't compare to a number.
+        assert_eq!(ids(query.shells(&environment)), ["m1"]);
+        assert_eq!(query.submodels(&environment).len(), 1);
+
+        let cast = Query::from_json(&json!({ "$gt": [
+            { "$numCast": { "$field": "$sme.MaxPower#value" } },
+            { "$numVal": 5000 }
+        ] }))
+        .unwrap();
+        assert_eq!(ids(cast.shells(&environment)), ["m1", "m3"]);
+
+        let kind = Query::from_json(&json!({ "$and": [
+            { "$eq": [{ "$field": "$aas#assetInformation.assetKind" }, { "$strVal": "Instance" }] },
+            { "$le": [{ "$field": "$sme.MaxPower#value" }, { "$numVal": 2200.0 }] }
+        ] }))
+        .unwrap();
+        assert_eq!(ids(kind.shells(&environment)), ["m2"]);
+    }
+
+    #[test]
+    fn elements_by_strings() {
+        let environment = environment();
+        let query = |condition: Value| Query::from_json(&condition).unwrap();
+
+        let names = query(json!({ "$and": [
+            { "$eq": [{ "$field": "$sme#language" }, { "$strVal": "de" }] },
+            { "$regex": [{ "$field": "$sme#value" }, { "$strVal": "^Antrieb m[12]$" }] }
+        ] }));
+        let found = names.elements(&environment);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].submodel.id().to_string(), "m2/technical-data");
+        assert_eq!(found[1].id_short_path, "ProductName");
+
+        let ratings = query(json!({ "$starts-with": [
+            { "$field": "$sme#value" },
+            { "$strVal": "IE" }
+        ] }));
+        let found = ratings.elements(&environment);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].id_short_path, "Ratings[1]");
+
+        let listed = query(json!({ "$contains": [
+            { "$field": "$sme.Ratings[]#value" },
+            { "$strVal": "55" }
+        ] }));
+        assert_eq!(listed.submodels(&environment).len(), 3);
+
+        let not_first = query(json!({ "$not": { "$eq": [
+            { "$field": "$sme.Ratings[0]#value" },
+            { "$strVal": "IE3" }
+        ] } }));
+        assert_eq!(not_first.submodels(&environment).len(), 3);
+    }
+}
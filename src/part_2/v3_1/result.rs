@@ -0,0 +1,88 @@
+use crate::part_2::v3_1::Message;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
+pub struct PagingMetadata {
+    /// Passed as `cursor` to get the next page, absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// One page of a collection, i.e. all submodels.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PagedResult<T> {
+    #[serde(rename = "paging_metadata")]
+    pub paging_metadata: PagingMetadata,
+
+    pub result: Vec<T>,
+}
+
+/// The `Result` of Part 2, the body of every failed request.
+/// Named differently so it does not shadow `std::result::Result`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
+pub struct ServiceResult {
+    pub messages: Vec<Message>,
+}
+
+impl ServiceResult {
+    pub fn error(text: impl Into<String>) -> Self {
+        Self {
+            messages: vec![Message::error(text)],
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+#[error("{0} is not a base64url encoded identifier")]
+pub struct IdentifierEncodingError(pub String);
+
+/// Identifiers are part of the path base64url encoded, i.e. `aHR0cHM6Ly9leGFtcGxlLmNvbQ`
+/// for `https://example.com`.
+pub fn encode_identifier(identifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(identifier)
+}
+
+/// Accepts the encoding with and without padding.
+pub fn decode_identifier(encoded: &str) -> Result<String, IdentifierEncodingError> {
+    URL_SAFE_NO_PAD
+        .decode(encoded.trim_end_matches('='))
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or_else(|| IdentifierEncodingError(encoded.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifier_round_trip() {
+        let encoded = encode_identifier("https://example.com/ids/sm/1?a=b");
+        assert!(!encoded.contains(['/', '+', '=']));
+        assert_eq!(
+            decode_identifier(&encoded).unwrap(),
+            "https://example.com/ids/sm/1?a=b"
+        );
+
+        assert_eq!(decode_identifier("YQ==").unwrap(), "a");
+        assert!(decode_identifier("not base64!").is_err());
+    }
+
+    #[test]
+    fn paged_result_json() {
+        let page = PagedResult {
+            paging_metadata: PagingMetadata {
+                cursor: Some("Mg".to_string()),
+            },
+            result: vec![1, 2],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&page).unwrap(),
+            serde_json::json!({ "paging_metadata": { "cursor": "Mg" }, "result": [1, 2] })
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! HTTP server for the Part 2 API, enabled by the `server` feature.
+//!
+//! The [`Server`] answers the AAS Repository (`/shells`) and Submodel Repository (`/submodels`)
+//! routes on top of a shared [`Environment`], see [`Service`] for the routes. Changes made through
+//! the API are visible to everyone holding the environment and the other way round.
+
+mod service;
+pub use service::*;
+
+use crate::part_1::v3_1::Environment;
+use std::error::Error;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use thiserror::Error;
+use tiny_http::{Header, Request, StatusCode};
+
+#[derive(Error, Debug)]
+pub enum ServerError {
+    #[error("Can't listen on the address: {0}")]
+    Bind(Box<dyn Error + Send + Sync>),
+}
+
+pub struct Server {
+    http: Arc<tiny_http::Server>,
+    service: Service,
+}
+
+impl Server {
+    /// Listens on `address`, use port 0 to let the system choose a free port.
+    pub fn bind(
+        address: impl ToSocketAddrs,
+        environment: Arc<Mutex<Environment>>,
+    ) -> Result<Self, ServerError> {
+        Ok(Self {
+            http: Arc::new(tiny_http::Server::http(address).map_err(ServerError::Bind)?),
+            service: Service::new(environment),
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Answers requests on the current thread, forever.
+    pub fn serve(&self) {
+        serve(&self.http, &self.service);
+    }
+
+    /// Answers requests on a background thread until the handle is stopped.
+    pub fn spawn(self) -> ServerHandle {
+        let http = self.http.clone();
+        let thread = thread::spawn(move || serve(&self.http, &self.service));
+
+        ServerHandle { http, thread }
+    }
+}
+
+pub struct ServerHandle {
+    http: Arc<tiny_http::Server>,
+    thread: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Stops accepting requests and waits for the server thread.
+    pub fn stop(self) {
+        self.http.unblock();
+        let _ = self.thread.join();
+    }
+}
+
+fn serve(http: &tiny_http::Server, service: &Service) {
+    for request in http.incoming_requests() {
+        answer(request, service);
+    }
+}
+
+fn answer(mut request: Request, service: &Service) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    let mut body = Vec::new();
+    let response = match (
+        Method::from_str(request.method().as_str()),
+        request.as_reader().read_to_end(&mut body),
+    ) {
+        (Ok(method), Ok(_)) => service.handle(method, path, query, &body),
+        (Err(_), _) => Response {
+            status: 405,
+            body: None,
+        },
+        (_, Err(_)) => Response {
+            status: 400,
+            body: None,
+        },
+    };
+
+    let status = StatusCode(response.status);
+    // The client might be gone already, there is no one left to tell.
+    let _ = match response.body {
+        Some(body) => {
+            let content_type = Header::from_bytes("Content-Type", "application/json")
+                .expect("The header is valid");
+            request.respond(
+                tiny_http::Response::from_string(body.to_string())
+                    .with_status_code(status)
+                    .with_header(content_type),
+            )
+        }
+        None => request.respond(tiny_http::Response::empty(status)),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_2::v3_1::encode_identifier;
+    use serde_json::{Value, json};
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[test]
+    fn serve_over_http() {
+        let environment = serde_json::from_value(json!({
+            "submodels": [{
+                "modelType": "Submodel",
+                "id": "https://example.com/sm/1",
+                "submodelElements": [
+                    { "modelType": "Property", "idShort": "Speed", "valueType": "xs:int", "value": 5 }
+                ]
+            }]
+        }))
+        .unwrap();
+        let environment = Arc::new(Mutex::new(environment));
+
+        let server = Server::bind("127.0.0.1:0", environment.clone())
+            .unwrap()
+            .spawn();
+        let address = server.local_addr().unwrap();
+        let speed = format!(
+            "/submodels/{}/submodel-elements/Speed/$value",
+            encode_identifier("https://example.com/sm/1")
+        );
+
+        assert_eq!(request(address, "PATCH", &speed, "8"), (204, String::new()));
+        assert_eq!(request(address, "GET", &speed, ""), (200, "8".to_string()));
+
+        let (status, body) = request(address, "GET", "/nothing", "");
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!((status, &body["messages"][0]["code"]), (404, &json!("404")));
+
+        let environment = environment.lock().unwrap();
+        let submodel = environment.submodel("https://example.com/sm/1").unwrap();
+        assert_eq!(
+            serde_json::to_value(submodel.element("Speed").unwrap()).unwrap()["value"],
+            8
+        );
+
+        drop(environment);
+        server.stop();
+    }
+}
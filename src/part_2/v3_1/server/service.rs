@@ -0,0 +1,1176 @@
+use crate::part_1::v3_1::core::{AssetAdministrationShell, Submodel};
+use crate::part_1::v3_1::observable::{
+    ObserveError, VALUE_ATTRIBUTES, add_element, children_attribute, remove_element,
+    strip_path_prefix,
+};
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use crate::part_1::v3_1::{Environment, Key, ModelReference, ReferenceInner};
+use crate::part_2::v3_1::value_only::{
+    ValueOnlyError, element_value, set_element_value, set_submodel_value, submodel_value,
+};
+use crate::part_2::v3_1::{
+    Message, PagedResult, PagingMetadata, ServiceResult, decode_identifier, encode_identifier,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use strum::{Display, EnumString};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, EnumString, Display)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+/// Status code and JSON body of an answered request.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: Option<Value>,
+}
+
+impl Response {
+    fn ok(body: impl Serialize) -> Self {
+        Self {
+            status: 200,
+            body: Some(to_json(&body)),
+        }
+    }
+
+    fn created(body: impl Serialize) -> Self {
+        Self {
+            status: 201,
+            body: Some(to_json(&body)),
+        }
+    }
+
+    fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: None,
+        }
+    }
+}
+
+/// A failed request, answered with a [`ServiceResult`].
+#[derive(Clone, PartialEq, Debug)]
+struct ApiError {
+    status: u16,
+    text: String,
+}
+
+impl ApiError {
+    fn bad_request(text: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            text: text.into(),
+        }
+    }
+
+    fn not_found(text: impl Into<String>) -> Self {
+        Self {
+            status: 404,
+            text: text.into(),
+        }
+    }
+
+    fn method_not_allowed(method: Method) -> Self {
+        Self {
+            status: 405,
+            text: format!("{method} is not allowed on this resource"),
+        }
+    }
+
+    fn conflict(text: impl Into<String>) -> Self {
+        Self {
+            status: 409,
+            text: text.into(),
+        }
+    }
+}
+
+impl From<ApiError> for Response {
+    fn from(error: ApiError) -> Self {
+        let message = Message {
+            code: Some(error.status.to_string()),
+            ..Message::error(error.text)
+        };
+
+        Self {
+            status: error.status,
+            body: Some(to_json(&ServiceResult {
+                messages: vec![message],
+            })),
+        }
+    }
+}
+
+impl From<ObserveError> for ApiError {
+    fn from(error: ObserveError) -> Self {
+        match error {
+            ObserveError::UnknownSubmodel(_) | ObserveError::UnknownElement(_) => {
+                ApiError::not_found(error.to_string())
+            }
+            ObserveError::DuplicateSubmodel(_) | ObserveError::DuplicateIdShort(_) => {
+                ApiError::conflict(error.to_string())
+            }
+            ObserveError::NotAContainer(_) | ObserveError::MissingIdShort => {
+                ApiError::bad_request(error.to_string())
+            }
+        }
+    }
+}
+
+impl From<ValueOnlyError> for ApiError {
+    fn from(error: ValueOnlyError) -> Self {
+        match error {
+            ValueOnlyError::UnknownElement(_) => ApiError::not_found(error.to_string()),
+            _ => ApiError::bad_request(error.to_string()),
+        }
+    }
+}
+
+/// Upper bound of `limit`, larger values are lowered to it.
+const MAX_LIMIT: usize = 1000;
+
+/// Query parameters shared by all routes.
+#[derive(Clone, PartialEq, Debug, Default)]
+struct Params {
+    /// `level=core`: only the direct children of the requested element.
+    core: bool,
+
+    /// `extent=withBlobValue`, by default the values of blobs are left out.
+    with_blob_value: bool,
+
+    limit: Option<usize>,
+
+    /// Position of the first result, passed as base64url encoded `cursor`.
+    offset: usize,
+}
+
+impl Params {
+    fn parse(query: &str) -> Result<Self, ApiError> {
+        let mut params = Params::default();
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let invalid = || ApiError::bad_request(format!("Invalid value {value} of {name}"));
+            let value = percent_decode(value).ok_or_else(invalid)?;
+
+            match name {
+                "level" => {
+                    params.core = match value.as_str() {
+                        "deep" => false,
+                        "core" => true,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "extent" => {
+                    params.with_blob_value = match value.as_str() {
+                        "withBlobValue" => true,
+                        "withoutBlobValue" => false,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "limit" => {
+                    params.limit = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|limit: &usize| *limit > 0)
+                            .ok_or_else(invalid)?
+                            .min(MAX_LIMIT),
+                    )
+                }
+                "cursor" => {
+                    params.offset = decode_identifier(&value)
+                        .ok()
+                        .and_then(|offset| offset.parse().ok())
+                        .ok_or_else(invalid)?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+struct Request<'a> {
+    method: Method,
+    params: Params,
+    body: &'a [u8],
+}
+
+impl Request<'_> {
+    fn json<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(self.body)
+            .map_err(|err| ApiError::bad_request(format!("Invalid request body: {err}")))
+    }
+}
+
+/// The Part 2 AAS Repository and Submodel Repository API, independent of the HTTP server.
+///
+/// Identifiers in paths are base64url encoded, elements are addressed by their idShort path,
+/// i.e. `/submodels/{submodelId}/submodel-elements/Maintenance.Counters[0]/$value`.
+#[derive(Clone, Debug)]
+pub struct Service {
+    environment: Arc<Mutex<Environment>>,
+}
+
+impl Service {
+    pub fn new(environment: Arc<Mutex<Environment>>) -> Self {
+        Self { environment }
+    }
+
+    pub fn environment(&self) -> &Arc<Mutex<Environment>> {
+        &self.environment
+    }
+
+    /// Answers a request, `path` and `query` as in the request URL, still percent encoded.
+    pub fn handle(&self, method: Method, path: &str, query: &str, body: &[u8]) -> Response {
+        self.route(method, path, query, body)
+            .unwrap_or_else(Response::from)
+    }
+
+    fn route(
+        &self,
+        method: Method,
+        path: &str,
+        query: &str,
+        body: &[u8],
+    ) -> Result<Response, ApiError> {
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                percent_decode(segment)
+                    .ok_or_else(|| ApiError::bad_request(format!("Invalid path {path}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+        let request = Request {
+            method,
+            params: Params::parse(query)?,
+            body,
+        };
+
+        let mut environment = self.lock();
+        match segments.as_slice() {
+            ["shells", rest @ ..] => shells(&mut environment, &request, rest),
+            ["submodels", rest @ ..] => submodels(&mut environment, &request, rest),
+            _ => Err(ApiError::not_found(format!("No resource {path}"))),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Environment> {
+        self.environment
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn shells(
+    environment: &mut Environment,
+    request: &Request,
+    segments: &[&str],
+) -> Result<Response, ApiError> {
+    let Some((encoded, rest)) = segments.split_first() else {
+        return match request.method {
+            Method::Get => page(
+                environment
+                    .asset_administration_shells
+                    .clone()
+                    .unwrap_or_default(),
+                &request.params,
+            ),
+            Method::Post => {
+                let shell: AssetAdministrationShell = request.json()?;
+                let id = shell.identifiable.id.as_ref();
+                if environment.asset_administration_shell(id).is_some() {
+                    return Err(ApiError::conflict(format!("The shell {id} already exists")));
+                }
+
+                let response = Response::created(&shell);
+                environment
+                    .asset_administration_shells
+                    .get_or_insert_default()
+                    .push(shell);
+                Ok(response)
+            }
+            method => Err(ApiError::method_not_allowed(method)),
+        };
+    };
+
+    let id = decode(encoded)?;
+    let not_found = || ApiError::not_found(format!("No shell {id}"));
+    let shells = environment
+        .asset_administration_shells
+        .as_mut()
+        .ok_or_else(not_found)?;
+    let index = shells
+        .iter()
+        .position(|shell| shell.identifiable.id.as_ref() == id)
+        .ok_or_else(not_found)?;
+    let shell = &mut shells[index];
+
+    match (request.method, rest) {
+        (Method::Get, []) => Ok(Response::ok(&*shell)),
+        (Method::Put, []) => {
+            let replacement: AssetAdministrationShell = request.json()?;
+            if replacement.identifiable.id.as_ref() != id {
+                return Err(ApiError::bad_request("The id of a shell can't be changed"));
+            }
+
+            *shell = replacement;
+            Ok(Response::no_content())
+        }
+        (Method::Delete, []) => {
+            shells.remove(index);
+            Ok(Response::no_content())
+        }
+        (Method::Get, ["$reference"]) => Ok(Response::ok(ModelReference::new(
+            ReferenceInner::new(Key::AssetAdministrationShell(id)),
+        ))),
+        (Method::Get, ["asset-information"]) => Ok(Response::ok(&shell.asset_information)),
+        (Method::Put, ["asset-information"]) => {
+            shell.asset_information = request.json()?;
+            Ok(Response::no_content())
+        }
+        (Method::Get, ["submodel-refs"]) => {
+            page(shell.submodels.clone().unwrap_or_default(), &request.params)
+        }
+        (Method::Post, ["submodel-refs"]) => {
+            let reference: ModelReference = request.json()?;
+            let references = shell.submodels.get_or_insert_default();
+            if references.contains(&reference) {
+                return Err(ApiError::conflict(format!(
+                    "{reference} is already referenced"
+                )));
+            }
+
+            references.push(reference.clone());
+            Ok(Response::created(reference))
+        }
+        (Method::Delete, ["submodel-refs", submodel]) => {
+            let submodel_id = decode(submodel)?;
+            if !refers_to(shell, &submodel_id) {
+                return Err(ApiError::not_found(format!(
+                    "No reference to {submodel_id}"
+                )));
+            }
+
+            remove_reference(shell, &submodel_id);
+            Ok(Response::no_content())
+        }
+        (method, ["submodels", encoded, rest @ ..]) => {
+            let submodel_id = decode(encoded)?;
+            if !refers_to(shell, &submodel_id) {
+                return Err(ApiError::not_found(format!(
+                    "The shell {id} has no submodel {submodel_id}"
+                )));
+            }
+
+            let response = submodel(environment, request, &submodel_id, rest)?;
+
+            // A submodel deleted through the shell is no longer referenced by it.
+            let shell = environment
+                .asset_administration_shells
+                .as_mut()
+                .map(|shells| &mut shells[index]);
+            if let (Method::Delete, [], Some(shell)) = (method, rest, shell) {
+                remove_reference(shell, &submodel_id);
+            }
+            Ok(response)
+        }
+        (
+            method,
+            [] | ["$reference"] | ["asset-information"] | ["submodel-refs"] | ["submodel-refs", _],
+        ) => Err(ApiError::method_not_allowed(method)),
+        _ => Err(ApiError::not_found("No such resource of a shell")),
+    }
+}
+
+fn refers_to(shell: &AssetAdministrationShell, submodel_id: &str) -> bool {
+    shell
+        .submodels
+        .iter()
+        .flatten()
+        .any(|reference| is_submodel_reference(reference, submodel_id))
+}
+
+fn remove_reference(shell: &mut AssetAdministrationShell, submodel_id: &str) {
+    if let Some(references) = &mut shell.submodels {
+        references.retain(|reference| !is_submodel_reference(reference, submodel_id));
+    }
+}
+
+fn is_submodel_reference(reference: &ModelReference, submodel_id: &str) -> bool {
+    matches!(reference.keys.as_slice(), [Key::Submodel(id)] if id == submodel_id)
+}
+
+fn submodels(
+    environment: &mut Environment,
+    request: &Request,
+    segments: &[&str],
+) -> Result<Response, ApiError> {
+    match (request.method, segments) {
+        (Method::Get, []) => page_views(
+            environment.submodels.as_deref().unwrap_or_default(),
+            |submodel| submodel_view(submodel, None, &request.params),
+            &request.params,
+        ),
+        (Method::Get, [modifier]) if modifier.starts_with('$') => page_views(
+            environment.submodels.as_deref().unwrap_or_default(),
+            |submodel| submodel_view(submodel, Some(modifier), &request.params),
+            &request.params,
+        ),
+        (Method::Post, []) => {
+            let submodel: Submodel = request.json()?;
+            let id = submodel.id().as_ref();
+            if environment.submodel(id).is_some() {
+                return Err(ApiError::conflict(format!(
+                    "The submodel {id} already exists"
+                )));
+            }
+
+            let response = Response::created(&submodel);
+            environment.submodels.get_or_insert_default().push(submodel);
+            Ok(response)
+        }
+        (method, []) => Err(ApiError::method_not_allowed(method)),
+        (_, [encoded, rest @ ..]) => submodel(environment, request, &decode(encoded)?, rest),
+    }
+}
+
+fn submodel(
+    environment: &mut Environment,
+    request: &Request,
+    id: &str,
+    segments: &[&str],
+) -> Result<Response, ApiError> {
+    let not_found = || ApiError::not_found(format!("No submodel {id}"));
+    let submodels = environment.submodels.as_mut().ok_or_else(not_found)?;
+    let index = submodels
+        .iter()
+        .position(|submodel| submodel.id().as_ref() == id)
+        .ok_or_else(not_found)?;
+    let submodel = &mut submodels[index];
+
+    match (request.method, segments) {
+        (Method::Get, []) => Ok(Response::ok(submodel_view(
+            submodel,
+            None,
+            &request.params,
+        )?)),
+        (Method::Get, [modifier]) if modifier.starts_with('$') => Ok(Response::ok(submodel_view(
+            submodel,
+            Some(modifier),
+            &request.params,
+        )?)),
+        (Method::Put, []) => {
+            let replacement: Submodel = request.json()?;
+            if replacement.id().as_ref() != id {
+                return Err(ApiError::bad_request(
+                    "The id of a submodel can't be changed",
+                ));
+            }
+
+            *submodel = replacement;
+            Ok(Response::no_content())
+        }
+        (Method::Patch, []) => {
+            let patched: Submodel = patch(submodel, request.json()?)?;
+            if patched.id().as_ref() != id {
+                return Err(ApiError::bad_request(
+                    "The id of a submodel can't be changed",
+                ));
+            }
+
+            *submodel = patched;
+            Ok(Response::no_content())
+        }
+        (Method::Patch, ["$metadata"]) => {
+            let changes = without(request.json()?, &["id", "submodelElements"]);
+
+            *submodel = patch(submodel, changes)?;
+            Ok(Response::no_content())
+        }
+        (Method::Patch, ["$value"]) => {
+            let mut patched = submodel.clone();
+            set_submodel_value(&mut patched, &request.json()?)?;
+
+            *submodel = patched;
+            Ok(Response::no_content())
+        }
+        (Method::Delete, []) => {
+            submodels.remove(index);
+            Ok(Response::no_content())
+        }
+        (_, ["submodel-elements", rest @ ..]) => elements(submodel, request, rest),
+        (method, [] | ["$metadata" | "$value" | "$reference" | "$path"]) => {
+            Err(ApiError::method_not_allowed(method))
+        }
+        _ => Err(ApiError::not_found("No such resource of a submodel")),
+    }
+}
+
+fn elements(
+    submodel: &mut Submodel,
+    request: &Request,
+    segments: &[&str],
+) -> Result<Response, ApiError> {
+    let top_level = |modifier: Option<&str>| {
+        let elements = submodel.submodel_elements.as_deref().unwrap_or_default();
+        page_views(
+            elements,
+            |element| {
+                let path = element.id_short().unwrap_or_default();
+                element_view(submodel, path, element, modifier, &request.params)
+            },
+            &request.params,
+        )
+    };
+
+    match (request.method, segments) {
+        (Method::Get, []) => top_level(None),
+        (Method::Get, [modifier]) if modifier.starts_with('$') => top_level(Some(modifier)),
+        (Method::Post, []) => {
+            let element: SubmodelElement = request.json()?;
+            let response = Response::created(&element);
+            add_element(submodel, "", element)?;
+            Ok(response)
+        }
+        (method, []) => Err(ApiError::method_not_allowed(method)),
+        (_, [path, rest @ ..]) => element(submodel, request, path, rest),
+    }
+}
+
+fn element(
+    submodel: &mut Submodel,
+    request: &Request,
+    path: &str,
+    segments: &[&str],
+) -> Result<Response, ApiError> {
+    let existing = submodel
+        .element(path)
+        .ok_or_else(|| ApiError::not_found(format!("No submodel element {path}")))?;
+
+    let replace = |submodel: &mut Submodel, replacement: SubmodelElement| {
+        let element = submodel.element_mut(path).expect("The element exists");
+        if replacement.id_short() != element.id_short() {
+            return Err(ApiError::bad_request(
+                "The idShort of an element can't be changed",
+            ));
+        }
+        if replacement.to_string() != element.to_string() {
+            return Err(ApiError::bad_request(
+                "The modelType of an element can't be changed",
+            ));
+        }
+
+        *element = replacement;
+        Ok(Response::no_content())
+    };
+
+    match (request.method, segments) {
+        (Method::Get, []) => Ok(Response::ok(element_view(
+            submodel,
+            path,
+            existing,
+            None,
+            &request.params,
+        )?)),
+        (Method::Get, [modifier]) if modifier.starts_with('$') => Ok(Response::ok(element_view(
+            submodel,
+            path,
+            existing,
+            Some(modifier),
+            &request.params,
+        )?)),
+        (Method::Put, []) => replace(submodel, request.json()?),
+        (Method::Post, []) => {
+            let element: SubmodelElement = request.json()?;
+            let response = Response::created(&element);
+            add_element(submodel, path, element)?;
+            Ok(response)
+        }
+        (Method::Patch, []) => {
+            let patched = patch(existing, request.json()?)?;
+            replace(submodel, patched)
+        }
+        (Method::Patch, ["$metadata"]) => {
+            let mut changes = without(request.json()?, VALUE_ATTRIBUTES);
+            if let Some(children) = children_attribute(existing) {
+                changes = without(changes, &[children]);
+            }
+
+            let patched = patch(existing, changes)?;
+            replace(submodel, patched)
+        }
+        (Method::Patch, ["$value"]) => {
+            let mut patched = existing.clone();
+            set_element_value(&mut patched, &request.json()?)?;
+            replace(submodel, patched)
+        }
+        (Method::Delete, []) => {
+            remove_element(submodel, path)?;
+            Ok(Response::no_content())
+        }
+        (method, ["$metadata" | "$value" | "$reference" | "$path"]) => {
+            Err(ApiError::method_not_allowed(method))
+        }
+        _ => Err(ApiError::not_found(
+            "No such resource of a submodel element",
+        )),
+    }
+}
+
+fn submodel_view(
+    submodel: &Submodel,
+    modifier: Option<&str>,
+    params: &Params,
+) -> Result<Value, ApiError> {
+    let mut submodel = submodel.clone();
+    for element in submodel.submodel_elements.iter_mut().flatten() {
+        restrict(element, params);
+        if let (true, Some(children)) = (params.core, element.children_mut()) {
+            children.clear();
+        }
+    }
+
+    match modifier {
+        None => Ok(to_json(&submodel)),
+        Some("$metadata") => Ok(without(to_json(&submodel), &["submodelElements"])),
+        Some("$value") => Ok(submodel_value(&submodel)),
+        Some("$reference") => Ok(to_json(&submodel.reference())),
+        Some("$path") => Ok(to_json(&paths(&submodel, "", params))),
+        Some(modifier) => Err(unknown_modifier(modifier)),
+    }
+}
+
+fn element_view(
+    submodel: &Submodel,
+    path: &str,
+    element: &SubmodelElement,
+    modifier: Option<&str>,
+    params: &Params,
+) -> Result<Value, ApiError> {
+    let mut element = element.clone();
+    restrict(&mut element, params);
+
+    match modifier {
+        None => Ok(to_json(&element)),
+        Some("$metadata") => {
+            let json = without(to_json(&element), VALUE_ATTRIBUTES);
+            Ok(match children_attribute(&element) {
+                Some(children) => without(json, &[children]),
+                None => json,
+            })
+        }
+        Some("$value") => element_value(&element)
+            .ok_or_else(|| ApiError::bad_request(format!("The element {path} has no value"))),
+        Some("$reference") => Ok(to_json(&submodel.element_reference(path))),
+        Some("$path") => Ok(to_json(&paths(submodel, path, params))),
+        Some(modifier) => Err(unknown_modifier(modifier)),
+    }
+}
+
+fn unknown_modifier(modifier: &str) -> ApiError {
+    ApiError::bad_request(format!("Unknown modifier {modifier}"))
+}
+
+/// Applies `level` and `extent` to a requested element: with `level=core` the children are
+/// kept, but not their children.
+fn restrict(element: &mut SubmodelElement, params: &Params) {
+    if !params.with_blob_value {
+        remove_blob_values(element);
+    }
+
+    if params.core {
+        for child in element.children_mut().into_iter().flatten() {
+            if let Some(grandchildren) = child.children_mut() {
+                grandchildren.clear();
+            }
+        }
+    }
+}
+
+fn remove_blob_values(element: &mut SubmodelElement) {
+    if let SubmodelElement::Blob(blob) = element {
+        blob.value = None;
+    }
+
+    element
+        .children_mut()
+        .into_iter()
+        .flatten()
+        .for_each(remove_blob_values);
+}
+
+/// The idShort paths of the element at `path` and below, or of all elements for an empty `path`.
+fn paths(submodel: &Submodel, path: &str, params: &Params) -> Vec<String> {
+    submodel
+        .id_short_paths()
+        .into_iter()
+        .filter(|candidate| {
+            strip_path_prefix(path, candidate).is_some_and(|rest| {
+                !params.core || rest.matches(['.', '[']).count() <= usize::from(!path.is_empty())
+            })
+        })
+        .collect()
+}
+
+fn page<T: Serialize>(items: Vec<T>, params: &Params) -> Result<Response, ApiError> {
+    if params.offset > items.len() {
+        return Err(ApiError::bad_request("The cursor is out of range"));
+    }
+
+    let end = params.limit.map_or(items.len(), |limit| {
+        params.offset.saturating_add(limit).min(items.len())
+    });
+    let cursor = (end < items.len()).then(|| encode_identifier(&end.to_string()));
+    let result = items
+        .into_iter()
+        .skip(params.offset)
+        .take(end - params.offset)
+        .collect();
+
+    Ok(Response::ok(PagedResult {
+        paging_metadata: PagingMetadata { cursor },
+        result,
+    }))
+}
+
+fn page_views<T>(
+    items: &[T],
+    view: impl Fn(&T) -> Result<Value, ApiError>,
+    params: &Params,
+) -> Result<Response, ApiError> {
+    page(items.iter().map(view).collect::<Result<_, _>>()?, params)
+}
+
+/// Applies a JSON merge patch (RFC 7396) to the serialization of `target`.
+fn patch<T: Serialize + DeserializeOwned>(target: &T, changes: Value) -> Result<T, ApiError> {
+    let mut json = to_json(target);
+    merge(&mut json, changes);
+
+    serde_json::from_value(json)
+        .map_err(|err| ApiError::bad_request(format!("Invalid request body: {err}")))
+}
+
+fn merge(target: &mut Value, changes: Value) {
+    let Value::Object(changes) = changes else {
+        *target = changes;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    if let Value::Object(target) = target {
+        for (name, value) in changes {
+            match value {
+                Value::Null => {
+                    target.remove(&name);
+                }
+                value => merge(target.entry(name).or_insert(Value::Null), value),
+            }
+        }
+    }
+}
+
+fn without(value: Value, names: &[&str]) -> Value {
+    match value {
+        Value::Object(mut object) => {
+            names.iter().for_each(|name| {
+                object.remove(*name);
+            });
+            Value::Object(object)
+        }
+        value => value,
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+fn decode(encoded: &str) -> Result<String, ApiError> {
+    decode_identifier(encoded).map_err(|err| ApiError::bad_request(err.to_string()))
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            byte => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn service() -> Service {
+        let environment = serde_json::from_value(json!({
+            "assetAdministrationShells": [{
+                "modelType": "AssetAdministrationShell",
+                "id": "https://example.com/aas/1",
+                "assetInformation": { "assetKind": "Instance", "globalAssetId": "https://example.com/asset/1" },
+                "submodels": [{
+                    "type": "ModelReference",
+                    "keys": [{ "type": "Submodel", "value": "https://example.com/sm/1" }]
+                }]
+            }],
+            "submodels": [{
+                "modelType": "Submodel",
+                "id": "https://example.com/sm/1",
+                "idShort": "Operational",
+                "submodelElements": [
+                    { "modelType": "Property", "idShort": "Speed", "valueType": "xs:int", "value": 5 },
+                    {
+                        "modelType": "SubmodelElementCollection",
+                        "idShort": "Maintenance",
+                        "value": [
+                            { "modelType": "Property", "idShort": "Hours", "valueType": "xs:double", "value": 1.5 },
+                            {
+                                "modelType": "SubmodelElementList",
+                                "idShort": "Counters",
+                                "typeValueListElement": "Property",
+                                "value": [
+                                    { "modelType": "Property", "valueType": "xs:int", "value": 1 },
+                                    { "modelType": "Property", "valueType": "xs:int", "value": 2 }
+                                ]
+                            },
+                            { "modelType": "Blob", "idShort": "Log", "contentType": "text/plain", "value": "aGk=" }
+                        ]
+                    }
+                ]
+            }]
+        }))
+        .unwrap();
+
+        Service::new(Arc::new(Mutex::new(environment)))
+    }
+
+    fn submodel_path(rest: &str) -> String {
+        format!(
+            "/submodels/{}{rest}",
+            encode_identifier("https://example.com/sm/1")
+        )
+    }
+
+    fn get(service: &Service, path: &str, query: &str) -> Response {
+        service.handle(Method::Get, path, query, b"")
+    }
+
+    fn send(service: &Service, method: Method, path: &str, body: Value) -> Response {
+        service.handle(method, path, "", body.to_string().as_bytes())
+    }
+
+    #[test]
+    fn shells() {
+        let service = service();
+        let shell = format!("/shells/{}", encode_identifier("https://example.com/aas/1"));
+
+        let response = get(&service, "/shells", "");
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.body.unwrap()["result"][0]["id"],
+            "https://example.com/aas/1"
+        );
+
+        let response = get(&service, &format!("{shell}/$reference"), "");
+        assert_eq!(
+            response.body.unwrap()["keys"][0],
+            json!({ "type": "AssetAdministrationShell", "value": "https://example.com/aas/1" })
+        );
+
+        let response = get(
+            &service,
+            &format!(
+                "{shell}/submodels/{}/submodel-elements/Speed/$value",
+                encode_identifier("https://example.com/sm/1")
+            ),
+            "",
+        );
+        assert_eq!(response.body, Some(json!(5)));
+
+        let reference = json!({
+            "type": "ModelReference",
+            "keys": [{ "type": "Submodel", "value": "https://example.com/sm/2" }]
+        });
+        let path = format!("{shell}/submodel-refs");
+        assert_eq!(
+            send(&service, Method::Post, &path, reference.clone()).status,
+            201
+        );
+        assert_eq!(send(&service, Method::Post, &path, reference).status, 409);
+
+        let path = format!(
+            "{shell}/submodel-refs/{}",
+            encode_identifier("https://example.com/sm/2")
+        );
+        assert_eq!(service.handle(Method::Delete, &path, "", b"").status, 204);
+        assert_eq!(service.handle(Method::Delete, &path, "", b"").status, 404);
+
+        let submodel = format!(
+            "{shell}/submodels/{}",
+            encode_identifier("https://example.com/sm/1")
+        );
+        assert_eq!(
+            service.handle(Method::Delete, &submodel, "", b"").status,
+            204
+        );
+        let references = get(&service, &format!("{shell}/submodel-refs"), "");
+        assert_eq!(references.body.unwrap()["result"], json!([]));
+        assert_eq!(get(&service, &submodel_path(""), "").status, 404);
+
+        assert_eq!(service.handle(Method::Delete, &shell, "", b"").status, 204);
+        assert_eq!(get(&service, &shell, "").status, 404);
+    }
+
+    #[test]
+    fn submodel_modifiers() {
+        let service = service();
+
+        let response = get(&service, &submodel_path("/$value"), "");
+        assert_eq!(
+            response.body.unwrap(),
+            json!({
+                "Speed": 5,
+                "Maintenance": {
+                    "Hours": 1.5,
+                    "Counters": [1, 2],
+                    "Log": { "contentType": "text/plain" }
+                }
+            })
+        );
+
+        let metadata = get(&service, &submodel_path("/$metadata"), "")
+            .body
+            .unwrap();
+        assert_eq!(metadata["idShort"], "Operational");
+        assert!(metadata.get("submodelElements").is_none());
+
+        let paths = get(&service, &submodel_path("/$path"), "level=core")
+            .body
+            .unwrap();
+        assert_eq!(paths, json!(["Speed", "Maintenance"]));
+
+        let paths = get(
+            &service,
+            &submodel_path("/submodel-elements/Maintenance/$path"),
+            "",
+        );
+        assert_eq!(
+            paths.body.unwrap(),
+            json!([
+                "Maintenance",
+                "Maintenance.Hours",
+                "Maintenance.Counters",
+                "Maintenance.Counters[0]",
+                "Maintenance.Counters[1]",
+                "Maintenance.Log"
+            ])
+        );
+
+        let reference = get(
+            &service,
+            &submodel_path("/submodel-elements/Maintenance.Counters%5B1%5D/$reference"),
+            "",
+        );
+        assert_eq!(
+            reference.body.unwrap()["keys"][3],
+            json!({ "type": "Property", "value": "1" })
+        );
+
+        let core = get(
+            &service,
+            &submodel_path("/submodel-elements/Maintenance"),
+            "level=core",
+        );
+        assert_eq!(core.body.unwrap()["value"][1]["value"], json!([]));
+
+        let blob = get(
+            &service,
+            &submodel_path("/submodel-elements/Maintenance.Log"),
+            "extent=withBlobValue",
+        );
+        assert_eq!(blob.body.unwrap()["value"], "aGk=");
+
+        assert_eq!(get(&service, &submodel_path("/$unknown"), "").status, 400);
+        assert_eq!(
+            get(&service, &submodel_path("/submodel-elements/Missing"), "").status,
+            404
+        );
+    }
+
+    #[test]
+    fn change_elements() {
+        let service = service();
+        let speed = submodel_path("/submodel-elements/Speed");
+
+        let response = send(
+            &service,
+            Method::Patch,
+            &format!("{speed}/$value"),
+            json!("7"),
+        );
+        assert_eq!(response.status, 204);
+        assert_eq!(
+            get(&service, &format!("{speed}/$value"), "").body,
+            Some(json!(7))
+        );
+
+        let response = send(
+            &service,
+            Method::Patch,
+            &format!("{speed}/$value"),
+            json!("fast"),
+        );
+        assert_eq!(response.status, 400);
+        assert_eq!(
+            response.body.unwrap()["messages"][0]["messageType"],
+            "Error"
+        );
+
+        let response = send(
+            &service,
+            Method::Patch,
+            &speed,
+            json!({ "category": "PARAMETER" }),
+        );
+        assert_eq!(response.status, 204);
+        let property = get(&service, &speed, "").body.unwrap();
+        assert_eq!(
+            (&property["category"], &property["value"]),
+            (&json!("PARAMETER"), &json!(7))
+        );
+
+        let response = send(
+            &service,
+            Method::Patch,
+            &speed,
+            json!({ "idShort": "Velocity" }),
+        );
+        assert_eq!(response.status, 400);
+
+        let added = json!({ "modelType": "Property", "idShort": "Cycles", "valueType": "xs:int", "value": 3 });
+        let maintenance = submodel_path("/submodel-elements/Maintenance");
+        assert_eq!(
+            send(&service, Method::Post, &maintenance, added.clone()).status,
+            201
+        );
+        assert_eq!(
+            send(&service, Method::Post, &maintenance, added).status,
+            409
+        );
+        assert_eq!(send(&service, Method::Post, &speed, json!({})).status, 400);
+
+        let values = json!({ "Maintenance": { "Cycles": 4, "Counters": [10] } });
+        assert_eq!(
+            send(&service, Method::Patch, &submodel_path("/$value"), values).status,
+            204
+        );
+        assert_eq!(
+            get(&service, &format!("{maintenance}/$value"), "")
+                .body
+                .unwrap()["Counters"],
+            json!([10, 2])
+        );
+
+        let cycles = format!("{maintenance}.Cycles");
+        assert_eq!(service.handle(Method::Delete, &cycles, "", b"").status, 204);
+        assert_eq!(get(&service, &cycles, "").status, 404);
+        assert_eq!(
+            service
+                .handle(Method::Put, &submodel_path("/$value"), "", b"{}")
+                .status,
+            405
+        );
+    }
+
+    #[test]
+    fn lookups_keep_missing_lists() {
+        let environment = serde_json::from_value(json!({})).unwrap();
+        let service = Service::new(Arc::new(Mutex::new(environment)));
+
+        let shell = format!("/shells/{}", encode_identifier("https://example.com/aas/1"));
+        assert_eq!(get(&service, &shell, "").status, 404);
+        assert_eq!(get(&service, &submodel_path(""), "").status, 404);
+
+        let environment = service.environment().lock().unwrap();
+        assert_eq!(environment.asset_administration_shells, None);
+        assert_eq!(environment.submodels, None);
+    }
+
+    #[test]
+    fn paging() {
+        let service = service();
+        for index in 2..=4 {
+            let submodel =
+                json!({ "modelType": "Submodel", "id": format!("https://example.com/sm/{index}") });
+            assert_eq!(
+                send(&service, Method::Post, "/submodels", submodel).status,
+                201
+            );
+        }
+
+        let first = get(&service, "/submodels/$reference", "limit=3")
+            .body
+            .unwrap();
+        assert_eq!(first["result"].as_array().unwrap().len(), 3);
+        let cursor = first["paging_metadata"]["cursor"].as_str().unwrap();
+
+        let second = get(&service, "/submodels", &format!("limit=3&cursor={cursor}"))
+            .body
+            .unwrap();
+        assert_eq!(second["result"][0]["id"], "https://example.com/sm/4");
+        assert!(second["paging_metadata"].get("cursor").is_none());
+
+        // the limit is capped, a cursor next to it must not overflow
+        let rest = get(
+            &service,
+            "/submodels",
+            "limit=18446744073709551615&cursor=MQ",
+        )
+        .body
+        .unwrap();
+        assert_eq!(rest["result"].as_array().unwrap().len(), 3);
+        assert!(rest["paging_metadata"].get("cursor").is_none());
+
+        assert_eq!(get(&service, "/submodels", "limit=0").status, 400);
+        assert_eq!(get(&service, "/submodels", "cursor=invalid!").status, 400);
+        assert_eq!(
+            send(
+                &service,
+                Method::Post,
+                "/submodels",
+                json!({ "id": "https://example.com/sm/4" })
+            )
+            .status,
+            409
+        );
+    }
+}
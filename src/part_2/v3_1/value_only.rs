@@ -0,0 +1,375 @@
+//! The `$value` serialization of submodels and submodel elements.
+//!
+//! The ValueOnly format leaves out all metadata: a `Property` is its value as JSON primitive, a
+//! `SubmodelElementCollection` an object keyed by the idShorts of its elements and a
+//! `SubmodelElementList` an array of the values of its elements.
+
+use crate::part_1::v3_1::LangString;
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::primitives::DataXsdError;
+use crate::part_1::v3_1::submodel_elements::SubmodelElement;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ValueOnlyError {
+    #[error("The value of {path} has to be {expected}")]
+    Mismatch { path: String, expected: String },
+
+    #[error("No submodel element {0}")]
+    UnknownElement(String),
+
+    #[error("The value of {path} can't be set: {source}")]
+    InvalidValue { path: String, source: DataXsdError },
+
+    #[error("The value of {0} can't be set")]
+    Unsupported(String),
+}
+
+/// The values of all elements of `submodel`, keyed by idShort.
+pub fn submodel_value(submodel: &Submodel) -> Value {
+    elements_value(submodel.submodel_elements.as_deref().unwrap_or_default())
+}
+
+/// The value of `element`, `None` for elements without value like `Operation` or `Capability`.
+pub fn element_value(element: &SubmodelElement) -> Option<Value> {
+    json_value(&serde_json::to_value(element).ok()?)
+}
+
+/// Sets the values of the elements of `submodel`, see [`set_element_value`].
+pub fn set_submodel_value(submodel: &mut Submodel, value: &Value) -> Result<(), ValueOnlyError> {
+    set_children_value(
+        submodel.submodel_elements.get_or_insert_default(),
+        value,
+        "",
+    )
+}
+
+/// Sets the value of `element` and the elements it contains. Only the given entries of
+/// collections are changed, all of them have to exist.
+pub fn set_element_value(
+    element: &mut SubmodelElement,
+    value: &Value,
+) -> Result<(), ValueOnlyError> {
+    let path = element.id_short().unwrap_or_default().to_string();
+    set_value(element, value, &path)
+}
+
+fn elements_value(elements: &[SubmodelElement]) -> Value {
+    Value::Object(
+        elements
+            .iter()
+            .filter_map(|element| Some((element.id_short()?.to_string(), element_value(element)?)))
+            .collect(),
+    )
+}
+
+/// Works on the JSON serialization, so annotations of relationships are covered as well.
+fn json_value(element: &Value) -> Option<Value> {
+    let field = |name: &str| element.get(name).cloned();
+    let pick = |names: &[&str]| {
+        Value::Object(
+            names
+                .iter()
+                .filter_map(|name| Some((name.to_string(), field(name)?)))
+                .collect(),
+        )
+    };
+    let by_id_short = |elements: Option<&Value>| {
+        Value::Object(
+            elements
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|element| {
+                    Some((
+                        element.get("idShort")?.as_str()?.to_string(),
+                        json_value(element)?,
+                    ))
+                })
+                .collect(),
+        )
+    };
+
+    let value = match element.get("modelType")?.as_str()? {
+        "Property" | "ReferenceElement" => field("value").unwrap_or(Value::Null),
+        "MultiLanguageProperty" => Value::Array(
+            field("value")
+                .and_then(|texts| texts.as_array().cloned())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|text| {
+                    let mut entry = Map::new();
+                    entry.insert(
+                        text.get("language")?.as_str()?.to_string(),
+                        text.get("text")?.clone(),
+                    );
+                    Some(Value::Object(entry))
+                })
+                .collect(),
+        ),
+        "Range" => pick(&["min", "max"]),
+        "File" | "Blob" => pick(&["contentType", "value"]),
+        "RelationshipElement" => pick(&["first", "second"]),
+        "AnnotatedRelationshipElement" => {
+            let mut value = pick(&["first", "second"]);
+            value["annotations"] = Value::Array(
+                by_id_short(element.get("annotations"))
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(id_short, value)| {
+                        Value::Object(Map::from_iter([(id_short.clone(), value.clone())]))
+                    })
+                    .collect(),
+            );
+            value
+        }
+        "SubmodelElementCollection" => by_id_short(element.get("value")),
+        "SubmodelElementList" => Value::Array(
+            field("value")
+                .and_then(|elements| elements.as_array().cloned())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(json_value)
+                .collect(),
+        ),
+        "Entity" => {
+            let mut value = pick(&["entityType", "globalAssetId", "specificAssetIds"]);
+            value["statements"] = by_id_short(element.get("statements"));
+            value
+        }
+        "BasicEventElement" => pick(&["observed"]),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+fn set_value(
+    element: &mut SubmodelElement,
+    value: &Value,
+    path: &str,
+) -> Result<(), ValueOnlyError> {
+    let mismatch = |expected: &str| ValueOnlyError::Mismatch {
+        path: path.to_string(),
+        expected: expected.to_string(),
+    };
+    let invalid = |source| ValueOnlyError::InvalidValue {
+        path: path.to_string(),
+        source,
+    };
+
+    match element {
+        SubmodelElement::Property(property) => match lexical(value) {
+            Some(lexical) => property.value.set_lexical(&lexical).map_err(invalid)?,
+            None if value.is_null() => property.value.clear(),
+            None => return Err(mismatch("a JSON primitive")),
+        },
+        SubmodelElement::Range(range) => {
+            let bounds = value.as_object().ok_or_else(|| mismatch("an object"))?;
+            let (min, max) = (
+                bounds.get("min").and_then(lexical),
+                bounds.get("max").and_then(lexical),
+            );
+            range
+                .set_lexical(min.as_deref(), max.as_deref())
+                .map_err(invalid)?
+        }
+        SubmodelElement::MultiLanguageProperty(property) => {
+            let texts = value
+                .as_array()
+                .ok_or_else(|| mismatch("an array of language strings"))?
+                .iter()
+                .flat_map(|entry| entry.as_object().into_iter().flatten())
+                .map(|(language, text)| {
+                    let text = text
+                        .as_str()
+                        .ok_or_else(|| mismatch("an array of language strings"))?;
+                    LangString::try_new(language, text.to_string())
+                        .map_err(|_| mismatch("an array of language strings"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            property.value = Some(texts);
+        }
+        SubmodelElement::SubmodelElementCollection(collection) => {
+            set_children_value(collection.value.get_or_insert_default(), value, path)?
+        }
+        SubmodelElement::SubmodelElementList(list) => {
+            let values = value.as_array().ok_or_else(|| mismatch("an array"))?;
+            let elements = list.value.get_or_insert_default();
+            for (index, value) in values.iter().enumerate() {
+                let path = format!("{path}[{index}]");
+                let element = elements
+                    .get_mut(index)
+                    .ok_or_else(|| ValueOnlyError::UnknownElement(path.clone()))?;
+                set_value(element, value, &path)?;
+            }
+        }
+        SubmodelElement::Entity(_) => {
+            let object = value.as_object().ok_or_else(|| mismatch("an object"))?;
+            let mut attributes = object.clone();
+            let statements = attributes.remove("statements");
+
+            merge(element, attributes, path)?;
+            if let (Some(statements), SubmodelElement::Entity(entity)) = (statements, element) {
+                let children = entity.inner_mut().statement.get_or_insert_default();
+                set_children_value(children, &statements, path)?;
+            }
+        }
+        SubmodelElement::File(_)
+        | SubmodelElement::Blob(_)
+        | SubmodelElement::RelationshipElement(_)
+        | SubmodelElement::BasicEventElement(_) => {
+            let object = value.as_object().ok_or_else(|| mismatch("an object"))?;
+            merge(element, object.clone(), path)?
+        }
+        SubmodelElement::ReferenceElement(_) => merge(
+            element,
+            Map::from_iter([("value".to_string(), value.clone())]),
+            path,
+        )?,
+        _ => return Err(ValueOnlyError::Unsupported(path.to_string())),
+    }
+
+    Ok(())
+}
+
+fn set_children_value(
+    elements: &mut [SubmodelElement],
+    value: &Value,
+    parent: &str,
+) -> Result<(), ValueOnlyError> {
+    let values = value.as_object().ok_or_else(|| ValueOnlyError::Mismatch {
+        path: parent.to_string(),
+        expected: "an object".to_string(),
+    })?;
+
+    for (id_short, value) in values {
+        let path = match parent {
+            "" => id_short.clone(),
+            parent => format!("{parent}.{id_short}"),
+        };
+        let element = elements
+            .iter_mut()
+            .find(|element| element.id_short() == Some(id_short))
+            .ok_or_else(|| ValueOnlyError::UnknownElement(path.clone()))?;
+        set_value(element, value, &path)?;
+    }
+
+    Ok(())
+}
+
+/// Replaces attributes through the JSON serialization, for elements without typed setters.
+fn merge(
+    element: &mut SubmodelElement,
+    attributes: Map<String, Value>,
+    path: &str,
+) -> Result<(), ValueOnlyError> {
+    let Ok(Value::Object(mut json)) = serde_json::to_value(&*element) else {
+        return Err(ValueOnlyError::Unsupported(path.to_string()));
+    };
+    json.extend(attributes);
+
+    *element =
+        serde_json::from_value(Value::Object(json)).map_err(|err| ValueOnlyError::Mismatch {
+            path: path.to_string(),
+            expected: err.to_string(),
+        })?;
+    Ok(())
+}
+
+fn lexical(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn element(json: Value) -> SubmodelElement {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn values() {
+        let status = element(json!({
+            "modelType": "MultiLanguageProperty",
+            "idShort": "Status",
+            "value": [{ "language": "en", "text": "running" }, { "language": "de", "text": "in Betrieb" }]
+        }));
+        assert_eq!(
+            element_value(&status),
+            Some(json!([{ "en": "running" }, { "de": "in Betrieb" }]))
+        );
+
+        let limits = element(json!({
+            "modelType": "Range", "idShort": "Limits", "valueType": "xs:int", "min": 1, "max": 9
+        }));
+        assert_eq!(element_value(&limits), Some(json!({ "min": 1, "max": 9 })));
+
+        let part = element(json!({
+            "modelType": "Entity",
+            "idShort": "Part",
+            "entityType": "SelfManagedEntity",
+            "globalAssetId": "https://example.com/asset/2",
+            "statements": [{ "modelType": "Property", "idShort": "Weight", "valueType": "xs:double", "value": 2.5 }]
+        }));
+        assert_eq!(
+            element_value(&part),
+            Some(json!({
+                "entityType": "SelfManagedEntity",
+                "globalAssetId": "https://example.com/asset/2",
+                "statements": { "Weight": 2.5 }
+            }))
+        );
+
+        let capability = element(json!({ "modelType": "Capability", "idShort": "Weld" }));
+        assert_eq!(element_value(&capability), None);
+    }
+
+    #[test]
+    fn set_values() {
+        let mut limits =
+            element(json!({ "modelType": "Range", "idShort": "Limits", "valueType": "xs:int" }));
+        set_element_value(&mut limits, &json!({ "min": "1", "max": 9 })).unwrap();
+        assert_eq!(element_value(&limits), Some(json!({ "min": 1, "max": 9 })));
+        assert!(matches!(
+            set_element_value(&mut limits, &json!({ "min": 1.5 })),
+            Err(ValueOnlyError::InvalidValue { .. })
+        ));
+
+        let mut status =
+            element(json!({ "modelType": "MultiLanguageProperty", "idShort": "Status" }));
+        set_element_value(&mut status, &json!([{ "en": "stopped" }])).unwrap();
+        assert_eq!(element_value(&status), Some(json!([{ "en": "stopped" }])));
+
+        let mut manual = element(
+            json!({ "modelType": "File", "idShort": "Manual", "contentType": "text/plain" }),
+        );
+        let value =
+            json!({ "contentType": "application/pdf", "value": "https://example.com/manual.pdf" });
+        set_element_value(&mut manual, &value).unwrap();
+        assert_eq!(element_value(&manual), Some(value));
+
+        let mut maintenance = element(json!({
+            "modelType": "SubmodelElementCollection",
+            "idShort": "Maintenance",
+            "value": [{ "modelType": "Property", "idShort": "Hours", "valueType": "xs:double" }]
+        }));
+        assert_eq!(
+            set_element_value(&mut maintenance, &json!({ "Minutes": 1 })),
+            Err(ValueOnlyError::UnknownElement(
+                "Maintenance.Minutes".to_string()
+            ))
+        );
+        set_element_value(&mut maintenance, &json!({ "Hours": 3 })).unwrap();
+        assert_eq!(element_value(&maintenance), Some(json!({ "Hours": 3.0 })));
+    }
+}
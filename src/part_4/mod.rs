@@ -0,0 +1,2 @@
+/// type definitions for version 3.0 of the AAS Specification part 4.
+pub mod v3_0;
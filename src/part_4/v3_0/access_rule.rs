@@ -0,0 +1,739 @@
+//! The access rule model: who may do what on which objects, under which condition.
+//!
+//! Rules are read from the JSON of `AllAccessPermissionRules`, their formulas use the grammar of
+//! the [query language](crate::part_2::v3_1::query) extended by `$attribute` for the attributes
+//! of the subject. Named definitions (`DEFATTRIBUTES`, `DEFACLS`, `DEFOBJECTS` and
+//! `DEFFORMULAS`) are resolved while parsing.
+//!
+//! Access is denied unless an enabled rule allows it.
+
+use crate::part_1::v3_1::core::Submodel;
+use crate::part_1::v3_1::{Environment, Key};
+pub use crate::part_2::v3_1::query::{AttributeItem, GlobalAttribute};
+use crate::part_2::v3_1::query::{
+    AttributeResolver, Context, Expression, QueryError, QueryValue, element_value, parse_keys,
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum AccessRuleError {
+    #[error("Malformed access rules: {0}")]
+    Syntax(String),
+
+    #[error("No definition named {0}")]
+    UnknownDefinition(String),
+
+    #[error("Invalid object {0}")]
+    InvalidObject(String),
+
+    #[error("Invalid formula: {0}")]
+    Formula(#[from] QueryError),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Right {
+    Create,
+    Read,
+    Update,
+    Delete,
+    Execute,
+    /// Seeing that an object exists, granted by `READ` as well.
+    View,
+    All,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Access {
+    Allow,
+    /// The rule is kept but has no effect.
+    Disabled,
+}
+
+/// The subject asking for access, described by the claims of its access token.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Subject {
+    claims: HashMap<String, Vec<String>>,
+    client_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Acl {
+    pub attributes: Vec<AttributeItem>,
+    pub rights: Vec<Right>,
+    pub access: Access,
+}
+
+/// The objects a rule is about. Values of keys may be `*` for any identifier.
+#[derive(Clone, PartialEq, Debug)]
+pub enum RuleObject {
+    /// An API route like `/submodels/*`, a trailing `*` matches any rest.
+    Route(String),
+    /// A shell, submodel or concept description like `(Submodel)*`, including all it contains.
+    Identifiable(Key),
+    /// A referable like `(Submodel)urn:x, (Property)Power`, including all it contains.
+    Referable(Vec<Key>),
+    /// A descriptor like `(aasDesc)*`, only known to registries.
+    Descriptor(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct AccessPermissionRule {
+    pub acl: Acl,
+    pub objects: Vec<RuleObject>,
+    /// Rules without formula hold unconditionally.
+    pub formula: Expression,
+}
+
+/// What access is asked for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Target<'a> {
+    Shell(&'a str),
+    Submodel(&'a str),
+    ConceptDescription(&'a str),
+    Element {
+        submodel: &'a str,
+        id_short_path: &'a str,
+    },
+    Route(&'a str),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AccessRules {
+    pub rules: Vec<AccessPermissionRule>,
+}
+
+impl Subject {
+    /// A subject without claims.
+    pub fn anonymous() -> Self {
+        Self::default()
+    }
+
+    /// Adds a value of the claim `name`, claims may have multiple values.
+    pub fn with_claim(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.claims
+            .entry(name.into())
+            .or_default()
+            .push(value.into());
+        self
+    }
+
+    /// The time of the client for `CLIENTNOW`.
+    pub fn with_client_time(mut self, time: DateTime<Utc>) -> Self {
+        self.client_time = Some(time);
+        self
+    }
+
+    pub fn claim(&self, name: &str) -> &[String] {
+        self.claims.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.claims.is_empty()
+    }
+}
+
+impl AttributeResolver for Subject {
+    fn attribute(
+        &self,
+        attribute: &AttributeItem,
+        environment: Option<&Environment>,
+    ) -> Vec<QueryValue> {
+        match attribute {
+            AttributeItem::Claim(name) => self
+                .claim(name)
+                .iter()
+                .map(|value| QueryValue::String(value.clone()))
+                .collect(),
+            AttributeItem::Global(GlobalAttribute::LocalNow | GlobalAttribute::UtcNow) => {
+                vec![QueryValue::DateTime(Utc::now())]
+            }
+            AttributeItem::Global(GlobalAttribute::ClientNow) => self
+                .client_time
+                .map(QueryValue::DateTime)
+                .into_iter()
+                .collect(),
+            AttributeItem::Global(GlobalAttribute::Anonymous) => {
+                vec![QueryValue::Boolean(self.is_anonymous())]
+            }
+            AttributeItem::Reference(keys) => environment
+                .and_then(|environment| environment.element(keys))
+                .and_then(|element| element_value(element).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Acl {
+    fn grants(&self, right: Right) -> bool {
+        self.rights.iter().any(|granted| {
+            *granted == right
+                || *granted == Right::All
+                || (*granted, right) == (Right::Read, Right::View)
+        })
+    }
+
+    /// Rules naming claims only apply to subjects having them.
+    fn applies_to(&self, subject: &Subject) -> bool {
+        self.attributes.iter().all(|attribute| match attribute {
+            AttributeItem::Claim(name) => !subject.claim(name).is_empty(),
+            _ => true,
+        })
+    }
+}
+
+impl RuleObject {
+    fn matches(&self, target: Target, environment: Option<&Environment>) -> bool {
+        match (self, target) {
+            (RuleObject::Route(pattern), Target::Route(route)) => match pattern.strip_suffix('*') {
+                Some(prefix) => route.starts_with(prefix),
+                None => route == pattern,
+            },
+            (RuleObject::Identifiable(key), target) => match (key, target) {
+                (Key::AssetAdministrationShell(id), Target::Shell(target))
+                | (Key::ConceptDescription(id), Target::ConceptDescription(target))
+                | (Key::Submodel(id), Target::Submodel(target))
+                | (
+                    Key::Submodel(id),
+                    Target::Element {
+                        submodel: target, ..
+                    },
+                ) => matches_value(id, target),
+                _ => false,
+            },
+            (RuleObject::Referable(keys), Target::Submodel(id)) => {
+                matches!(keys.as_slice(), [Key::Submodel(rule)] if matches_value(rule, id))
+            }
+            (
+                RuleObject::Referable(keys),
+                Target::Element {
+                    submodel,
+                    id_short_path,
+                },
+            ) => {
+                let Some(reference) = environment
+                    .and_then(|environment| environment.submodel(submodel))
+                    .and_then(|submodel| submodel.element_reference(id_short_path))
+                else {
+                    return false;
+                };
+
+                keys.len() <= reference.keys.len()
+                    && keys.iter().zip(&reference.keys).all(|(rule, key)| {
+                        rule.key_type() == key.key_type()
+                            && matches_value(rule.value(), key.value())
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl AccessPermissionRule {
+    fn allows(&self, subject: &Subject, right: Right, target: Target, context: &Context) -> bool {
+        self.acl.access == Access::Allow
+            && self.acl.grants(right)
+            && self.acl.applies_to(subject)
+            && self
+                .objects
+                .iter()
+                .any(|object| object.matches(target, context.environment))
+            && self.formula.evaluate(context)
+    }
+}
+
+impl AccessRules {
+    /// Accepts `{ "AllAccessPermissionRules": { … } }` or the inner object.
+    pub fn from_json(json: &Value) -> Result<Self, AccessRuleError> {
+        let json = json.get("AllAccessPermissionRules").unwrap_or(json);
+        let mut definitions = Definitions::default();
+
+        for (name, attributes) in named(json, "DEFATTRIBUTES", "attributes")? {
+            let attributes = definitions.attributes(attributes)?;
+            definitions.attributes.insert(name, attributes);
+        }
+        for (name, acl) in named(json, "DEFACLS", "acl")? {
+            let acl = definitions.acl(acl)?;
+            definitions.acls.insert(name, acl);
+        }
+        for (name, objects) in named(json, "DEFOBJECTS", "objects")? {
+            let objects = definitions.objects(objects)?;
+            definitions.objects.insert(name, objects);
+        }
+        for (name, formula) in named(json, "DEFFORMULAS", "formula")? {
+            definitions
+                .formulas
+                .insert(name, Expression::from_json(formula)?);
+        }
+
+        let rules = json
+            .get("rules")
+            .and_then(Value::as_array)
+            .ok_or_else(|| syntax("rules has to be a list"))?
+            .iter()
+            .map(|rule| definitions.rule(rule))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Whether `subject` may use `right` on `target` of `environment`.
+    pub fn is_allowed(
+        &self,
+        environment: &Environment,
+        subject: &Subject,
+        right: Right,
+        target: Target,
+    ) -> bool {
+        let mut context = Context {
+            environment: Some(environment),
+            subject: Some(subject),
+            ..Context::default()
+        };
+
+        match target {
+            Target::Shell(id) => {
+                let Some(shell) = environment.asset_administration_shell(id) else {
+                    return false;
+                };
+                context.shell = Some(shell);
+            }
+            Target::Submodel(id) => {
+                let Some(submodel) = environment.submodel(id) else {
+                    return false;
+                };
+                context.submodel = Some(submodel);
+            }
+            Target::Element {
+                submodel,
+                id_short_path,
+            } => {
+                let Some(submodel) = environment.submodel(submodel) else {
+                    return false;
+                };
+                let Some(element) = submodel.element(id_short_path) else {
+                    return false;
+                };
+                context.submodel = Some(submodel);
+                context.element = Some(element);
+            }
+            Target::ConceptDescription(id) => {
+                if environment.concept_description(id).is_none() {
+                    return false;
+                }
+            }
+            Target::Route(_) => {}
+        }
+
+        self.rules
+            .iter()
+            .any(|rule| rule.allows(subject, right, target, &context))
+    }
+
+    /// The submodels `subject` may read.
+    pub fn readable_submodels<'a>(
+        &self,
+        environment: &'a Environment,
+        subject: &Subject,
+    ) -> Vec<&'a Submodel> {
+        environment
+            .submodels
+            .iter()
+            .flatten()
+            .filter(|submodel| {
+                let target = Target::Submodel(submodel.identifiable.id.as_ref());
+                self.is_allowed(environment, subject, Right::Read, target)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for AccessRules {
+    type Err = AccessRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let json = serde_json::from_str(s).map_err(|err| syntax(&err.to_string()))?;
+        Self::from_json(&json)
+    }
+}
+
+#[derive(Default)]
+struct Definitions {
+    attributes: HashMap<String, Vec<AttributeItem>>,
+    acls: HashMap<String, Acl>,
+    objects: HashMap<String, Vec<RuleObject>>,
+    formulas: HashMap<String, Expression>,
+}
+
+impl Definitions {
+    fn rule(&self, json: &Value) -> Result<AccessPermissionRule, AccessRuleError> {
+        let acl = match (json.get("ACL"), json.get("USEACL")) {
+            (Some(acl), None) => self.acl(acl)?,
+            (None, Some(name)) => lookup(&self.acls, name)?.clone(),
+            _ => return Err(syntax("a rule needs either ACL or USEACL")),
+        };
+        let objects = self.objects(
+            json.get("OBJECTS")
+                .ok_or_else(|| syntax("a rule needs OBJECTS"))?,
+        )?;
+        let formula = match (json.get("FORMULA"), json.get("USEFORMULA")) {
+            (Some(formula), None) => Expression::from_json(formula)?,
+            (None, Some(name)) => lookup(&self.formulas, name)?.clone(),
+            (None, None) => Expression::Boolean(true),
+            _ => return Err(syntax("a rule has either FORMULA or USEFORMULA")),
+        };
+
+        Ok(AccessPermissionRule {
+            acl,
+            objects,
+            formula,
+        })
+    }
+
+    fn acl(&self, json: &Value) -> Result<Acl, AccessRuleError> {
+        let attributes = match (json.get("ATTRIBUTES"), json.get("USEATTRIBUTES")) {
+            (Some(attributes), None) => self.attributes(attributes)?,
+            (None, Some(name)) => lookup(&self.attributes, name)?.clone(),
+            (None, None) => Vec::new(),
+            _ => return Err(syntax("an ACL has either ATTRIBUTES or USEATTRIBUTES")),
+        };
+        let rights = json
+            .get("RIGHTS")
+            .and_then(Value::as_array)
+            .ok_or_else(|| syntax("an ACL needs a list of RIGHTS"))?
+            .iter()
+            .map(|right| {
+                right
+                    .as_str()
+                    .and_then(|right| Right::from_str(right).ok())
+                    .ok_or_else(|| syntax(&format!("{right} is not a right")))
+            })
+            .collect::<Result<_, _>>()?;
+        let access = json
+            .get("ACCESS")
+            .and_then(Value::as_str)
+            .and_then(|access| Access::from_str(access).ok())
+            .ok_or_else(|| syntax("ACCESS has to be ALLOW or DISABLED"))?;
+
+        Ok(Acl {
+            attributes,
+            rights,
+            access,
+        })
+    }
+
+    /// A list of attributes, `{ "USEATTRIBUTES": "name" }` entries are replaced by their
+    /// definition.
+    fn attributes(&self, json: &Value) -> Result<Vec<AttributeItem>, AccessRuleError> {
+        let mut attributes = Vec::new();
+
+        for item in list(json, "ATTRIBUTES")? {
+            match item.get("USEATTRIBUTES") {
+                Some(name) => attributes.extend(lookup(&self.attributes, name)?.iter().cloned()),
+                None => attributes.push(AttributeItem::from_json(item)?),
+            }
+        }
+
+        Ok(attributes)
+    }
+
+    /// A list of objects, `{ "USEOBJECTS": ["name"] }` entries are replaced by their definitions.
+    fn objects(&self, json: &Value) -> Result<Vec<RuleObject>, AccessRuleError> {
+        let mut objects = Vec::new();
+
+        for item in list(json, "OBJECTS")? {
+            if let Some(names) = item.get("USEOBJECTS") {
+                for name in list(names, "USEOBJECTS")? {
+                    objects.extend(lookup(&self.objects, name)?.iter().cloned());
+                }
+                continue;
+            }
+
+            let invalid = || AccessRuleError::InvalidObject(item.to_string());
+            let (kind, value) = single_entry(item).ok_or_else(invalid)?;
+            let value = value.as_str().ok_or_else(invalid)?;
+
+            objects.push(match kind {
+                "ROUTE" => RuleObject::Route(value.to_string()),
+                "IDENTIFIABLE" => {
+                    RuleObject::Identifiable(Key::from_str(value.trim()).map_err(|_| invalid())?)
+                }
+                "REFERABLE" => RuleObject::Referable(parse_keys(value).ok_or_else(invalid)?),
+                "DESCRIPTOR" => RuleObject::Descriptor(value.to_string()),
+                _ => return Err(invalid()),
+            });
+        }
+
+        Ok(objects)
+    }
+}
+
+fn syntax(message: &str) -> AccessRuleError {
+    AccessRuleError::Syntax(message.to_string())
+}
+
+fn single_entry(json: &Value) -> Option<(&str, &Value)> {
+    let object = json.as_object().filter(|object| object.len() == 1)?;
+    object
+        .iter()
+        .next()
+        .map(|(key, value)| (key.as_str(), value))
+}
+
+fn list<'a>(json: &'a Value, name: &str) -> Result<&'a Vec<Value>, AccessRuleError> {
+    json.as_array()
+        .ok_or_else(|| syntax(&format!("{name} has to be a list")))
+}
+
+/// The entries of a list of definitions like `DEFACLS: [{ "name": "…", "acl": { … } }]`.
+fn named<'a>(
+    json: &'a Value,
+    list_name: &str,
+    field: &str,
+) -> Result<Vec<(String, &'a Value)>, AccessRuleError> {
+    let Some(definitions) = json.get(list_name) else {
+        return Ok(Vec::new());
+    };
+
+    list(definitions, list_name)?
+        .iter()
+        .map(|definition| {
+            let name = definition.get("name").and_then(Value::as_str);
+            match (name, definition.get(field)) {
+                (Some(name), Some(value)) => Ok((name.to_string(), value)),
+                _ => Err(syntax(&format!(
+                    "{list_name} entries need name and {field}"
+                ))),
+            }
+        })
+        .collect()
+}
+
+fn lookup<'a, T>(
+    definitions: &'a HashMap<String, T>,
+    name: &Value,
+) -> Result<&'a T, AccessRuleError> {
+    let name = name
+        .as_str()
+        .ok_or_else(|| syntax(&format!("{name} is not a name")))?;
+    definitions
+        .get(name)
+        .ok_or_else(|| AccessRuleError::UnknownDefinition(name.to_string()))
+}
+
+/// Keys in the text serialization, i.e. `(Submodel)urn:x, (Property)Power`.
+/// Parsed like the keys of a [`Reference`], so key values may contain commas.
+fn matches_value(rule: &str, value: &str) -> bool {
+    rule == "*" || rule == value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn environment() -> Environment {
+        let submodel = |id: &str, customer: &str| {
+            json!({
+                "modelType": "Submodel",
+                "id": id,
+                "submodelElements": [
+                    { "modelType": "Property", "idShort": "Customer", "valueType": "xs:string", "value": customer },
+                    { "modelType": "Property", "idShort": "Price", "valueType": "xs:decimal", "value": 120 }
+                ]
+            })
+        };
+
+        serde_json::from_value(json!({
+            "submodels": [
+                submodel("https://example.com/sm/offer/1", "ACME"),
+                submodel("https://example.com/sm/offer/2", "Globex"),
+                submodel("https://example.com/sm/offer/3", "ACME")
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn rules() -> AccessRules {
+        json!({ "AllAccessPermissionRules": {
+            "DEFACLS": [{
+                "name": "customer read",
+                "acl": {
+                    "ATTRIBUTES": [{ "CLAIM": "customer" }],
+                    "RIGHTS": ["READ"],
+                    "ACCESS": "ALLOW"
+                }
+            }],
+            "rules": [
+                {
+                    "USEACL": "customer read",
+                    "OBJECTS": [{ "IDENTIFIABLE": "(Submodel)*" }],
+                    "FORMULA": { "$eq": [
+                        { "$attribute": { "CLAIM": "customer" } },
+                        { "$field": "$sme.Customer#value" }
+                    ] }
+                },
+                {
+                    "ACL": { "ATTRIBUTES": [{ "CLAIM": "role" }], "RIGHTS": ["ALL"], "ACCESS": "ALLOW" },
+                    "OBJECTS": [{ "IDENTIFIABLE": "(Submodel)*" }, { "ROUTE": "/submodels/*" }],
+                    "FORMULA": { "$eq": [{ "$attribute": { "CLAIM": "role" } }, { "$strVal": "admin" }] }
+                },
+                {
+                    "ACL": { "RIGHTS": ["UPDATE"], "ACCESS": "DISABLED" },
+                    "OBJECTS": [{ "REFERABLE": "(Submodel)https://example.com/sm/offer/1, (Property)Price" }]
+                },
+                {
+                    "ACL": { "RIGHTS": ["READ"], "ACCESS": "ALLOW" },
+                    "OBJECTS": [{ "REFERABLE": "(Submodel)*, (Property)Price" }],
+                    "FORMULA": { "$eq": [
+                        { "$attribute": { "GLOBAL": "ANONYMOUS" } },
+                        { "$boolean": false }
+                    ] }
+                }
+            ]
+        } })
+        .to_string()
+        .parse()
+        .unwrap()
+    }
+
+    fn ids(submodels: Vec<&Submodel>) -> Vec<String> {
+        submodels
+            .iter()
+            .map(|submodel| submodel.id().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn per_customer_visibility() {
+        let environment = environment();
+        let rules = rules();
+        let acme = Subject::default().with_claim("customer", "ACME");
+        let admin = Subject::default().with_claim("role", "admin");
+
+        assert_eq!(
+            ids(rules.readable_submodels(&environment, &acme)),
+            [
+                "https://example.com/sm/offer/1",
+                "https://example.com/sm/offer/3"
+            ]
+        );
+        assert_eq!(
+            ids(rules.readable_submodels(
+                &environment,
+                &Subject::default().with_claim("customer", "Globex")
+            )),
+            ["https://example.com/sm/offer/2"]
+        );
+        assert_eq!(rules.readable_submodels(&environment, &admin).len(), 3);
+        assert!(
+            rules
+                .readable_submodels(&environment, &Subject::anonymous())
+                .is_empty()
+        );
+
+        let submodel = "https://example.com/sm/offer/1";
+        assert!(rules.is_allowed(&environment, &acme, Right::View, Target::Submodel(submodel)));
+        assert!(!rules.is_allowed(
+            &environment,
+            &acme,
+            Right::Update,
+            Target::Submodel(submodel)
+        ));
+        assert!(rules.is_allowed(
+            &environment,
+            &admin,
+            Right::Delete,
+            Target::Route("/submodels/abc")
+        ));
+        assert!(!rules.is_allowed(
+            &environment,
+            &acme,
+            Right::Read,
+            Target::Route("/submodels/abc")
+        ));
+    }
+
+    #[test]
+    fn referable_objects() {
+        let environment = environment();
+        let rules = rules();
+        let price = |submodel| Target::Element {
+            submodel,
+            id_short_path: "Price",
+        };
+        let globex = Subject::default().with_claim("customer", "Globex");
+
+        assert!(rules.is_allowed(
+            &environment,
+            &globex,
+            Right::Read,
+            price("https://example.com/sm/offer/1")
+        ));
+        assert!(!rules.is_allowed(
+            &environment,
+            &Subject::anonymous(),
+            Right::Read,
+            price("https://example.com/sm/offer/1")
+        ));
+        assert!(!rules.is_allowed(
+            &environment,
+            &globex,
+            Right::Update,
+            price("https://example.com/sm/offer/1")
+        ));
+        assert!(!rules.is_allowed(
+            &environment,
+            &globex,
+            Right::Read,
+            Target::Element {
+                submodel: "https://example.com/sm/offer/1",
+                id_short_path: "Customer"
+            }
+        ));
+    }
+
+    #[test]
+    fn keys_with_commas() {
+        assert_eq!(
+            parse_keys("(Submodel)urn:x:sm:a,b, (Property)Price"),
+            Some(vec![
+                Key::Submodel("urn:x:sm:a,b".to_string()),
+                Key::Property("Price".to_string())
+            ])
+        );
+        assert_eq!(parse_keys(""), None);
+        assert_eq!(parse_keys("Submodel"), None);
+    }
+
+    #[test]
+    fn unknown_definitions() {
+        let error = |json: Value| AccessRules::from_json(&json).unwrap_err();
+
+        assert_eq!(
+            error(json!({ "rules": [{ "USEACL": "missing", "OBJECTS": [] }] })),
+            AccessRuleError::UnknownDefinition("missing".to_string())
+        );
+        assert_eq!(
+            error(json!({ "rules": [{
+                "ACL": { "RIGHTS": ["READ"], "ACCESS": "ALLOW" },
+                "OBJECTS": [{ "FILE": "x" }]
+            }] })),
+            AccessRuleError::InvalidObject(r#"{"FILE":"x"}"#.to_string())
+        );
+        assert!(matches!(
+            error(json!({ "rules": [{
+                "ACL": { "RIGHTS": ["READ"], "ACCESS": "ALLOW" },
+                "OBJECTS": [],
+                "FORMULA": { "$attribute": { "CLAIM": "x" } }
+            }] })),
+            AccessRuleError::Formula(QueryError::UnknownOperator(_))
+        ));
+    }
+}
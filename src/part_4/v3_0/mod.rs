@@ -0,0 +1,2 @@
+mod access_rule;
+pub use access_rule::*;
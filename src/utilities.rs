@@ -44,3 +44,79 @@ where
 
     LanguageTag::parse_and_normalize(&buf).map_err(serde::de::Error::custom)
 }
+
+/// Parse a non-negative `xs:duration`, i.e. `PT30S` or `P1DT2H`.
+///
+/// There is no fixed length for years and months, a year is counted as 365 days and a month as
+/// 30 days. Negative durations are not supported and return `None`.
+pub fn parse_xs_duration(duration: &str) -> Option<std::time::Duration> {
+    let rest = duration.strip_prefix('P')?;
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+
+    let mut seconds = 0f64;
+    let mut components = 0;
+
+    let mut add = |part: &str, units: &[(char, f64)], fraction: char| -> Option<()> {
+        let mut rest = part;
+        let mut last = None;
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+            let (number, unit) = (&rest[..end], rest[end..].chars().next()?);
+
+            let position = units.iter().position(|(u, _)| *u == unit)?;
+            if last.is_some_and(|last| position <= last)
+                || number.is_empty()
+                || number.starts_with('.')
+                || number.ends_with('.')
+                || (number.contains('.') && unit != fraction)
+            {
+                return None;
+            }
+
+            seconds += number.parse::<f64>().ok()? * units[position].1;
+            components += 1;
+            last = Some(position);
+            rest = &rest[end + unit.len_utf8()..];
+        }
+        Some(())
+    };
+
+    const DAY: f64 = 86_400.0;
+    add(
+        date,
+        &[('Y', 365.0 * DAY), ('M', 30.0 * DAY), ('D', DAY)],
+        '\0',
+    )?;
+    if let Some(time) = time {
+        add(time, &[('H', 3_600.0), ('M', 60.0), ('S', 1.0)], 'S')?;
+    }
+
+    match components {
+        0 => None,
+        _ => std::time::Duration::try_from_secs_f64(seconds).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn xs_duration() {
+        assert_eq!(parse_xs_duration("PT30S"), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_xs_duration("P1DT2H3M4.5S"),
+            Some(Duration::from_secs_f64(93_784.5))
+        );
+        assert_eq!(parse_xs_duration("P"), None);
+        assert_eq!(parse_xs_duration("PT"), None);
+        assert_eq!(parse_xs_duration("P1H"), None);
+        assert_eq!(parse_xs_duration("PT2M1H"), None);
+        assert_eq!(parse_xs_duration("-PT1S"), None);
+    }
+}